@@ -12,14 +12,13 @@ with open("../.etc/blocks.json") as f:
     for block in blocks:
         data = blocks[block]
         for state in data["states"]:
+            block_id = state["id"]
+            out[block_id] = {"name": block}
             if "properties" in state:
-                props = state["properties"]
-                if "id" in state:
-                    block_id = state["id"]
-                    out[block_id] = {"name": block, "properties": props}
-            else:
-                block_id = state["id"]
-                out[block_id] = {"name": block, "default": True}
+                out[block_id]["properties"] = state["properties"]
+            # The report only marks the default state, and every block has exactly one
+            if state.get("default", False):
+                out[block_id]["default"] = True
 
 out = dict_reorder(out)
 
@@ -29,4 +28,4 @@ with open("../.etc/blockmappings.bz2", "wb") as f:
     as_string = json.dumps(out, separators=(',', ':'))
     print("Compressing and writing to file...")
     print("This may take a while.")
-    f.write(bz2.compress(as_string.encode("utf-8")))
\ No newline at end of file
+    f.write(bz2.compress(as_string.encode("utf-8")))
@@ -0,0 +1,14 @@
+import bz2
+import json
+
+# Reads the registries report from the vanilla data generator and writes the item names by ID,
+# compressed the same way as the block mappings.
+with open("../.etc/registries.json") as f:
+    entries = json.load(f)["minecraft:item"]["entries"]
+
+out = {str(entry["protocol_id"]): name for name, entry in entries.items()}
+out = dict(sorted(out.items(), key=lambda item: int(item[0])))
+
+with open("../.etc/itemmappings.bz2", "wb") as f:
+    as_string = json.dumps(out, separators=(',', ':'))
+    f.write(bz2.compress(as_string.encode("utf-8")))
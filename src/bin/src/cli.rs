@@ -16,6 +16,8 @@ pub enum Command {
     Setup,
     /// Import the world data
     Import(ImportArgs),
+    /// Export the world to vanilla region files
+    Export(ExportArgs),
     /// Start the server
     Run,
 }
@@ -29,6 +31,15 @@ pub struct ImportArgs {
     pub import_path: String,
 }
 
+#[derive(Debug, Clone, Parser)]
+pub struct ExportArgs {
+    /// Path to the folder to export the world to
+    ///
+    /// Each dimension's region files are written to the folder vanilla keeps them in, such as `region` and `DIM-1/region`. Region files already there are replaced.
+    #[clap(long, required = true)]
+    pub output: String,
+}
+
 // Wrapper struct for the Level enum
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct LogLevel(Level);
@@ -0,0 +1,36 @@
+use ferrumc_commands::arguments::ArgumentParser;
+use ferrumc_commands::context::CommandContext;
+use ferrumc_commands::nodes::{argument, literal, CommandNode};
+use ferrumc_commands::sender::CommandSender;
+use ferrumc_commands::CommandResult;
+use ferrumc_core::dimensions::dimension::Dimension;
+use ferrumc_macros::command;
+use ferrumc_net::utils::dimension::change_dimension;
+
+/// `/dimension <dimension> <targets>`: sends players to the spawn of another dimension.
+/// Console only, until there are permissions.
+#[command]
+fn dimension() -> CommandNode {
+    Dimension::ALL.into_iter().fold(
+        literal("dimension").requires(|sender, _| sender.is_console()),
+        |node, dimension| {
+            node.then(
+                literal(dimension.name()).then(
+                    argument("targets", ArgumentParser::players()).executes(
+                        move |ctx| async move { send_to_dimension(ctx, dimension).await },
+                    ),
+                ),
+            )
+        },
+    )
+}
+
+async fn send_to_dimension(ctx: CommandContext, dimension: Dimension) -> CommandResult {
+    for &target in ctx.get_entities("targets")? {
+        change_dimension(&ctx.state, target, dimension).await?;
+
+        let name = CommandSender::Player(target).name(&ctx.state.universe);
+        ctx.reply(format!("Sent {} to {}", name, dimension)).await?;
+    }
+    Ok(())
+}
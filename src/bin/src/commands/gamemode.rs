@@ -0,0 +1,48 @@
+use ferrumc_commands::arguments::ArgumentParser;
+use ferrumc_commands::context::CommandContext;
+use ferrumc_commands::nodes::{argument, literal, CommandNode};
+use ferrumc_commands::sender::CommandSender;
+use ferrumc_commands::CommandResult;
+use ferrumc_core::gamemode::game_mode::GameMode;
+use ferrumc_macros::command;
+use ferrumc_net::utils::chat::send_message;
+use ferrumc_net::utils::game_mode::set_game_mode;
+use ferrumc_text::ComponentBuilder;
+
+/// `/gamemode <gamemode> <targets>`: changes players' game modes. Console only, until there
+/// are permissions.
+#[command]
+fn gamemode() -> CommandNode {
+    GameMode::ALL.into_iter().fold(
+        literal("gamemode").requires(|sender, _| sender.is_console()),
+        |node, game_mode| {
+            node.then(
+                literal(game_mode.name()).then(
+                    argument("targets", ArgumentParser::players()).executes(
+                        move |ctx| async move { change_game_modes(ctx, game_mode).await },
+                    ),
+                ),
+            )
+        },
+    )
+}
+
+async fn change_game_modes(ctx: CommandContext, game_mode: GameMode) -> CommandResult {
+    for &target in ctx.get_entities("targets")? {
+        set_game_mode(&ctx.state, target, game_mode).await?;
+
+        let changed = ComponentBuilder::translate(
+            "gameMode.changed",
+            vec![ComponentBuilder::translate(
+                format!("gameMode.{}", game_mode.name()),
+                vec![],
+            )],
+        );
+        send_message(&ctx.state, target, changed).await?;
+
+        let name = CommandSender::Player(target).name(&ctx.state.universe);
+        ctx.reply(format!("Set {}'s game mode to {}", name, game_mode))
+            .await?;
+    }
+    Ok(())
+}
@@ -0,0 +1,38 @@
+use ferrumc_commands::arguments::ArgumentParser;
+use ferrumc_commands::context::CommandContext;
+use ferrumc_commands::nodes::{argument, literal, CommandNode};
+use ferrumc_commands::sender::CommandSender;
+use ferrumc_commands::CommandResult;
+use ferrumc_macros::command;
+use ferrumc_net::utils::state::terminate_connection;
+
+const DEFAULT_REASON: &str = "Kicked by an operator";
+
+/// `/kick <targets> [<reason>]`: disconnects players. Console only, until there are
+/// permissions.
+#[command]
+fn kick() -> CommandNode {
+    literal("kick")
+        .requires(|sender, _| sender.is_console())
+        .then(
+            argument("targets", ArgumentParser::players())
+                .executes(|ctx| async move { kick_targets(ctx, DEFAULT_REASON).await })
+                .then(
+                    argument("reason", ArgumentParser::greedy_string()).executes(
+                        |ctx| async move {
+                            let reason = ctx.get_string("reason")?.to_string();
+                            kick_targets(ctx, &reason).await
+                        },
+                    ),
+                ),
+        )
+}
+
+async fn kick_targets(ctx: CommandContext, reason: &str) -> CommandResult {
+    for &target in ctx.get_entities("targets")? {
+        let name = CommandSender::Player(target).name(&ctx.state.universe);
+        terminate_connection(ctx.state.clone(), target, reason.to_string()).await?;
+        ctx.reply(format!("Kicked {}: {}", name, reason)).await?;
+    }
+    Ok(())
+}
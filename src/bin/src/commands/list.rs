@@ -0,0 +1,28 @@
+use ferrumc_commands::nodes::{literal, CommandNode};
+use ferrumc_config::statics::get_global_config;
+use ferrumc_core::identity::player_identity::PlayerIdentity;
+use ferrumc_macros::command;
+use ferrumc_net::connection::ConnectionState;
+
+/// `/list`: lists the players online.
+#[command]
+fn list() -> CommandNode {
+    literal("list").executes(|ctx| async move {
+        let mut names = ctx
+            .state
+            .universe
+            .query::<(&PlayerIdentity, &ConnectionState)>()
+            .filter(|(_, (_, conn_state))| matches!(**conn_state, ConnectionState::Play))
+            .map(|(_, (identity, _))| identity.username.clone())
+            .collect::<Vec<_>>();
+        names.sort();
+
+        ctx.reply(format!(
+            "There are {} of a max of {} players online: {}",
+            names.len(),
+            get_global_config().max_players,
+            names.join(", ")
+        ))
+        .await
+    })
+}
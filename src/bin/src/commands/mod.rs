@@ -0,0 +1,12 @@
+//! Commands that come with the server.
+
+mod dimension;
+mod gamemode;
+mod kick;
+mod list;
+mod msg;
+mod mspt;
+mod say;
+mod stop;
+mod time;
+mod tps;
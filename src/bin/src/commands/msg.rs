@@ -0,0 +1,38 @@
+use ferrumc_commands::arguments::ArgumentParser;
+use ferrumc_commands::nodes::{argument, literal, CommandNode};
+use ferrumc_commands::sender::CommandSender;
+use ferrumc_macros::command;
+use ferrumc_net::utils::chat::send_message;
+use ferrumc_text::{ComponentBuilder, NamedColor, TextComponent};
+
+/// `/msg <targets> <message>`: sends a private message.
+#[command]
+fn msg() -> CommandNode {
+    literal("msg").then(argument("targets", ArgumentParser::players()).then(
+        argument("message", ArgumentParser::greedy_string()).executes(|ctx| async move {
+            let message = ctx.get_string("message")?;
+            let sender_name = ctx.sender.name(&ctx.state.universe);
+
+            for &target in ctx.get_entities("targets")? {
+                let target_name = CommandSender::Player(target).name(&ctx.state.universe);
+
+                let incoming = whisper(
+                    "commands.message.display.incoming",
+                    sender_name.clone(),
+                    message,
+                );
+                send_message(&ctx.state, target, incoming).await?;
+
+                let outgoing = whisper("commands.message.display.outgoing", target_name, message);
+                ctx.reply(outgoing).await?;
+            }
+            Ok(())
+        }),
+    ))
+}
+
+fn whisper(key: &str, name: String, message: &str) -> TextComponent {
+    ComponentBuilder::translate(key, vec![name.into(), message.into()])
+        .color(NamedColor::Gray)
+        .italic()
+}
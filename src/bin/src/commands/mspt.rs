@@ -0,0 +1,20 @@
+use ferrumc_commands::nodes::{literal, CommandNode};
+use ferrumc_macros::command;
+
+/// `/mspt`: shows how long the latest ticks took to run.
+#[command]
+fn mspt() -> CommandNode {
+    literal("mspt").executes(|ctx| async move {
+        let mspt = ctx.state.ticks.mspt();
+        ctx.reply(format!(
+            "Tick times (ms): mean {:.2}, p50 {:.2}, p95 {:.2}, p99 {:.2}, max {:.2} (budget {:.2})",
+            mspt.mean,
+            mspt.p50,
+            mspt.p95,
+            mspt.p99,
+            mspt.max,
+            ctx.state.ticks.tick_period().as_secs_f64() * 1000.0
+        ))
+        .await
+    })
+}
@@ -0,0 +1,25 @@
+use ferrumc_commands::arguments::ArgumentParser;
+use ferrumc_commands::nodes::{argument, literal, CommandNode};
+use ferrumc_macros::command;
+use ferrumc_net::utils::chat::broadcast_message;
+use ferrumc_text::ComponentBuilder;
+use tracing::info;
+
+/// `/say <message>`: announces a message to every player.
+#[command]
+fn say() -> CommandNode {
+    literal("say").then(
+        argument("message", ArgumentParser::greedy_string()).executes(|ctx| async move {
+            let message = ctx.get_string("message")?;
+            let name = ctx.sender.name(&ctx.state.universe);
+            info!("[{}] {}", name, message);
+
+            let announcement = ComponentBuilder::translate(
+                "chat.type.announcement",
+                vec![name.into(), message.into()],
+            );
+            broadcast_message(&ctx.state, announcement).await?;
+            Ok(())
+        }),
+    )
+}
@@ -0,0 +1,14 @@
+use ferrumc_commands::nodes::{literal, CommandNode};
+use ferrumc_macros::command;
+
+/// `/stop`: shuts the server down. Console only, until there are permissions.
+#[command]
+fn stop() -> CommandNode {
+    literal("stop")
+        .requires(|sender, _| sender.is_console())
+        .executes(|ctx| async move {
+            ctx.reply("Stopping the server...").await?;
+            ctx.state.shutdown.cancel();
+            Ok(())
+        })
+}
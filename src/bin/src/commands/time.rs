@@ -0,0 +1,73 @@
+use ferrumc_commands::arguments::ArgumentParser;
+use ferrumc_commands::context::CommandContext;
+use ferrumc_commands::nodes::{argument, literal, CommandNode};
+use ferrumc_commands::CommandResult;
+use ferrumc_macros::command;
+use ferrumc_net::utils::time::broadcast_time;
+use ferrumc_world::time::{WorldTime, DAY_LENGTH};
+
+/// Times of day that can be set by name.
+const NAMED_TIMES: [(&str, i64); 4] = [
+    ("day", 1000),
+    ("noon", 6000),
+    ("night", 13000),
+    ("midnight", 18000),
+];
+
+/// `/time set|add <time>` and `/time query daytime|gametime|day`: changes or shows the world's
+/// time. Console only, until there are permissions.
+#[command]
+fn time() -> CommandNode {
+    let set = NAMED_TIMES.into_iter().fold(
+        literal("set").then(
+            argument("time", ArgumentParser::integer_range(0, i32::MAX)).executes(
+                |ctx| async move {
+                    let time = ctx.get_integer("time")? as i64;
+                    set_time(ctx, time).await
+                },
+            ),
+        ),
+        |set, (name, time)| {
+            set.then(literal(name).executes(move |ctx| async move { set_time(ctx, time).await }))
+        },
+    );
+    let add = literal("add").then(
+        argument("time", ArgumentParser::integer_range(0, i32::MAX)).executes(|ctx| async move {
+            let ticks = ctx.get_integer("time")? as i64;
+            let time = ctx.state.world.add_time(ticks);
+            changed(ctx, time).await
+        }),
+    );
+    let query = literal("query")
+        .then(literal("daytime").executes(|ctx| async move {
+            let time = ctx.state.world.time().day_time();
+            ctx.reply(format!("The time is {}", time)).await
+        }))
+        .then(literal("gametime").executes(|ctx| async move {
+            let time = ctx.state.world.time().world_age;
+            ctx.reply(format!("The time is {}", time)).await
+        }))
+        .then(literal("day").executes(|ctx| async move {
+            let day = ctx.state.world.time().day();
+            ctx.reply(format!("The time is {}", day)).await
+        }));
+
+    literal("time")
+        .requires(|sender, _| sender.is_console())
+        .then(set)
+        .then(add)
+        .then(query)
+}
+
+/// Sets the time of day, keeping the day it is.
+async fn set_time(ctx: CommandContext, day_time: i64) -> CommandResult {
+    let day = ctx.state.world.time().day();
+    let time = ctx.state.world.set_time_of_day(day * DAY_LENGTH + day_time);
+    changed(ctx, time).await
+}
+
+async fn changed(ctx: CommandContext, time: WorldTime) -> CommandResult {
+    broadcast_time(&ctx.state).await?;
+    ctx.reply(format!("Set the time to {}", time.day_time()))
+        .await
+}
@@ -0,0 +1,18 @@
+use ferrumc_commands::nodes::{literal, CommandNode};
+use ferrumc_macros::command;
+
+/// `/tps`: shows how many ticks per second the server has been running.
+#[command]
+fn tps() -> CommandNode {
+    literal("tps").executes(|ctx| async move {
+        let [one, five, fifteen] = ctx.state.ticks.tps();
+        ctx.reply(format!(
+            "TPS from the last 1m, 5m, 15m: {:.2}, {:.2}, {:.2} (target {})",
+            one,
+            five,
+            fifteen,
+            ctx.state.ticks.tick_rate()
+        ))
+        .await
+    })
+}
@@ -5,19 +5,27 @@ extern crate core;
 use crate::errors::BinaryError;
 use clap::Parser;
 use ferrumc_config::statics::get_global_config;
+use ferrumc_core::gamemode::game_mode::GameMode;
 use ferrumc_ecs::Universe;
 use ferrumc_general_purpose::paths::get_root_path;
 use ferrumc_net::server::create_server_listener;
+use ferrumc_net_encryption::session::MojangSessionVerifier;
+use ferrumc_state::ticks::TickStats;
 use ferrumc_state::ServerState;
+use ferrumc_world::items::ItemRegistry;
 use ferrumc_world::World;
+use std::path::PathBuf;
 use std::sync::Arc;
 use systems::definition;
+use tokio_util::sync::CancellationToken;
 use tracing::{error, info};
 
 pub(crate) mod errors;
-use crate::cli::{CLIArgs, Command, ImportArgs};
+use crate::cli::{CLIArgs, Command, ExportArgs, ImportArgs};
 mod cli;
+mod commands;
 mod packet_handlers;
+mod shutdown;
 mod systems;
 
 pub type Result<T> = std::result::Result<T, BinaryError>;
@@ -45,6 +53,14 @@ async fn main() {
                 info!("Import completed successfully.");
             }
         }
+        Some(Command::Export(export_args)) => {
+            info!("Starting export...");
+            if let Err(e) = handle_export(export_args).await {
+                error!("Export failed with the following error: {}", e.to_string());
+            } else {
+                info!("Export completed successfully.");
+            }
+        }
         Some(Command::Run) | None => {
             info!("Starting server...");
             if let Err(e) = entry().await {
@@ -60,15 +76,16 @@ async fn entry() -> Result<()> {
     let state = create_state().await?;
     let global_state = Arc::new(state);
 
+    tokio::spawn(shutdown::shutdown_on_signal(global_state.clone()));
     let all_system_handles = tokio::spawn(definition::start_all_systems(global_state.clone()));
 
-    // Start the systems and wait until all of them are done
-    all_system_handles.await??;
-
-    // Stop all systems
-    definition::stop_all_systems(global_state).await?;
+    // Run until all the systems are done, or the server is told to stop
+    tokio::select! {
+        result = all_system_handles => result??,
+        _ = global_state.shutdown.cancelled() => {}
+    }
 
-    Ok(())
+    shutdown::shutdown(global_state).await
 }
 
 async fn handle_import(import_args: ImportArgs) -> Result<()> {
@@ -98,12 +115,44 @@ async fn handle_import(import_args: ImportArgs) -> Result<()> {
     Ok(())
 }
 
+async fn handle_export(export_args: ExportArgs) -> Result<()> {
+    //! Handles the export of the world.
+    info!("Exporting world...");
+
+    let world = World::new().await;
+
+    let mut output = PathBuf::from(export_args.output);
+    if output.is_relative() {
+        output = get_root_path().join(output);
+    }
+
+    if let Err(e) = world.export(output).await {
+        error!("Could not export world: {}", e.to_string());
+        return Err(BinaryError::Custom("Could not export world.".to_string()));
+    }
+
+    Ok(())
+}
+
 async fn create_state() -> Result<ServerState> {
+    let tick_rate = get_global_config().tick_rate;
+    if tick_rate == 0 {
+        return Err(BinaryError::Custom(
+            "The tick rate must be at least 1".to_string(),
+        ));
+    }
+    // Checked here so a typo stops the server instead of being warned about on every join
+    get_global_config().default_gamemode.parse::<GameMode>()?;
     let listener = create_server_listener().await?;
+    // Load the items now so a missing registries report is reported at startup
+    ItemRegistry::global();
 
     Ok(ServerState {
         universe: Universe::new(),
         tcp_listener: listener,
         world: World::new().await,
+        session_verifier: Arc::new(MojangSessionVerifier::new()),
+        shutdown: CancellationToken::new(),
+        ticks: TickStats::new(tick_rate),
     })
 }
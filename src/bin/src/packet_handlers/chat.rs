@@ -0,0 +1,27 @@
+use ferrumc_core::identity::player_identity::PlayerIdentity;
+use ferrumc_macros::event_handler;
+use ferrumc_net::errors::NetError;
+use ferrumc_net::packets::incoming::chat_message::ChatEvent;
+use ferrumc_net::utils::chat::broadcast_player_message;
+use ferrumc_state::GlobalState;
+use tracing::info;
+
+/// Broadcasts chat messages. Runs last, so other listeners get the chance to change or cancel
+/// the message first.
+#[event_handler(priority = "slowest")]
+async fn handle_chat(event: ChatEvent, state: GlobalState) -> Result<ChatEvent, NetError> {
+    if event.cancelled {
+        return Ok(event);
+    }
+
+    let username = state
+        .universe
+        .get::<PlayerIdentity>(event.conn_id)?
+        .username
+        .clone();
+    info!("<{}> {}", username, event.message);
+
+    broadcast_player_message(&state, event.conn_id, event.message.clone()).await?;
+
+    Ok(event)
+}
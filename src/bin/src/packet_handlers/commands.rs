@@ -0,0 +1,71 @@
+use ferrumc_commands::infrastructure::dispatcher;
+use ferrumc_commands::sender::CommandSender;
+use ferrumc_macros::event_handler;
+use ferrumc_net::connection::StreamWriter;
+use ferrumc_net::errors::NetError;
+use ferrumc_net::packets::incoming::chat_command::ChatCommandEvent;
+use ferrumc_net::packets::incoming::command_suggestion_request::CommandSuggestionRequestEvent;
+use ferrumc_net::packets::outgoing::command_suggestions_response::{
+    CommandSuggestionsResponsePacket, SuggestionMatch,
+};
+use ferrumc_net::utils::chat::send_message;
+use ferrumc_net_codec::encode::NetEncodeOpts;
+use ferrumc_state::GlobalState;
+use ferrumc_text::{NamedColor, TextComponent};
+use tracing::debug;
+
+#[event_handler]
+async fn handle_chat_command(
+    event: ChatCommandEvent,
+    state: GlobalState,
+) -> Result<ChatCommandEvent, NetError> {
+    debug!("Entity {} ran command: /{}", event.conn_id, event.command);
+
+    if let Err(e) = dispatcher()
+        .execute(
+            &event.command,
+            CommandSender::Player(event.conn_id),
+            state.clone(),
+        )
+        .await
+    {
+        let message = TextComponent::from(e.to_string()).color(NamedColor::Red);
+        send_message(&state, event.conn_id, message).await?;
+    }
+
+    Ok(event)
+}
+
+#[event_handler]
+async fn handle_command_suggestion_request(
+    event: CommandSuggestionRequestEvent,
+    state: GlobalState,
+) -> Result<CommandSuggestionRequestEvent, NetError> {
+    let packet = &event.command_suggestion_request_packet;
+    let input = packet.text.strip_prefix('/').unwrap_or(&packet.text);
+    // The client's positions include the slash
+    let offset = packet.text.len() - input.len();
+
+    let suggestions = dispatcher().suggest(
+        input,
+        &CommandSender::Player(event.conn_id),
+        &state.universe,
+    );
+    let response = CommandSuggestionsResponsePacket::new(
+        packet.transaction_id.clone(),
+        suggestions.start + offset,
+        suggestions.length,
+        suggestions
+            .matches
+            .into_iter()
+            .map(|text| SuggestionMatch::new(text, None))
+            .collect(),
+    );
+
+    let mut writer = state.universe.get_mut::<StreamWriter>(event.conn_id)?;
+    writer
+        .send_packet(&response, &NetEncodeOpts::WithLength)
+        .await?;
+
+    Ok(event)
+}
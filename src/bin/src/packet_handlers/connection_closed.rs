@@ -0,0 +1,30 @@
+use ferrumc_core::tracking::tracked_entities::TrackedEntities;
+use ferrumc_macros::event_handler;
+use ferrumc_net::errors::NetError;
+use ferrumc_net::packets::packet_events::ConnectionClosedEvent;
+use ferrumc_net::utils::entity_tracking::remove_player;
+use ferrumc_net::utils::player_data::save_player_data;
+use ferrumc_state::GlobalState;
+use tracing::warn;
+
+#[event_handler]
+async fn handle_connection_closed(
+    event: ConnectionClosedEvent,
+    state: GlobalState,
+) -> Result<ConnectionClosedEvent, NetError> {
+    // Only players that made it into the game are known to anyone else
+    if state
+        .universe
+        .get::<TrackedEntities>(event.conn_id)
+        .is_err()
+    {
+        return Ok(event);
+    }
+
+    if let Err(e) = save_player_data(&state, event.conn_id).await {
+        warn!("Could not save data for entity {}: {}", event.conn_id, e);
+    }
+    remove_player(&state, event.conn_id).await?;
+
+    Ok(event)
+}
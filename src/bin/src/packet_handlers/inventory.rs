@@ -0,0 +1,126 @@
+use ferrumc_core::gamemode::game_mode::GameMode;
+use ferrumc_core::items::inventory::{Inventory, PLAYER_INVENTORY_SIZE};
+use ferrumc_macros::event_handler;
+use ferrumc_net::errors::NetError;
+use ferrumc_net::packets::incoming::click_container::ClickContainerEvent;
+use ferrumc_net::packets::incoming::set_creative_mode_slot::SetCreativeModeSlotEvent;
+use ferrumc_net::packets::incoming::set_held_item::SetHeldItemEvent;
+use ferrumc_net::utils::ecs_helpers::EntityExt;
+use ferrumc_net::utils::inventory::{send_inventory, send_inventory_slot};
+use ferrumc_state::GlobalState;
+use tracing::debug;
+
+/// A slot number from the client, if it's a slot of the player inventory window.
+fn inventory_slot(slot: i16) -> Option<usize> {
+    usize::try_from(slot)
+        .ok()
+        .filter(|&slot| slot < PLAYER_INVENTORY_SIZE)
+}
+
+#[event_handler]
+async fn handle_set_held_item(
+    event: SetHeldItemEvent,
+    state: GlobalState,
+) -> Result<SetHeldItemEvent, NetError> {
+    let selected = match usize::try_from(event.slot) {
+        Ok(slot) => event
+            .conn_id
+            .get_mut::<Inventory>(&state)?
+            .select(slot)
+            .is_ok(),
+        Err(_) => false,
+    };
+
+    if !selected {
+        debug!(
+            "Entity {} selected an invalid hotbar slot {}",
+            event.conn_id, event.slot
+        );
+    }
+
+    Ok(event)
+}
+
+/// Applies the result of a click that the client worked out, as long as it was made on the
+/// contents we last sent and the player isn't a spectator. The clicks themselves aren't simulated
+/// yet, so outside of creative mode a result is only applied if it moves items around without
+/// making or losing any.
+#[event_handler]
+async fn handle_click_container(
+    event: ClickContainerEvent,
+    state: GlobalState,
+) -> Result<ClickContainerEvent, NetError> {
+    let conn_id = event.conn_id;
+    let packet = &event.packet;
+    // The player's own inventory is the only window so far
+    if packet.window_id != 0 {
+        return Ok(event);
+    }
+
+    let game_mode = *conn_id.get::<GameMode>(&state)?;
+    let applied = {
+        let mut inventory = conn_id.get_mut::<Inventory>(&state)?;
+        let changed_slots = packet.result.as_ref().and_then(|result| {
+            result
+                .changed_slots
+                .iter()
+                .map(|(slot, stack)| inventory_slot(*slot).map(|slot| (slot, stack.0.clone())))
+                .collect::<Option<Vec<_>>>()
+        });
+        match (&packet.result, changed_slots) {
+            (Some(result), Some(changed_slots))
+                if game_mode.can_use_inventory()
+                    && packet.state_id.val == inventory.state_id()
+                    && (game_mode.can_spawn_items()
+                        || inventory
+                            .keeps_items(&changed_slots, result.carried_item.0.as_ref())) =>
+            {
+                for (slot, stack) in changed_slots {
+                    inventory.set(slot, stack)?;
+                }
+                inventory.carried = result.carried_item.0.clone();
+                true
+            }
+            _ => false,
+        }
+    };
+
+    if !applied {
+        debug!("Undoing a click from entity {}", conn_id);
+        send_inventory(&state, conn_id).await?;
+    }
+
+    Ok(event)
+}
+
+#[event_handler]
+async fn handle_set_creative_mode_slot(
+    event: SetCreativeModeSlotEvent,
+    state: GlobalState,
+) -> Result<SetCreativeModeSlotEvent, NetError> {
+    let conn_id = event.conn_id;
+    // Dropping items isn't supported, since there are no item entities yet
+    let Some(slot) = inventory_slot(event.packet.slot) else {
+        return Ok(event);
+    };
+
+    if !conn_id.get::<GameMode>(&state)?.can_spawn_items() {
+        debug!("Entity {} isn't in creative mode", conn_id);
+        send_inventory_slot(&state, conn_id, slot).await?;
+        return Ok(event);
+    }
+
+    match &event.packet.clicked_item {
+        Some(stack) => {
+            conn_id
+                .get_mut::<Inventory>(&state)?
+                .set(slot, stack.0.clone())?;
+        }
+        None => {
+            debug!("Entity {} set a slot to an unsupported item", conn_id);
+            send_inventory_slot(&state, conn_id, slot).await?;
+        }
+    }
+
+    Ok(event)
+}
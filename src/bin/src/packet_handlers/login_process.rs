@@ -1,30 +1,54 @@
-use ferrumc_core::identity::player_identity::PlayerIdentity;
-use ferrumc_core::transform::grounded::OnGround;
+use ferrumc_commands::infrastructure::dispatcher;
+use ferrumc_commands::sender::CommandSender;
+use ferrumc_config::statics::get_global_config;
+use ferrumc_core::chunks::loaded_chunks::LoadedChunks;
+use ferrumc_core::dimensions::dimension::Dimension;
+use ferrumc_core::gamemode::digging::DiggingTracker;
+use ferrumc_core::gamemode::game_mode::GameMode;
+use ferrumc_core::identity::player_identity::{PlayerIdentity, PlayerProperty};
+use ferrumc_core::items::inventory::Inventory;
+use ferrumc_core::tracking::tracked_entities::TrackedEntities;
 use ferrumc_core::transform::position::Position;
-use ferrumc_core::transform::rotation::Rotation;
+use ferrumc_core::transform::teleport::TeleportTracker;
 use ferrumc_ecs::components::storage::ComponentRefMut;
 use ferrumc_macros::event_handler;
-use ferrumc_net::connection::{ConnectionState, StreamWriter};
+use ferrumc_net::connection::{CompressionStatus, ConnectionState, EncryptionStatus, StreamWriter};
 use ferrumc_net::errors::NetError;
 use ferrumc_net::packets::incoming::ack_finish_configuration::AckFinishConfigurationEvent;
+use ferrumc_net::packets::incoming::encryption_response::EncryptionResponseEvent;
 use ferrumc_net::packets::incoming::keep_alive::IncomingKeepAlivePacket;
 use ferrumc_net::packets::incoming::login_acknowledged::LoginAcknowledgedEvent;
 use ferrumc_net::packets::incoming::login_start::LoginStartEvent;
 use ferrumc_net::packets::incoming::server_bound_known_packs::ServerBoundKnownPacksEvent;
+use ferrumc_net::packets::outgoing::change_difficulty::ChangeDifficultyPacket;
 use ferrumc_net::packets::outgoing::client_bound_known_packs::ClientBoundKnownPacksPacket;
+use ferrumc_net::packets::outgoing::encryption_request::EncryptionRequestPacket;
 use ferrumc_net::packets::outgoing::finish_configuration::FinishConfigurationPacket;
 use ferrumc_net::packets::outgoing::game_event::GameEventPacket;
+use ferrumc_net::packets::outgoing::initialize_world_border::InitializeWorldBorderPacket;
 use ferrumc_net::packets::outgoing::keep_alive::OutgoingKeepAlivePacket;
 use ferrumc_net::packets::outgoing::login_play::LoginPlayPacket;
 use ferrumc_net::packets::outgoing::login_success::LoginSuccessPacket;
 use ferrumc_net::packets::outgoing::registry_data::get_registry_packets;
 use ferrumc_net::packets::outgoing::set_center_chunk::SetCenterChunk;
+use ferrumc_net::packets::outgoing::set_compression::SetCompressionPacket;
 use ferrumc_net::packets::outgoing::set_default_spawn_position::SetDefaultSpawnPositionPacket;
+use ferrumc_net::packets::outgoing::set_held_item::SetHeldItemPacket;
 use ferrumc_net::packets::outgoing::set_render_distance::SetRenderDistance;
-use ferrumc_net::packets::outgoing::synchronize_player_position::SynchronizePlayerPositionPacket;
+use ferrumc_net::utils::entity_tracking::{add_to_player_list, chunk_of};
+use ferrumc_net::utils::game_mode::send_abilities;
+use ferrumc_net::utils::inventory::send_inventory;
+use ferrumc_net::utils::movement::resync_position;
+use ferrumc_net::utils::player_data::load_player_data;
+use ferrumc_net::utils::state::terminate_connection;
+use ferrumc_net::utils::time::send_time;
 use ferrumc_net_codec::encode::NetEncodeOpts;
+use ferrumc_net_encryption::digest::minecraft_digest;
+use ferrumc_net_encryption::errors::NetEncryptionError;
+use ferrumc_net_encryption::keys::{generate_verify_token, get_server_keys};
+use ferrumc_net_encryption::session::ProfileProperty;
 use ferrumc_state::GlobalState;
-use tracing::{debug, trace};
+use tracing::{debug, trace, warn};
 
 #[event_handler]
 async fn handle_login_start(
@@ -43,19 +67,165 @@ async fn handle_login_start(
         PlayerIdentity::new(username.to_string(), uuid),
     )?;
 
-    //Send a Login Success Response to further the login sequence
-    let mut writer = state
+    if get_global_config().online_mode {
+        // Start the encryption handshake, login continues in `handle_encryption_response`.
+        let verify_token = generate_verify_token();
+        state
+            .universe
+            .get_mut::<EncryptionStatus>(login_start_event.conn_id)?
+            .verify_token = Some(verify_token);
+
+        let mut writer = state
+            .universe
+            .get_mut::<StreamWriter>(login_start_event.conn_id)?;
+
+        writer
+            .send_packet(
+                &EncryptionRequestPacket::new(
+                    get_server_keys().public_key_der(),
+                    &verify_token,
+                    true,
+                ),
+                &NetEncodeOpts::WithLength,
+            )
+            .await?;
+
+        return Ok(login_start_event);
+    }
+
+    send_login_success(login_start_event.conn_id, uuid, username, &[], &state).await?;
+
+    Ok(login_start_event)
+}
+
+#[event_handler]
+async fn handle_encryption_response(
+    encryption_response_event: EncryptionResponseEvent,
+    state: GlobalState,
+) -> Result<EncryptionResponseEvent, NetError> {
+    trace!("Handling Encryption Response event");
+
+    let conn_id = encryption_response_event.conn_id;
+    let packet = &encryption_response_event.encryption_response_packet;
+    let keys = get_server_keys();
+
+    let shared_secret = keys.decrypt(&packet.shared_secret.data)?;
+    let shared_secret: [u8; 16] = shared_secret
+        .as_slice()
+        .try_into()
+        .map_err(|_| NetEncryptionError::InvalidSharedSecretLength(shared_secret.len()))?;
+    let verify_token = keys.decrypt(&packet.verify_token.data)?;
+
+    {
+        let mut encryption_status = state.universe.get_mut::<EncryptionStatus>(conn_id)?;
+        let expected_token = encryption_status.verify_token.take();
+        if expected_token.is_none_or(|token| token[..] != verify_token[..]) {
+            return Err(NetEncryptionError::VerifyTokenMismatch.into());
+        }
+
+        // The client encrypts everything from here on, and so must we.
+        // The connection loop picks this up for the read half.
+        encryption_status.shared_secret = Some(shared_secret);
+        state
+            .universe
+            .get_mut::<StreamWriter>(conn_id)?
+            .enable_encryption(&shared_secret)?;
+    }
+
+    let username = state
         .universe
-        .get_mut::<StreamWriter>(login_start_event.conn_id)?;
+        .get::<PlayerIdentity>(conn_id)?
+        .username
+        .clone();
+    let server_hash = minecraft_digest("", &shared_secret, keys.public_key_der());
+
+    let profile = match state
+        .session_verifier
+        .has_joined(&username, &server_hash)
+        .await
+    {
+        Ok(profile) => profile,
+        Err(e) => {
+            warn!("Could not verify session for {}: {}", username, e);
+            terminate_connection(
+                state.clone(),
+                conn_id,
+                "Failed to verify username!".to_string(),
+            )
+            .await?;
+            return Ok(encryption_response_event);
+        }
+    };
+
+    debug!(
+        "Verified session for {} ({:x})",
+        profile.username, profile.uuid
+    );
+
+    // The session server is the source of truth for the player's identity.
+    {
+        let mut identity = state.universe.get_mut::<PlayerIdentity>(conn_id)?;
+        identity.uuid = profile.uuid;
+        identity.username.clone_from(&profile.username);
+        identity.properties = profile
+            .properties
+            .iter()
+            .map(|property| PlayerProperty {
+                name: property.name.clone(),
+                value: property.value.clone(),
+                signature: property.signature.clone(),
+            })
+            .collect();
+    }
+
+    send_login_success(
+        conn_id,
+        profile.uuid,
+        &profile.username,
+        &profile.properties,
+        &state,
+    )
+    .await?;
+
+    Ok(encryption_response_event)
+}
+
+/// Enables compression (unless disabled in the config) and sends a Login Success to further
+/// the login sequence.
+async fn send_login_success(
+    conn_id: usize,
+    uuid: u128,
+    username: &str,
+    properties: &[ProfileProperty],
+    state: &GlobalState,
+) -> Result<(), NetError> {
+    let mut writer = state.universe.get_mut::<StreamWriter>(conn_id)?;
+
+    let compression_threshold = get_global_config().network_compression_threshold;
+    if compression_threshold >= 0 {
+        writer
+            .send_packet(
+                &SetCompressionPacket::new(compression_threshold),
+                &NetEncodeOpts::WithLength,
+            )
+            .await?;
+
+        // Everything after Set Compression is compressed, in both directions.
+        writer.enable_compression(compression_threshold as usize);
+        state
+            .universe
+            .get_mut::<CompressionStatus>(conn_id)?
+            .enabled = true;
+    }
 
     writer
         .send_packet(
-            &LoginSuccessPacket::new(uuid, username),
+            &LoginSuccessPacket::with_properties(uuid, username, properties),
             &NetEncodeOpts::WithLength,
         )
         .await?;
 
-    Ok(login_start_event)
+    Ok(())
 }
 
 #[event_handler]
@@ -98,9 +268,7 @@ async fn handle_server_bound_known_packs(
         .get_mut::<StreamWriter>(server_bound_known_packs_event.conn_id)?;
 
     let registry_packets = get_registry_packets();
-    writer
-        .send_packet(&registry_packets, &NetEncodeOpts::None)
-        .await?;
+    writer.send_encoded_frames(registry_packets).await?;
 
     writer
         .send_packet(
@@ -127,26 +295,47 @@ async fn handle_ack_finish_configuration(
 
     // add components to the entity after the connection state has been set to play.
     // to avoid wasting resources on entities that are fetching stuff like server status etc.
+    load_player_data(&state, conn_id).await?;
     state
         .universe
-        .add_component::<Position>(conn_id, Position::default())?
-        .add_component::<Rotation>(conn_id, Rotation::default())?
-        .add_component::<OnGround>(conn_id, OnGround::default())?;
+        .add_component::<LoadedChunks>(conn_id, LoadedChunks::new())?
+        .add_component::<TrackedEntities>(conn_id, TrackedEntities::new())?
+        .add_component::<TeleportTracker>(conn_id, TeleportTracker::new())?
+        .add_component::<DiggingTracker>(conn_id, DiggingTracker::new())?;
+
+    let (center_x, center_z) = chunk_of(&*state.universe.get::<Position>(conn_id)?);
+    let game_mode = *state.universe.get::<GameMode>(conn_id)?;
+    let dimension = *state.universe.get::<Dimension>(conn_id)?;
+    let level = state.world.level_info();
 
     let mut writer = state.universe.get_mut::<StreamWriter>(conn_id)?;
 
     writer // 21
-        .send_packet(&LoginPlayPacket::new(conn_id), &NetEncodeOpts::WithLength)
+        .send_packet(
+            &LoginPlayPacket::new(conn_id, game_mode, dimension, level.hardcore),
+            &NetEncodeOpts::WithLength,
+        )
         .await?;
-    writer // 29
+    writer
         .send_packet(
-            &SynchronizePlayerPositionPacket::default(), // The coordinates here should be used for the center chunk.
+            &ChangeDifficultyPacket::from_level(&level),
             &NetEncodeOpts::WithLength,
         )
         .await?;
+    drop(writer);
+    send_abilities(&state, conn_id).await?;
+    // 29, the client's moves are ignored until it confirms this
+    resync_position(&state, conn_id).await?;
+    let mut writer = state.universe.get_mut::<StreamWriter>(conn_id)?;
     writer // 37
         .send_packet(
-            &SetDefaultSpawnPositionPacket::default(), // Player specific, aka. home, bed, where it would respawn.
+            &SetDefaultSpawnPositionPacket::from_spawn(&state.world.spawn()),
+            &NetEncodeOpts::WithLength,
+        )
+        .await?;
+    writer
+        .send_packet(
+            &InitializeWorldBorderPacket::new(&state.world.border()),
             &NetEncodeOpts::WithLength,
         )
         .await?;
@@ -158,17 +347,37 @@ async fn handle_ack_finish_configuration(
         .await?;
     writer // 41
         .send_packet(
-            &SetCenterChunk::new(0, 0), // TODO - Dependent on the player spawn position.
+            &SetCenterChunk::new(center_x, center_z),
             &NetEncodeOpts::WithLength,
         )
         .await?;
     writer // other
         .send_packet(
-            &SetRenderDistance::new(5), // TODO
+            &SetRenderDistance::new(get_global_config().max_view_distance),
             &NetEncodeOpts::WithLength,
         )
         .await?;
-    send_keep_alive(conn_id, state, &mut writer).await?;
+    writer
+        .send_packet(
+            &dispatcher().declare_commands_packet(&CommandSender::Player(conn_id), &state.universe),
+            &NetEncodeOpts::WithLength,
+        )
+        .await?;
+    let selected_slot = state.universe.get::<Inventory>(conn_id)?.selected_slot();
+    writer
+        .send_packet(
+            &SetHeldItemPacket::new(selected_slot as i8),
+            &NetEncodeOpts::WithLength,
+        )
+        .await?;
+    send_keep_alive(conn_id, state.clone(), &mut writer).await?;
+    drop(writer);
+
+    send_inventory(&state, conn_id).await?;
+    send_time(&state, conn_id).await?;
+
+    // Other players are spawned by the chunk sender once their chunks are loaded
+    add_to_player_list(&state, conn_id).await?;
 
     Ok(ack_finish_configuration_event)
 }
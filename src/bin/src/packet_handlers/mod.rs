@@ -1,4 +1,9 @@
+mod chat;
+mod commands;
+mod connection_closed;
 mod handshake;
+mod inventory;
 mod login_process;
+mod player_actions;
 mod tick_handler;
 mod transform;
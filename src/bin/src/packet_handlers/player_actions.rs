@@ -0,0 +1,353 @@
+use ferrumc_core::chunks::loaded_chunks::LoadedChunks;
+use ferrumc_core::dimensions::dimension::Dimension;
+use ferrumc_core::gamemode::digging::DiggingTracker;
+use ferrumc_core::gamemode::game_mode::GameMode;
+use ferrumc_core::items::inventory::{Inventory, HOTBAR_START, OFFHAND_SLOT};
+use ferrumc_core::transform::position::Position;
+use ferrumc_core::transform::rotation::Rotation;
+use ferrumc_macros::event_handler;
+use ferrumc_net::connection::StreamWriter;
+use ferrumc_net::errors::NetError;
+use ferrumc_net::packets::incoming::player_action::{PlayerActionEvent, PlayerActionStatus};
+use ferrumc_net::packets::incoming::swing_arm::SwingArmEvent;
+use ferrumc_net::packets::incoming::use_item_on::{Hand, UseItemOnEvent, UseItemOnPacket};
+use ferrumc_net::packets::outgoing::acknowledge_block_change::AcknowledgeBlockChangePacket;
+use ferrumc_net::packets::outgoing::block_update::BlockUpdatePacket;
+use ferrumc_net::packets::outgoing::entity_animation::EntityAnimationPacket;
+use ferrumc_net::packets::outgoing::update_light::UpdateLightPacket;
+use ferrumc_net::utils::broadcast::{BroadcastOptions, BroadcastToAll};
+use ferrumc_net_codec::encode::NetEncodeOpts;
+use ferrumc_net_codec::net_types::network_position::NetworkPosition;
+use ferrumc_net_codec::net_types::var_int::VarInt;
+use ferrumc_state::GlobalState;
+use ferrumc_world::block_id::BlockId;
+use ferrumc_world::block_state::{BlockRegistry, BlockState};
+use ferrumc_world::items::ItemRegistry;
+use ferrumc_world::placement::Placement;
+use tracing::debug;
+
+#[event_handler]
+async fn handle_player_action(
+    event: PlayerActionEvent,
+    state: GlobalState,
+) -> Result<PlayerActionEvent, NetError> {
+    let packet = &event.player_action_packet;
+    let location = &packet.location;
+    let game_mode = *state.universe.get::<GameMode>(event.conn_id)?;
+    let can_build = can_build_at(event.conn_id, location, &state)?;
+
+    match packet.status {
+        PlayerActionStatus::StartedDigging | PlayerActionStatus::FinishedDigging if !can_build => {
+            resync_block(event.conn_id, location, &state).await?;
+        }
+        // Blocks break as soon as digging starts in creative mode, or if they have no hardness.
+        // The client doesn't send a finished status for them.
+        PlayerActionStatus::StartedDigging
+            if game_mode.breaks_instantly()
+                || breaks_instantly(event.conn_id, location, &state).await? =>
+        {
+            set_block(event.conn_id, location, BlockId::AIR, &state).await?;
+        }
+        PlayerActionStatus::StartedDigging => {
+            state
+                .universe
+                .get_mut::<DiggingTracker>(event.conn_id)?
+                .start(location.x, location.y.into(), location.z);
+            // Dig speeds from tools aren't modelled, so a client that predicted breaking the
+            // block at once is told it's still there
+            resync_block(event.conn_id, location, &state).await?;
+        }
+        // Only blocks the player started digging can be finished
+        PlayerActionStatus::FinishedDigging => {
+            if state
+                .universe
+                .get_mut::<DiggingTracker>(event.conn_id)?
+                .finish(location.x, location.y.into(), location.z)
+            {
+                set_block(event.conn_id, location, BlockId::AIR, &state).await?;
+            } else {
+                resync_block(event.conn_id, location, &state).await?;
+            }
+        }
+        PlayerActionStatus::CancelledDigging => {
+            state
+                .universe
+                .get_mut::<DiggingTracker>(event.conn_id)?
+                .cancel();
+        }
+        // Item actions don't change blocks, so there's nothing to acknowledge.
+        _ => return Ok(event),
+    }
+
+    acknowledge(event.conn_id, &packet.sequence, &state).await?;
+
+    Ok(event)
+}
+
+#[event_handler]
+async fn handle_use_item_on(
+    event: UseItemOnEvent,
+    state: GlobalState,
+) -> Result<UseItemOnEvent, NetError> {
+    let packet = &event.use_item_on_packet;
+
+    let (dx, dy, dz) = packet.face.offset();
+    let target = NetworkPosition::new(
+        packet.location.x + dx,
+        packet.location.y + dy as i16,
+        packet.location.z + dz,
+    );
+
+    let dimension = *state.universe.get::<Dimension>(event.conn_id)?;
+    let replaced = state
+        .world
+        .get_block_at(target.x, target.y.into(), target.z, dimension.name())
+        .await
+        .ok()
+        .and_then(BlockId::state);
+    let replaceable = replaced.is_some_and(BlockState::is_replaceable);
+    let in_water =
+        replaced.is_some_and(|block| block.is("water") && block.property("level") == Some("0"));
+
+    let can_build = can_build_at(event.conn_id, &target, &state)?;
+    match held_block(event.conn_id, packet, in_water, &state)? {
+        Some((slot, block)) if can_build && replaceable => {
+            if set_block(event.conn_id, &target, block, &state).await? {
+                use_held_item(event.conn_id, slot, &state)?;
+            }
+        }
+        // Undo whatever the client predicted
+        _ => resync_block(event.conn_id, &target, &state).await?,
+    }
+
+    acknowledge(event.conn_id, &packet.sequence, &state).await?;
+
+    Ok(event)
+}
+
+#[event_handler]
+async fn handle_swing_arm(
+    event: SwingArmEvent,
+    state: GlobalState,
+) -> Result<SwingArmEvent, NetError> {
+    let animation = match event.swing_arm_packet.hand {
+        Hand::Main => EntityAnimationPacket::SWING_MAIN_ARM,
+        Hand::Off => EntityAnimationPacket::SWING_OFFHAND,
+    };
+    let packet = EntityAnimationPacket::new(event.conn_id as i32, animation);
+
+    let (x, z) = {
+        let position = state.universe.get::<Position>(event.conn_id)?;
+        (position.x.floor() as i32, position.z.floor() as i32)
+    };
+    let dimension = *state.universe.get::<Dimension>(event.conn_id)?;
+    let viewers = players_viewing(x, z, dimension, &state)
+        .into_iter()
+        .filter(|&entity| entity != event.conn_id)
+        .collect();
+
+    state
+        .broadcast(&packet, BroadcastOptions::default().only(viewers))
+        .await?;
+
+    Ok(event)
+}
+
+/// Whether the player's game mode lets them change blocks, and the block at `location` is in
+/// their reach and inside the world border.
+fn can_build_at(
+    conn_id: usize,
+    location: &NetworkPosition,
+    state: &GlobalState,
+) -> Result<bool, NetError> {
+    let game_mode = *state.universe.get::<GameMode>(conn_id)?;
+    let position = state.universe.get::<Position>(conn_id)?;
+    Ok(game_mode.can_build()
+        && position.can_reach_block(
+            location.x,
+            location.y.into(),
+            location.z,
+            game_mode.block_reach(),
+        )
+        && state.world.border().contains_block(location.x, location.z))
+}
+
+/// Whether the block at a location in the player's dimension has no hardness.
+async fn breaks_instantly(
+    conn_id: usize,
+    location: &NetworkPosition,
+    state: &GlobalState,
+) -> Result<bool, NetError> {
+    let dimension = *state.universe.get::<Dimension>(conn_id)?;
+    Ok(state
+        .world
+        .get_block_at(location.x, location.y.into(), location.z, dimension.name())
+        .await
+        .is_ok_and(|block| block.state().is_some_and(|state| state.breaks_instantly())))
+}
+
+/// The inventory slot of the stack in the packet's hand, and the state of the block that stack
+/// places, turned the way the player placed it. Blocks placed in still water are waterlogged
+/// if they can be.
+fn held_block(
+    conn_id: usize,
+    packet: &UseItemOnPacket,
+    in_water: bool,
+    state: &GlobalState,
+) -> Result<Option<(usize, BlockId)>, NetError> {
+    let inventory = state.universe.get::<Inventory>(conn_id)?;
+    let slot = match packet.hand {
+        Hand::Main => HOTBAR_START + inventory.selected_slot(),
+        Hand::Off => OFFHAND_SLOT,
+    };
+    let registry = BlockRegistry::global();
+    let Some(block) = inventory
+        .get(slot)
+        .and_then(|stack| ItemRegistry::global().block(stack.item, registry))
+        .and_then(|block| registry.get(block))
+    else {
+        return Ok(None);
+    };
+
+    let rotation = state.universe.get::<Rotation>(conn_id)?;
+    let placement = Placement {
+        face: packet.face.facing(),
+        cursor_y: packet.cursor_y,
+        yaw: rotation.yaw,
+        pitch: rotation.pitch,
+    };
+    let placed = registry.placement_state(block, &placement);
+    let placed = if in_water {
+        registry
+            .with_property(placed, "waterlogged", "true")
+            .unwrap_or(placed)
+    } else {
+        placed
+    };
+    Ok(Some((slot, placed.id)))
+}
+
+/// Takes one item out of the stack in a slot after it's been placed, unless the player's game
+/// mode gives them as many items as they like. The client takes it out on its own.
+fn use_held_item(conn_id: usize, slot: usize, state: &GlobalState) -> Result<(), NetError> {
+    if state.universe.get::<GameMode>(conn_id)?.can_spawn_items() {
+        return Ok(());
+    }
+    let mut inventory = state.universe.get_mut::<Inventory>(conn_id)?;
+    let stack = inventory.get(slot).cloned().map(|mut stack| {
+        stack.count -= 1;
+        stack
+    });
+    inventory.set(slot, stack)?;
+    Ok(())
+}
+
+/// Sets a block in the world and sends the change to every player that has its chunk loaded.
+/// Returns whether the block was set.
+///
+/// If the block can't be set, the player who changed it is sent the block that's actually
+/// there instead, so their client doesn't keep its prediction.
+async fn set_block(
+    conn_id: usize,
+    location: &NetworkPosition,
+    block: BlockId,
+    state: &GlobalState,
+) -> Result<bool, NetError> {
+    let dimension = *state.universe.get::<Dimension>(conn_id)?;
+    match state
+        .world
+        .set_block_at(
+            location.x,
+            location.y.into(),
+            location.z,
+            dimension.name(),
+            block,
+        )
+        .await
+    {
+        Ok(change) => {
+            let packet = BlockUpdatePacket::new(location.clone(), block.0);
+            let viewers = players_viewing(location.x, location.z, dimension, state);
+            state
+                .broadcast(&packet, BroadcastOptions::default().only(viewers))
+                .await?;
+            send_light(&change.relit_chunks, dimension, state).await?;
+            Ok(true)
+        }
+        Err(e) => {
+            debug!("Unable to set block at {}: {}", location, e);
+            resync_block(conn_id, location, state).await?;
+            Ok(false)
+        }
+    }
+}
+
+/// Sends the light of chunks whose light changed to every player that has them loaded.
+async fn send_light(
+    chunks: &[(i32, i32)],
+    dimension: Dimension,
+    state: &GlobalState,
+) -> Result<(), NetError> {
+    for &(chunk_x, chunk_z) in chunks {
+        let chunk = state
+            .world
+            .load_chunk(chunk_x, chunk_z, dimension.name())
+            .await?;
+        let viewers = players_viewing(chunk_x * 16, chunk_z * 16, dimension, state);
+        state
+            .broadcast(
+                &UpdateLightPacket::from_chunk(&chunk),
+                BroadcastOptions::default().only(viewers),
+            )
+            .await?;
+    }
+    Ok(())
+}
+
+/// Sends the block that's actually at a location to a player.
+async fn resync_block(
+    conn_id: usize,
+    location: &NetworkPosition,
+    state: &GlobalState,
+) -> Result<(), NetError> {
+    let dimension = *state.universe.get::<Dimension>(conn_id)?;
+    let block = state
+        .world
+        .get_block_at(location.x, location.y.into(), location.z, dimension.name())
+        .await
+        .unwrap_or_default();
+    let mut writer = state.universe.get_mut::<StreamWriter>(conn_id)?;
+    writer
+        .send_packet(
+            &BlockUpdatePacket::new(location.clone(), block.0),
+            &NetEncodeOpts::WithLength,
+        )
+        .await
+}
+
+async fn acknowledge(
+    conn_id: usize,
+    sequence: &VarInt,
+    state: &GlobalState,
+) -> Result<(), NetError> {
+    let mut writer = state.universe.get_mut::<StreamWriter>(conn_id)?;
+    writer
+        .send_packet(
+            &AcknowledgeBlockChangePacket::new(sequence.clone()),
+            &NetEncodeOpts::WithLength,
+        )
+        .await
+}
+
+/// Every player in the dimension that has the chunk containing the block at (`x`, `z`) loaded.
+fn players_viewing(x: i32, z: i32, dimension: Dimension, state: &GlobalState) -> Vec<usize> {
+    let chunk = (x.div_euclid(16), z.div_euclid(16));
+    state
+        .universe
+        .query::<(&LoadedChunks, &Dimension)>()
+        .filter(|(_, (loaded, in_dimension))| {
+            **in_dimension == dimension && loaded.chunks.contains(&chunk)
+        })
+        .map(|(entity, _)| entity)
+        .collect()
+}
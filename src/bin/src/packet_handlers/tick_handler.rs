@@ -1,46 +1,27 @@
+use ferrumc_config::statics::get_global_config;
 use ferrumc_macros::event_handler;
-use ferrumc_net::connection::ConnectionState;
-use ferrumc_net::connection::StreamWriter;
 use ferrumc_net::errors::NetError;
 use ferrumc_net::packets::outgoing::update_time::TickEvent;
-use ferrumc_net::packets::outgoing::update_time::UpdateTimePacket;
-use ferrumc_net::utils::broadcast::{BroadcastOptions, BroadcastToAll};
+use ferrumc_net::utils::time::broadcast_time;
 use ferrumc_state::GlobalState;
 use tracing::warn;
 
 #[event_handler]
 async fn handle_tick(event: TickEvent, state: GlobalState) -> Result<TickEvent, NetError> {
-    // info!("Tick {} ", event.tick);
     // TODO: Handle tick in terms of game logic here
     // this should call a function in world which handles the world state and calls the appropriate events which send their respective packets
 
-    ///////
+    let time = state.world.tick_time();
 
-    let packet = UpdateTimePacket::new(event.tick, event.tick % 24000);
-
-    let entities = state
-        .universe
-        .query::<(&mut StreamWriter, &ConnectionState)>()
-        .into_entities()
-        .into_iter()
-        .filter_map(|entity| {
-            let conn_state = state.universe.get::<ConnectionState>(entity).ok()?;
-            if matches!(*conn_state, ConnectionState::Play) {
-                Some(entity)
-            } else {
-                None
+    // Clients move the time on by themselves, so they only need correcting now and then
+    let interval = get_global_config().time.broadcast_interval.max(1) as i64;
+    if time.world_age % interval == 0 {
+        tokio::spawn(async move {
+            if let Err(e) = broadcast_time(&state).await {
+                warn!("Failed to broadcast the time: {:?}", e);
             }
-        })
-        .collect::<Vec<_>>();
-
-    tokio::spawn(async move {
-        if let Err(e) = state
-            .broadcast(&packet, BroadcastOptions::default().only(entities))
-            .await
-        {
-            warn!("Failed to broadcast tick packet: {:?}", e);
-        }
-    });
+        });
+    }
 
     Ok(event)
 }
@@ -0,0 +1,27 @@
+use ferrumc_core::transform::teleport::TeleportTracker;
+use ferrumc_macros::event_handler;
+use ferrumc_net::errors::NetError;
+use ferrumc_net::packets::incoming::confirm_teleportation::ConfirmTeleportationEvent;
+use ferrumc_net::utils::ecs_helpers::EntityExt;
+use ferrumc_state::GlobalState;
+use tracing::debug;
+
+#[event_handler]
+async fn handle_confirm_teleportation(
+    event: ConfirmTeleportationEvent,
+    state: GlobalState,
+) -> Result<ConfirmTeleportationEvent, NetError> {
+    let confirmed = event
+        .conn_id
+        .get_mut::<TeleportTracker>(&state)?
+        .confirm(event.teleport_id);
+
+    if !confirmed {
+        debug!(
+            "Entity {} confirmed an outdated teleport {}",
+            event.conn_id, event.teleport_id
+        );
+    }
+
+    Ok(event)
+}
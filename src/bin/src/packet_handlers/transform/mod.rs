@@ -1 +1,2 @@
+pub mod confirm_teleportation;
 pub mod update_player_position;
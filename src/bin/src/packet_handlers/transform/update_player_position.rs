@@ -1,11 +1,18 @@
+use ferrumc_config::statics::get_global_config;
+use ferrumc_core::dimensions::dimension::Dimension;
+use ferrumc_core::gamemode::game_mode::GameMode;
 use ferrumc_core::transform::grounded::OnGround;
 use ferrumc_core::transform::position::Position;
 use ferrumc_core::transform::rotation::Rotation;
+use ferrumc_core::transform::teleport::TeleportTracker;
 use ferrumc_macros::event_handler;
 use ferrumc_net::errors::NetError;
 use ferrumc_net::packets::packet_events::TransformEvent;
 use ferrumc_net::utils::ecs_helpers::EntityExt;
+use ferrumc_net::utils::entity_tracking::broadcast_movement;
+use ferrumc_net::utils::movement::{resync_position, validate_move};
 use ferrumc_state::GlobalState;
+use tracing::debug;
 
 #[event_handler]
 async fn handle_player_move(
@@ -13,7 +20,32 @@ async fn handle_player_move(
     state: GlobalState,
 ) -> Result<TransformEvent, NetError> {
     let conn_id = event.conn_id;
+    let previous_position = *conn_id.get::<Position>(&state)?;
+
+    // Moves sent before the client got our last teleport are from where it used to be
+    if conn_id.get::<TeleportTracker>(&state)?.is_awaiting() {
+        return Ok(event);
+    }
+
     if let Some(ref new_position) = event.position {
+        let config = &get_global_config().movement;
+        let game_mode = *conn_id.get::<GameMode>(&state)?;
+        let dimension = *conn_id.get::<Dimension>(&state)?;
+        if let Err(e) = validate_move(
+            config,
+            &state.world,
+            game_mode,
+            dimension,
+            &previous_position,
+            new_position,
+        )
+        .await
+        {
+            debug!("Rejected a move from entity {}: {}", conn_id, e);
+            resync_position(&state, conn_id).await?;
+            return Ok(event);
+        }
+
         let mut position = conn_id.get_mut::<Position>(&state)?;
 
         *position = Position::new(new_position.x, new_position.y, new_position.z);
@@ -31,5 +63,11 @@ async fn handle_player_move(
         *on_ground = OnGround(new_grounded);
     }
 
+    if let Err(e) =
+        broadcast_movement(&state, conn_id, previous_position, event.rotation.is_some()).await
+    {
+        debug!("Failed to broadcast movement of entity {}: {}", conn_id, e);
+    }
+
     Ok(event)
 }
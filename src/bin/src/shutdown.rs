@@ -0,0 +1,78 @@
+//! Stopping the server without losing anything.
+
+use crate::systems::definition;
+use crate::Result;
+use ferrumc_config::statics::get_global_config;
+use ferrumc_net::connection::ConnectionState;
+use ferrumc_net::utils::player_data::save_all_player_data;
+use ferrumc_net::utils::state::terminate_connection;
+use ferrumc_state::GlobalState;
+use tracing::{error, info, warn};
+
+/// Starts the shutdown on Ctrl-C, or SIGTERM on unix.
+pub async fn shutdown_on_signal(state: GlobalState) {
+    #[cfg(unix)]
+    let terminate = async {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                sigterm.recv().await;
+            }
+            Err(e) => {
+                warn!("Could not listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        result = tokio::signal::ctrl_c() => {
+            if let Err(e) = result {
+                error!("Could not listen for Ctrl-C: {}", e);
+                return;
+            }
+        }
+        _ = terminate => {}
+        _ = state.shutdown.cancelled() => return,
+    }
+
+    info!("Received a shutdown signal");
+    state.shutdown.cancel();
+}
+
+/// Saves and disconnects every player, stops the systems and saves the world.
+pub async fn shutdown(state: GlobalState) -> Result<()> {
+    info!("Shutting down...");
+    // Also stops the TCP listener accepting connections, if the systems stopped on their own
+    state.shutdown.cancel();
+
+    info!("Saving players...");
+    save_all_player_data(&state).await;
+    disconnect_all(&state).await;
+    definition::stop_all_systems(state.clone()).await?;
+
+    info!("Saving the world...");
+    state.world.close().await?;
+
+    Ok(())
+}
+
+async fn disconnect_all(state: &GlobalState) {
+    let reason = &get_global_config().shutdown_message;
+    let connections = state
+        .universe
+        .query::<&ConnectionState>()
+        .filter(|(_, conn_state)| {
+            matches!(**conn_state, ConnectionState::Login | ConnectionState::Play)
+        })
+        .map(|(entity, _)| entity)
+        .collect::<Vec<_>>();
+
+    for entity in connections {
+        if let Err(e) = terminate_connection(state.clone(), entity, reason.clone()).await {
+            warn!("Could not disconnect entity {}: {}", entity, e);
+        }
+    }
+}
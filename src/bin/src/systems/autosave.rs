@@ -0,0 +1,42 @@
+use crate::systems::definition::System;
+use async_trait::async_trait;
+use ferrumc_net::utils::player_data::save_all_player_data;
+use ferrumc_state::GlobalState;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+/// How often players' data and the world's time are saved, in case the server doesn't stop
+/// cleanly. Vanilla does the same every 6000 ticks.
+const AUTOSAVE_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+pub(super) struct AutosaveSystem;
+
+#[async_trait]
+impl System for AutosaveSystem {
+    async fn start(self: Arc<Self>, state: GlobalState) {
+        info!("Autosave system started");
+
+        loop {
+            tokio::select! {
+                _ = tokio::time::sleep(AUTOSAVE_INTERVAL) => {}
+                _ = state.shutdown.cancelled() => break,
+            }
+
+            let saved = save_all_player_data(&state).await;
+            debug!("Autosaved data for {} players", saved);
+            if let Err(e) = state.world.save_time().await {
+                warn!("Could not save the world's time: {}", e);
+            }
+        }
+    }
+
+    async fn stop(self: Arc<Self>, _state: GlobalState) {
+        // The shutdown saves everyone itself
+        debug!("Stopping autosave system...");
+    }
+
+    fn name(&self) -> &'static str {
+        "autosave"
+    }
+}
@@ -1,21 +1,30 @@
 use crate::systems::definition::System;
 use async_trait::async_trait;
+use ferrumc_config::statics::get_global_config;
+use ferrumc_core::chunks::loaded_chunks::LoadedChunks;
+use ferrumc_core::dimensions::dimension::Dimension;
 use ferrumc_core::identity::player_identity::PlayerIdentity;
 use ferrumc_core::transform::position::Position;
 use ferrumc_net::connection::StreamWriter;
+use ferrumc_net::errors::NetError;
+use ferrumc_net::packets::incoming::client_information::ClientInformation;
 use ferrumc_net::packets::outgoing::chunk_and_light_data::ChunkAndLightData;
 use ferrumc_net::packets::outgoing::set_center_chunk::SetCenterChunk;
+use ferrumc_net::packets::outgoing::unload_chunk::UnloadChunkPacket;
+use ferrumc_net::utils::entity_tracking::update_tracking;
 use ferrumc_net_codec::encode::NetEncodeOpts;
 use ferrumc_state::GlobalState;
+use ferrumc_world::chunk_format::Chunk;
 use std::ops::Div;
 use std::simd::num::SimdFloat;
 use std::simd::{f64x2, StdFloat};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 
-const CHUNK_RADIUS: i32 = 12;
+/// How often players' views are checked for changes.
+const UPDATE_INTERVAL: Duration = Duration::from_millis(100);
 
 pub(super) struct ChunkSenderSystem {
     pub stop: AtomicBool,
@@ -35,115 +44,21 @@ impl System for ChunkSenderSystem {
         info!("Chunk sender system started");
 
         while !self.stop.load(Ordering::Relaxed) {
-            debug!("Sending chunks to players");
             let players = state
                 .universe
-                .query::<(&PlayerIdentity, &Position, &mut StreamWriter)>();
-            // TODO: This is so ass. Please fix this.
-            for (_entity, (player, position, mut conn)) in players {
-                debug!(
-                    "Sending chunks to player: {} @ {}",
-                    player.username, position
-                );
-                // Haha SIMD go brrrrt
-                let [chunk_x, chunk_z] = f64x2::from_array([position.x, position.z])
-                    .floor()
-                    .div(f64x2::from_array([16f64, 16f64]))
-                    .cast::<i32>()
-                    .to_array();
-                if let Err(e) = conn
-                    .send_packet(
-                        &SetCenterChunk::new(chunk_x, chunk_z),
-                        &NetEncodeOpts::WithLength,
-                    )
-                    .await
-                {
-                    error!(
-                        "Unable to set the center chunk for {} @ {}, {}: {}",
-                        &player.username, chunk_x, chunk_z, e
-                    );
-                    continue;
+                .query::<(&PlayerIdentity, &Position, &LoadedChunks)>()
+                .into_entities();
+
+            for entity in players {
+                if let Err(e) = update_player_view(entity, &state).await {
+                    error!("Unable to send chunks to entity {}: {}", entity, e);
                 }
-                let start = std::time::Instant::now();
-                let mut chunk_range = (chunk_x - CHUNK_RADIUS..chunk_x + CHUNK_RADIUS)
-                    .flat_map(|z| {
-                        (chunk_z - CHUNK_RADIUS..chunk_z + CHUNK_RADIUS)
-                            .map(move |x| (x, z, "overworld"))
-                    })
-                    .collect::<Vec<_>>();
-
-                chunk_range.sort_by_key(|&(x, z, _)| {
-                    let dx = x - chunk_x;
-                    let dz = z - chunk_z;
-                    (((dx ^ 2) + (dz ^ 2)) as f64).sqrt() as i32
-                });
-
-                match state.world.load_chunk_batch(chunk_range).await {
-                    Ok(chunks) => {
-                        for chunk in chunks {
-                            match ChunkAndLightData::from_chunk(&chunk) {
-                                Ok(data) => {
-                                    if let Err(e) =
-                                        conn.send_packet(&data, &NetEncodeOpts::WithLength).await
-                                    {
-                                        error!(
-                                            "Unable to send chunk data to {} @ {}, {}: {}",
-                                            &player.username, chunk.x, chunk.z, e
-                                        );
-                                        if let Err(e) = conn
-                                            .send_packet(
-                                                &ChunkAndLightData::empty(chunk.x, chunk.z),
-                                                &NetEncodeOpts::WithLength,
-                                            )
-                                            .await
-                                        {
-                                            error!(
-                                                "Unable to send empty chunk data to {} @ {}, {}: {}",
-                                                &player.username, chunk.x, chunk.z, e
-                                            );
-                                        }
-                                    }
-                                }
-                                Err(e) => {
-                                    error!(
-                                        "Unable to convert chunk to chunk and light data for {} @ {}, {}: {}",
-                                        &player.username, chunk.x, chunk.z, e
-                                    );
-                                    if let Err(e) = conn
-                                        .send_packet(
-                                            &ChunkAndLightData::empty(chunk.x, chunk.z),
-                                            &NetEncodeOpts::WithLength,
-                                        )
-                                        .await
-                                    {
-                                        error!(
-                                            "Unable to send empty chunk data to {} @ {}, {}: {}",
-                                            &player.username, chunk.x, chunk.z, e
-                                        );
-                                    }
-                                }
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        error!(
-                            "Unable to load chunks for {} @ {}, {}: {}",
-                            &player.username, chunk_x, chunk_z, e
-                        );
-                    }
+                if let Err(e) = update_tracking(&state, entity).await {
+                    debug!("Unable to update entities for entity {}: {}", entity, e);
                 }
-
-                debug!(
-                    "Sent {} chunks to player: {} @ {:.2},{:.2} in {:?}",
-                    (CHUNK_RADIUS * 2) * 2,
-                    player.username,
-                    position.x,
-                    position.z,
-                    start.elapsed()
-                );
             }
 
-            tokio::time::sleep(Duration::from_secs(5)).await;
+            tokio::time::sleep(UPDATE_INTERVAL).await;
         }
     }
 
@@ -156,3 +71,110 @@ impl System for ChunkSenderSystem {
         "chunk_sender"
     }
 }
+
+/// The view distance for a player: their client's view distance, capped by the server.
+fn view_distance(entity: usize, state: &GlobalState) -> i32 {
+    let max_view_distance = get_global_config().max_view_distance;
+    let view_distance = state
+        .universe
+        .get::<ClientInformation>(entity)
+        .map(|info| info.view_distance.min(max_view_distance))
+        .unwrap_or(max_view_distance);
+
+    i32::from(view_distance)
+}
+
+/// Sends the chunks that entered the player's view and unloads the ones that left it.
+async fn update_player_view(entity: usize, state: &GlobalState) -> Result<(), NetError> {
+    let center = {
+        let position = state.universe.get::<Position>(entity)?;
+        // Haha SIMD go brrrrt
+        let [chunk_x, chunk_z] = f64x2::from_array([position.x, position.z])
+            .div(f64x2::from_array([16f64, 16f64]))
+            .floor()
+            .cast::<i32>()
+            .to_array();
+        (chunk_x, chunk_z)
+    };
+    let radius = view_distance(entity, state);
+    let dimension = *state.universe.get::<Dimension>(entity)?;
+
+    let change = state
+        .universe
+        .get_mut::<LoadedChunks>(entity)?
+        .update(center, radius);
+    if change.is_empty() {
+        return Ok(());
+    }
+
+    let start = std::time::Instant::now();
+
+    // Load everything before taking the writer, so other packets aren't held up by the database.
+    let chunks = load_chunks(&change.load, dimension, state).await;
+
+    let mut conn = state.universe.get_mut::<StreamWriter>(entity)?;
+
+    if change.center_changed {
+        conn.send_packet(
+            &SetCenterChunk::new(center.0, center.1),
+            &NetEncodeOpts::WithLength,
+        )
+        .await?;
+    }
+
+    for &(x, z) in &change.unload {
+        conn.send_packet(&UnloadChunkPacket::new(x, z), &NetEncodeOpts::WithLength)
+            .await?;
+    }
+
+    for ((x, z), chunk) in change.load.iter().copied().zip(chunks) {
+        let data = match chunk.as_ref().map(ChunkAndLightData::from_chunk) {
+            Some(Ok(data)) => data,
+            Some(Err(e)) => {
+                error!(
+                    "Unable to convert chunk {}, {} to chunk and light data: {}",
+                    x, z, e
+                );
+                ChunkAndLightData::empty(x, z, dimension)
+            }
+            None => ChunkAndLightData::empty(x, z, dimension),
+        };
+        conn.send_packet(&data, &NetEncodeOpts::WithLength).await?;
+    }
+
+    debug!(
+        "Sent {} chunks and unloaded {} for entity {} @ {}, {} in {:?}",
+        change.load.len(),
+        change.unload.len(),
+        entity,
+        center.0,
+        center.1,
+        start.elapsed()
+    );
+
+    Ok(())
+}
+
+/// Loads the given chunks of a dimension in order. Chunks that can't be loaded are `None`.
+async fn load_chunks(
+    coords: &[(i32, i32)],
+    dimension: Dimension,
+    state: &GlobalState,
+) -> Vec<Option<Chunk>> {
+    let batch = coords
+        .iter()
+        .map(|&(x, z)| (x, z, dimension.name()))
+        .collect();
+
+    state
+        .world
+        .load_chunk_batch(batch)
+        .await
+        .into_iter()
+        .map(|((x, z), chunk)| {
+            chunk
+                .inspect_err(|e| warn!("Unable to load chunk {}, {}: {}", x, z, e))
+                .ok()
+        })
+        .collect()
+}
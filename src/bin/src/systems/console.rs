@@ -0,0 +1,135 @@
+use crate::systems::definition::System;
+use async_trait::async_trait;
+use ferrumc_commands::infrastructure::dispatcher;
+use ferrumc_commands::sender::CommandSender;
+use ferrumc_logging::{clear_log_printer, set_log_printer};
+use ferrumc_state::GlobalState;
+use rustyline::completion::Completer;
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::DefaultHistory;
+use rustyline::validate::Validator;
+use rustyline::{CompletionType, Config, Context, Editor, ExternalPrinter, Helper};
+use std::sync::Arc;
+use tokio::runtime::Handle;
+use tracing::{debug, error, info, warn};
+
+/// Reads commands from stdin and runs them as the console.
+pub struct ConsoleSystem;
+
+#[async_trait]
+impl System for ConsoleSystem {
+    async fn start(self: Arc<Self>, state: GlobalState) {
+        let runtime = Handle::current();
+        // Reading a line blocks, and a blocking task would hold up the runtime's shutdown until
+        // the next line is entered, so the console gets a thread of its own.
+        let spawned = std::thread::Builder::new()
+            .name("console".to_string())
+            .spawn(move || run_console(state, runtime));
+        if let Err(e) = spawned {
+            error!("Could not start the console: {}", e);
+        }
+    }
+
+    async fn stop(self: Arc<Self>, _state: GlobalState) {
+        debug!("Stopping console system...");
+    }
+
+    fn name(&self) -> &'static str {
+        "console"
+    }
+}
+
+fn run_console(state: GlobalState, runtime: Handle) {
+    let config = Config::builder()
+        .auto_add_history(true)
+        .completion_type(CompletionType::List)
+        .build();
+    let mut editor = match Editor::<ConsoleHelper, DefaultHistory>::with_config(config) {
+        Ok(editor) => editor,
+        Err(e) => {
+            error!("Could not start the console: {}", e);
+            return;
+        }
+    };
+    editor.set_helper(Some(ConsoleHelper {
+        state: state.clone(),
+    }));
+
+    // Print logs above the prompt instead of through the line being typed
+    match editor.create_external_printer() {
+        Ok(mut printer) => set_log_printer(move |line| {
+            let _ = printer.print(line);
+        }),
+        Err(e) => debug!("Logs may interrupt console input: {}", e),
+    }
+
+    while !state.shutdown.is_cancelled() {
+        match editor.readline("> ") {
+            Ok(line) => {
+                let line = line.trim();
+                let command = line.strip_prefix('/').unwrap_or(line);
+                if command.is_empty() {
+                    continue;
+                }
+
+                let result = runtime.block_on(dispatcher().execute(
+                    command,
+                    CommandSender::Console,
+                    state.clone(),
+                ));
+                if let Err(e) = result {
+                    warn!("{}", e);
+                }
+            }
+            Err(ReadlineError::Interrupted) => {
+                info!("Stopping the server...");
+                state.shutdown.cancel();
+            }
+            // stdin was closed, e.g. when running in the background
+            Err(ReadlineError::Eof) => break,
+            Err(e) => {
+                error!("Console failed with error: {}", e);
+                break;
+            }
+        }
+    }
+
+    clear_log_printer();
+}
+
+/// Tab-completes commands.
+struct ConsoleHelper {
+    state: GlobalState,
+}
+
+impl Completer for ConsoleHelper {
+    type Candidate = String;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        let input = &line[..pos];
+        let command = input.strip_prefix('/').unwrap_or(input);
+        let suggestions =
+            dispatcher().suggest(command, &CommandSender::Console, &self.state.universe);
+        Ok((
+            suggestions.start + (input.len() - command.len()),
+            suggestions.matches,
+        ))
+    }
+}
+
+impl Hinter for ConsoleHelper {
+    type Hint = String;
+}
+
+impl Highlighter for ConsoleHelper {}
+
+impl Validator for ConsoleHelper {}
+
+impl Helper for ConsoleHelper {}
@@ -1,4 +1,6 @@
+use crate::systems::autosave::AutosaveSystem;
 use crate::systems::chunk_sender::ChunkSenderSystem;
+use crate::systems::console::ConsoleSystem;
 use crate::systems::keep_alive_system::KeepAliveSystem;
 use crate::systems::tcp_listener_system::TcpListenerSystem;
 use crate::systems::ticking_system::TickingSystem;
@@ -7,7 +9,11 @@ use ferrumc_net::NetResult;
 use ferrumc_state::GlobalState;
 use futures::stream::FuturesUnordered;
 use std::sync::{Arc, LazyLock};
-use tracing::{debug, debug_span, info, Instrument};
+use std::time::Duration;
+use tracing::{debug, debug_span, info, warn, Instrument};
+
+/// How long each system gets to stop before the server moves on without it.
+const STOP_TIMEOUT: Duration = Duration::from_secs(5);
 
 #[async_trait]
 pub trait System: Send + Sync {
@@ -24,6 +30,8 @@ pub fn create_systems() -> Vec<Arc<dyn System>> {
         Arc::new(KeepAliveSystem::new()),
         Arc::new(TickingSystem),
         Arc::new(ChunkSenderSystem::new()),
+        Arc::new(ConsoleSystem),
+        Arc::new(AutosaveSystem),
     ]
 }
 pub async fn start_all_systems(state: GlobalState) -> NetResult<()> {
@@ -51,7 +59,12 @@ pub async fn stop_all_systems(state: GlobalState) -> NetResult<()> {
 
     for system in SYSTEMS.iter() {
         debug!("Stopping system: {}", system.name());
-        system.clone().stop(state.clone()).await;
+        if tokio::time::timeout(STOP_TIMEOUT, system.clone().stop(state.clone()))
+            .await
+            .is_err()
+        {
+            warn!("System {} took too long to stop", system.name());
+        }
     }
 
     Ok(())
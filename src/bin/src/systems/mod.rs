@@ -1,6 +1,8 @@
 pub(crate) mod definition;
 
+mod autosave;
 mod chunk_sender;
+mod console;
 mod keep_alive_system;
 mod tcp_listener_system;
 mod ticking_system;
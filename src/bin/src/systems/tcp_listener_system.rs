@@ -32,7 +32,10 @@ impl TcpListenerSystem {
 
         loop {
             debug!("Accepting connection");
-            let (stream, _) = tcp_listener.accept().await?;
+            let (stream, _) = tokio::select! {
+                accepted = tcp_listener.accept() => accepted?,
+                _ = state.shutdown.cancelled() => break,
+            };
             let addy = stream.peer_addr()?;
             tokio::task::spawn(
                 handle_connection(Arc::clone(&state), stream)
@@ -40,7 +43,7 @@ impl TcpListenerSystem {
             );
         }
 
-        #[allow(unreachable_code)]
+        debug!("Stopped accepting connections");
         Ok(())
     }
 }
@@ -7,32 +7,48 @@ use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::time::Instant;
-use tracing::{debug, info};
+use tracing::{debug, warn};
+
+/// Runs ticks on a fixed schedule, at the configured tick rate. When a tick overruns, the next
+/// ones run early to catch up, but never closer together than half a tick period, so players
+/// don't see the game suddenly speed up.
 pub struct TickingSystem;
 
 static KILLED: AtomicBool = AtomicBool::new(false);
 
+/// How far behind the server can get before it gives up on the ticks it missed.
+const MAX_TICK_DEBT: Duration = Duration::from_secs(2);
+
 #[async_trait]
 impl System for TickingSystem {
     async fn start(self: Arc<Self>, state: GlobalState) {
-        // TODO game time must be loaded from a file
+        let period = state.ticks.tick_period();
         let mut tick = 0;
+        let mut scheduled = Instant::now();
+        let mut last_start = scheduled;
         while !KILLED.load(Ordering::Relaxed) {
-            let required_end = Instant::now() + Duration::from_millis(50);
+            let start = Instant::now();
             // TODO handle error
             let res = TickEvent::trigger(TickEvent::new(tick), state.clone()).await;
             if res.is_err() {
                 debug!("error handling tick event: {:?}", res);
             }
-            let now = Instant::now();
-            if required_end > now {
-                tokio::time::sleep(required_end - now).await;
-            } else {
-                let time_debt = now - required_end;
-                info!("Running behind by {:?}", time_debt);
-            }
-
+            let end = Instant::now();
+            state.ticks.record(start - last_start, end - start);
+            last_start = start;
             tick += 1;
+
+            scheduled += period;
+            let debt = end.saturating_duration_since(scheduled);
+            if debt > MAX_TICK_DEBT {
+                warn!(
+                    "Running behind by {:?}, skipping {} ticks",
+                    debt,
+                    debt.as_nanos() / period.as_nanos()
+                );
+                scheduled = end;
+            }
+            tokio::time::sleep_until(scheduled.max(start + period / 2)).await;
         }
     }
 
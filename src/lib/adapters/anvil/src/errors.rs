@@ -19,6 +19,12 @@ pub enum AnvilError {
     MissingChecksum,
     #[error("Cannot decompress data (probably invalid)")]
     DecompressionError,
+    #[error("Cannot compress data")]
+    CompressionError,
+    #[error("Chunk {0}, {1} is too large to fit in a region file")]
+    ChunkTooLarge(u32, u32),
+    #[error("Unable to write file {0}: {1}")]
+    UnableToWriteFile(PathBuf, std::io::Error),
 }
 
 impl From<lzzzz::Error> for AnvilError {
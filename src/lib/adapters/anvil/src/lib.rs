@@ -1,4 +1,5 @@
 pub mod errors;
+pub mod writer;
 
 use crate::errors::AnvilError;
 use memmap2::Mmap;
@@ -163,7 +164,18 @@ impl LoadedAnvilFile {
         let offset = offset * 4096;
         let size = (location & 0xFF) * 4096;
         let chunk_data = self.get_data_from_file(offset, size)?;
-        let chunk_compressed_data = &chunk_data[5..];
+        if chunk_data.len() < 5 {
+            return Err(AnvilError::InvalidOffsetOrSize);
+        }
+        // The length counts the compression type byte but not itself, and leaves out the padding
+        // at the end of the last sector
+        let length =
+            u32::from_be_bytes([chunk_data[0], chunk_data[1], chunk_data[2], chunk_data[3]])
+                as usize;
+        if length == 0 || length + 4 > chunk_data.len() {
+            return Err(AnvilError::InvalidOffsetOrSize);
+        }
+        let chunk_compressed_data = &chunk_data[5..length + 4];
         let compression_type = chunk_data[4];
 
         match compression_type {
@@ -209,8 +221,7 @@ impl LoadedAnvilFile {
     /// This function will return the decompressed chunk data, or an error if the data reading
     /// fails for any reason.
     pub fn get_chunk(&self, x: u32, z: u32) -> Result<Option<Vec<u8>>, AnvilError> {
-        let index = u64::from(4 * ((x & 31) + (z & 31) * 32));
-        let base_index = index as usize * 4;
+        let base_index = 4 * ((x & 31) + (z & 31) * 32) as usize;
         let chunk_data = [
             u32::from(self.table[base_index]),
             u32::from(self.table[base_index + 1]),
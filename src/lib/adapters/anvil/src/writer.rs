@@ -0,0 +1,228 @@
+use crate::errors::AnvilError;
+use flate2::write::GzEncoder;
+use std::io::Write;
+use std::path::PathBuf;
+
+/// Size of a sector in a region file. The header takes two, and every chunk starts on a new one.
+const SECTOR_SIZE: usize = 4096;
+/// A chunk's location entry only has a byte for its sector count.
+const MAX_CHUNK_SECTORS: usize = 255;
+
+/// How the chunks in a region file are compressed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CompressionType {
+    Gzip,
+    /// What vanilla uses unless it's configured otherwise.
+    #[default]
+    Zlib,
+    None,
+}
+
+impl CompressionType {
+    /// The byte written before each chunk's data to say how it's compressed.
+    pub fn id(self) -> u8 {
+        match self {
+            CompressionType::Gzip => 1,
+            CompressionType::Zlib => 2,
+            CompressionType::None => 3,
+        }
+    }
+
+    fn compress(self, data: &[u8]) -> Result<Vec<u8>, AnvilError> {
+        match self {
+            CompressionType::Gzip => {
+                let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder
+                    .write_all(data)
+                    .map_err(|_| AnvilError::CompressionError)?;
+                encoder.finish().map_err(|_| AnvilError::CompressionError)
+            }
+            CompressionType::Zlib => {
+                yazi::compress(data, yazi::Format::Zlib, yazi::CompressionLevel::Default)
+                    .map_err(|_| AnvilError::CompressionError)
+            }
+            CompressionType::None => Ok(data.to_vec()),
+        }
+    }
+}
+
+/// A chunk that has been compressed and is waiting to be written.
+struct PendingChunk {
+    timestamp: u32,
+    data: Vec<u8>,
+}
+
+/// Builds a region file out of chunks.
+///
+/// Chunks are compressed as they're added, so only the compressed data is kept in memory until
+/// the file is written.
+///
+/// # Examples
+///
+/// ```no_run
+/// use ferrumc_anvil::writer::RegionWriter;
+/// use std::path::PathBuf;
+///
+/// let mut writer = RegionWriter::new();
+/// writer.add_chunk(0, 0, b"chunk nbt", 0).unwrap();
+/// writer.write(PathBuf::from("r.0.0.mca")).unwrap();
+/// ```
+pub struct RegionWriter {
+    compression: CompressionType,
+    chunks: Vec<Option<PendingChunk>>,
+}
+
+impl Default for RegionWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RegionWriter {
+    /// A writer that compresses chunks with zlib.
+    pub fn new() -> Self {
+        Self::with_compression(CompressionType::default())
+    }
+
+    pub fn with_compression(compression: CompressionType) -> Self {
+        RegionWriter {
+            compression,
+            chunks: (0..1024).map(|_| None).collect(),
+        }
+    }
+
+    /// Adds a chunk's uncompressed NBT, replacing any chunk already at that position.
+    ///
+    /// Only the lowest 5 bits of `x` and `z` are used, so world chunk coordinates can be passed
+    /// as they are. `timestamp` is when the chunk was last saved, in seconds since the epoch.
+    pub fn add_chunk(
+        &mut self,
+        x: u32,
+        z: u32,
+        data: &[u8],
+        timestamp: u32,
+    ) -> Result<(), AnvilError> {
+        let data = self.compression.compress(data)?;
+        // 4 bytes of length and 1 of compression type come before the data
+        if (data.len() + 5).div_ceil(SECTOR_SIZE) > MAX_CHUNK_SECTORS {
+            return Err(AnvilError::ChunkTooLarge(x, z));
+        }
+        self.chunks[chunk_index(x, z)] = Some(PendingChunk { timestamp, data });
+        Ok(())
+    }
+
+    /// Whether no chunks have been added.
+    pub fn is_empty(&self) -> bool {
+        self.chunks.iter().all(Option::is_none)
+    }
+
+    /// The whole region file: the location table, the timestamp table, then each chunk padded
+    /// to a whole number of sectors.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut locations = [0; SECTOR_SIZE];
+        let mut timestamps = [0; SECTOR_SIZE];
+        let mut body = Vec::new();
+        for (index, chunk) in self.chunks.iter().enumerate() {
+            let Some(chunk) = chunk else {
+                continue;
+            };
+            let offset = 2 + body.len() / SECTOR_SIZE;
+            body.extend_from_slice(&(chunk.data.len() as u32 + 1).to_be_bytes());
+            body.push(self.compression.id());
+            body.extend_from_slice(&chunk.data);
+            body.resize(body.len().next_multiple_of(SECTOR_SIZE), 0);
+            let sectors = 2 + body.len() / SECTOR_SIZE - offset;
+
+            let location = (offset as u32) << 8 | sectors as u32;
+            locations[index * 4..index * 4 + 4].copy_from_slice(&location.to_be_bytes());
+            timestamps[index * 4..index * 4 + 4].copy_from_slice(&chunk.timestamp.to_be_bytes());
+        }
+
+        let mut bytes = Vec::with_capacity(SECTOR_SIZE * 2 + body.len());
+        bytes.extend_from_slice(&locations);
+        bytes.extend_from_slice(&timestamps);
+        bytes.extend_from_slice(&body);
+        bytes
+    }
+
+    /// Writes the region file, replacing the file if it exists.
+    pub fn write(&self, file_path: PathBuf) -> Result<(), AnvilError> {
+        std::fs::write(&file_path, self.to_bytes())
+            .map_err(|e| AnvilError::UnableToWriteFile(file_path, e))
+    }
+}
+
+/// Index of a chunk in the region file's tables.
+fn chunk_index(x: u32, z: u32) -> usize {
+    ((x & 31) + (z & 31) * 32) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::load_anvil_file;
+    use fastanvil::Region;
+    use std::io::Cursor;
+
+    fn chunk_data(seed: u8) -> Vec<u8> {
+        (0..10_000).map(|i| (i % 7) as u8 ^ seed).collect()
+    }
+
+    #[test]
+    fn test_layout() {
+        let mut writer = RegionWriter::with_compression(CompressionType::None);
+        assert!(writer.is_empty());
+        writer.add_chunk(1, 0, &chunk_data(1), 1234).unwrap();
+        writer.add_chunk(32, 33, &chunk_data(2), 5678).unwrap();
+        assert!(!writer.is_empty());
+        let bytes = writer.to_bytes();
+
+        assert_eq!(bytes.len() % SECTOR_SIZE, 0);
+        // Chunks are written in table order, each taking 3 sectors after the two of the header
+        assert_eq!(bytes[4..8], [0, 0, 2, 3]);
+        assert_eq!(bytes[128..132], [0, 0, 5, 3]);
+        assert_eq!(bytes[4096 + 4..4096 + 8], 1234u32.to_be_bytes());
+        assert_eq!(bytes[4096 + 128..4096 + 132], 5678u32.to_be_bytes());
+        assert_eq!(
+            bytes[2 * SECTOR_SIZE..2 * SECTOR_SIZE + 4],
+            10_001u32.to_be_bytes()
+        );
+        assert_eq!(bytes[2 * SECTOR_SIZE + 4], 3);
+    }
+
+    #[test]
+    fn test_read_back() {
+        for compression in [
+            CompressionType::Gzip,
+            CompressionType::Zlib,
+            CompressionType::None,
+        ] {
+            let mut writer = RegionWriter::with_compression(compression);
+            writer.add_chunk(0, 0, &chunk_data(1), 0).unwrap();
+            writer.add_chunk(5, 31, &chunk_data(2), 0).unwrap();
+
+            let mut region = Region::from_stream(Cursor::new(writer.to_bytes())).unwrap();
+            assert_eq!(region.read_chunk(0, 0).unwrap(), Some(chunk_data(1)));
+            assert_eq!(region.read_chunk(5, 31).unwrap(), Some(chunk_data(2)));
+            assert_eq!(region.read_chunk(1, 0).unwrap(), None);
+
+            let dir = tempfile::tempdir().unwrap();
+            let path = dir.path().join("r.0.0.mca");
+            writer.write(path.clone()).unwrap();
+            let loaded = load_anvil_file(path).unwrap();
+            assert_eq!(loaded.get_locations().len(), 2);
+            assert_eq!(loaded.get_chunk(5, 31).unwrap(), Some(chunk_data(2)));
+        }
+    }
+
+    #[test]
+    fn test_chunk_too_large() {
+        let mut writer = RegionWriter::with_compression(CompressionType::None);
+        let data = vec![0; MAX_CHUNK_SECTORS * SECTOR_SIZE];
+        assert!(matches!(
+            writer.add_chunk(3, 4, &data, 0),
+            Err(AnvilError::ChunkTooLarge(3, 4))
+        ));
+        assert!(writer.is_empty());
+    }
+}
@@ -0,0 +1,395 @@
+use crate::sender::CommandSender;
+use ferrumc_core::identity::player_identity::PlayerIdentity;
+use ferrumc_core::transform::position::Position;
+use ferrumc_ecs::entities::Entity;
+use ferrumc_ecs::Universe;
+
+/// Reads a command's input, one argument at a time.
+#[derive(Clone, Debug)]
+pub struct ArgumentReader<'a> {
+    input: &'a str,
+    cursor: usize,
+}
+
+impl<'a> ArgumentReader<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Self { input, cursor: 0 }
+    }
+
+    /// The byte offset of the next character to read.
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    pub fn remaining(&self) -> &'a str {
+        &self.input[self.cursor..]
+    }
+
+    pub fn is_at_end(&self) -> bool {
+        self.cursor >= self.input.len()
+    }
+
+    pub fn peek(&self) -> Option<char> {
+        self.remaining().chars().next()
+    }
+
+    /// Skips the next character.
+    pub fn skip(&mut self) {
+        if let Some(c) = self.peek() {
+            self.cursor += c.len_utf8();
+        }
+    }
+
+    /// Reads up to the next space, or the end of the input.
+    pub fn read_word(&mut self) -> &'a str {
+        let remaining = self.remaining();
+        let end = remaining.find(' ').unwrap_or(remaining.len());
+        self.cursor += end;
+        &remaining[..end]
+    }
+
+    /// Reads everything left in the input.
+    pub fn read_rest(&mut self) -> &'a str {
+        let remaining = self.remaining();
+        self.cursor = self.input.len();
+        remaining
+    }
+
+    /// Reads a single word, or a string in double quotes which may contain spaces. Quotes and
+    /// backslashes inside quotes are escaped with a backslash.
+    pub fn read_quotable(&mut self) -> Result<String, String> {
+        if self.peek() != Some('"') {
+            return Ok(self.read_word().to_string());
+        }
+        self.skip();
+
+        let mut result = String::new();
+        let mut escaped = false;
+        while let Some(c) = self.peek() {
+            self.skip();
+            match c {
+                _ if escaped => {
+                    if c != '"' && c != '\\' {
+                        return Err(format!("Invalid escape sequence '\\{}'", c));
+                    }
+                    result.push(c);
+                    escaped = false;
+                }
+                '\\' => escaped = true,
+                '"' => return Ok(result),
+                _ => result.push(c),
+            }
+        }
+        Err("Unclosed quoted string".to_string())
+    }
+}
+
+/// How a string argument is read.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StringKind {
+    /// A single word.
+    Word,
+    /// A single word, or a phrase in double quotes.
+    Quotable,
+    /// The rest of the input.
+    Greedy,
+}
+
+/// Parses an argument's input. The variants match the client's argument parsers, so the client
+/// can validate and highlight arguments as they're typed.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ArgumentParser {
+    Bool,
+    Integer { min: Option<i32>, max: Option<i32> },
+    Float { min: Option<f32>, max: Option<f32> },
+    String(StringKind),
+    Entity { single: bool, players_only: bool },
+    BlockPos,
+}
+
+/// A parsed argument.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ArgumentValue {
+    Bool(bool),
+    Integer(i32),
+    Float(f32),
+    String(String),
+    Entities(Vec<Entity>),
+    BlockPos(i32, i32, i32),
+}
+
+impl ArgumentParser {
+    pub fn integer() -> Self {
+        Self::Integer {
+            min: None,
+            max: None,
+        }
+    }
+
+    pub fn integer_range(min: i32, max: i32) -> Self {
+        Self::Integer {
+            min: Some(min),
+            max: Some(max),
+        }
+    }
+
+    pub fn float() -> Self {
+        Self::Float {
+            min: None,
+            max: None,
+        }
+    }
+
+    pub fn float_range(min: f32, max: f32) -> Self {
+        Self::Float {
+            min: Some(min),
+            max: Some(max),
+        }
+    }
+
+    pub fn word() -> Self {
+        Self::String(StringKind::Word)
+    }
+
+    pub fn string() -> Self {
+        Self::String(StringKind::Quotable)
+    }
+
+    pub fn greedy_string() -> Self {
+        Self::String(StringKind::Greedy)
+    }
+
+    /// Exactly one player.
+    pub fn player() -> Self {
+        Self::Entity {
+            single: true,
+            players_only: true,
+        }
+    }
+
+    /// Any number of players.
+    pub fn players() -> Self {
+        Self::Entity {
+            single: false,
+            players_only: true,
+        }
+    }
+
+    /// Any number of entities.
+    pub fn entities() -> Self {
+        Self::Entity {
+            single: false,
+            players_only: false,
+        }
+    }
+
+    /// The parser's ID in the `minecraft:command_argument_type` registry.
+    pub fn id(&self) -> i32 {
+        match self {
+            Self::Bool => 0,
+            Self::Float { .. } => 1,
+            Self::Integer { .. } => 3,
+            Self::String(_) => 5,
+            Self::Entity { .. } => 6,
+            Self::BlockPos => 8,
+        }
+    }
+
+    /// The parser's properties, encoded for the Declare Commands packet.
+    pub fn properties(&self) -> Option<Vec<u8>> {
+        match self {
+            Self::Bool | Self::BlockPos => None,
+            Self::Integer { min, max } => {
+                let mut properties = vec![range_flags(min, max)];
+                for bound in [min, max].into_iter().flatten() {
+                    properties.extend_from_slice(&bound.to_be_bytes());
+                }
+                Some(properties)
+            }
+            Self::Float { min, max } => {
+                let mut properties = vec![range_flags(min, max)];
+                for bound in [min, max].into_iter().flatten() {
+                    properties.extend_from_slice(&bound.to_be_bytes());
+                }
+                Some(properties)
+            }
+            // A VarInt, always a single byte here
+            Self::String(kind) => Some(vec![*kind as u8]),
+            Self::Entity {
+                single,
+                players_only,
+            } => Some(vec![u8::from(*single) | (u8::from(*players_only) << 1)]),
+        }
+    }
+
+    /// Parsers that ask the server for suggestions instead of completing on the client.
+    pub fn suggestions_type(&self) -> Option<&'static str> {
+        match self {
+            Self::Entity { .. } => Some("minecraft:ask_server"),
+            _ => None,
+        }
+    }
+
+    /// Parses an argument from the reader. On failure, returns a message for the sender.
+    pub fn parse(
+        &self,
+        reader: &mut ArgumentReader,
+        sender: &CommandSender,
+        universe: &Universe,
+    ) -> Result<ArgumentValue, String> {
+        match self {
+            Self::Bool => match reader.read_word() {
+                "true" => Ok(ArgumentValue::Bool(true)),
+                "false" => Ok(ArgumentValue::Bool(false)),
+                word => Err(format!("Expected true or false, found '{}'", word)),
+            },
+            Self::Integer { min, max } => {
+                let word = reader.read_word();
+                let value = word
+                    .parse::<i32>()
+                    .map_err(|_| format!("Invalid integer '{}'", word))?;
+                check_range(value, min, max)?;
+                Ok(ArgumentValue::Integer(value))
+            }
+            Self::Float { min, max } => {
+                let word = reader.read_word();
+                let value = word
+                    .parse::<f32>()
+                    .ok()
+                    .filter(|value| value.is_finite())
+                    .ok_or_else(|| format!("Invalid float '{}'", word))?;
+                check_range(value, min, max)?;
+                Ok(ArgumentValue::Float(value))
+            }
+            Self::String(kind) => {
+                let value = match kind {
+                    StringKind::Word => reader.read_word().to_string(),
+                    StringKind::Quotable => reader.read_quotable()?,
+                    StringKind::Greedy => reader.read_rest().to_string(),
+                };
+                if value.is_empty() && *kind != StringKind::Quotable {
+                    return Err("Expected a string".to_string());
+                }
+                Ok(ArgumentValue::String(value))
+            }
+            Self::Entity { single, .. } => {
+                let entities = select_entities(reader.read_word(), sender, universe)?;
+                if entities.is_empty() {
+                    return Err("No player was found".to_string());
+                }
+                if *single && entities.len() > 1 {
+                    return Err(
+                        "Only one player is allowed, but the selector allows more than one"
+                            .to_string(),
+                    );
+                }
+                Ok(ArgumentValue::Entities(entities))
+            }
+            Self::BlockPos => {
+                // The console's relative coordinates start at the origin
+                let origin = sender
+                    .entity()
+                    .and_then(|entity| universe.get::<Position>(entity).ok())
+                    .map(|position| [position.x, position.y, position.z])
+                    .unwrap_or_default();
+                let mut coordinates = [0; 3];
+                for (i, coordinate) in coordinates.iter_mut().enumerate() {
+                    if i > 0 {
+                        if reader.peek() != Some(' ') {
+                            return Err(
+                                "Incomplete block position, expected 3 coordinates".to_string()
+                            );
+                        }
+                        reader.skip();
+                    }
+                    *coordinate = parse_coordinate(reader.read_word(), origin[i])?;
+                }
+                let [x, y, z] = coordinates;
+                Ok(ArgumentValue::BlockPos(x, y, z))
+            }
+        }
+    }
+
+    /// Suggestions for a partially typed argument.
+    pub fn suggest(&self, partial: &str, universe: &Universe) -> Vec<String> {
+        let candidates = match self {
+            Self::Bool => vec!["true".to_string(), "false".to_string()],
+            Self::Entity { players_only, .. } => {
+                let mut candidates = universe
+                    .query::<&PlayerIdentity>()
+                    .map(|(_, identity)| identity.username.clone())
+                    .collect::<Vec<_>>();
+                candidates.sort();
+                candidates.extend(["@a", "@p", "@s"].map(String::from));
+                if !players_only {
+                    candidates.push("@e".to_string());
+                }
+                candidates
+            }
+            _ => vec![],
+        };
+        candidates
+            .into_iter()
+            .filter(|candidate| {
+                candidate
+                    .to_lowercase()
+                    .starts_with(&partial.to_lowercase())
+            })
+            .collect()
+    }
+}
+
+fn range_flags<T>(min: &Option<T>, max: &Option<T>) -> u8 {
+    u8::from(min.is_some()) | (u8::from(max.is_some()) << 1)
+}
+
+fn check_range<T: PartialOrd + std::fmt::Display>(
+    value: T,
+    min: &Option<T>,
+    max: &Option<T>,
+) -> Result<(), String> {
+    if let Some(min) = min.as_ref().filter(|min| value < **min) {
+        return Err(format!("Must not be less than {}, found {}", min, value));
+    }
+    if let Some(max) = max.as_ref().filter(|max| value > **max) {
+        return Err(format!("Must not be more than {}, found {}", max, value));
+    }
+    Ok(())
+}
+
+/// Parses a block coordinate, which is either absolute or relative to `origin` with `~`.
+fn parse_coordinate(word: &str, origin: f64) -> Result<i32, String> {
+    let invalid = || format!("Invalid coordinate '{}'", word);
+    match word.strip_prefix('~') {
+        Some("") => Ok(origin.floor() as i32),
+        Some(offset) => {
+            let offset = offset.parse::<i32>().map_err(|_| invalid())?;
+            Ok(origin.floor() as i32 + offset)
+        }
+        None => word.parse::<i32>().map_err(|_| invalid()),
+    }
+}
+
+/// Resolves a target selector or a player name.
+///
+/// Players are the only entities so far, so `@e` selects every player and `@p` (the nearest
+/// player) is the sender themselves.
+fn select_entities(
+    selector: &str,
+    sender: &CommandSender,
+    universe: &Universe,
+) -> Result<Vec<Entity>, String> {
+    let players = universe.query::<&PlayerIdentity>();
+    match selector {
+        "@s" | "@p" => sender
+            .entity()
+            .map(|entity| vec![entity])
+            .ok_or_else(|| format!("Only players can use '{}'", selector)),
+        "@a" | "@e" => Ok(players.map(|(entity, _)| entity).collect()),
+        _ if selector.starts_with('@') => Err(format!("Unknown selector type '{}'", selector)),
+        name => Ok(players
+            .filter(|(_, identity)| identity.username.eq_ignore_ascii_case(name))
+            .map(|(entity, _)| entity)
+            .collect()),
+    }
+}
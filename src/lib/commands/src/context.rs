@@ -0,0 +1,79 @@
+use crate::arguments::ArgumentValue;
+use crate::errors::CommandError;
+use crate::sender::CommandSender;
+use crate::CommandResult;
+use ferrumc_ecs::entities::Entity;
+use ferrumc_net::utils::chat::send_message;
+use ferrumc_state::GlobalState;
+use ferrumc_text::TextComponent;
+use std::collections::HashMap;
+use tracing::info;
+
+/// Everything an executor gets when its command is run.
+pub struct CommandContext {
+    pub sender: CommandSender,
+    pub state: GlobalState,
+    /// The full command, without the leading slash.
+    pub input: String,
+    pub arguments: HashMap<String, ArgumentValue>,
+}
+
+impl CommandContext {
+    pub fn argument(&self, name: &str) -> Result<&ArgumentValue, CommandError> {
+        self.arguments
+            .get(name)
+            .ok_or_else(|| CommandError::MissingArgument(name.to_string()))
+    }
+
+    pub fn get_bool(&self, name: &str) -> Result<bool, CommandError> {
+        match self.argument(name)? {
+            ArgumentValue::Bool(value) => Ok(*value),
+            _ => Err(CommandError::MissingArgument(name.to_string())),
+        }
+    }
+
+    pub fn get_integer(&self, name: &str) -> Result<i32, CommandError> {
+        match self.argument(name)? {
+            ArgumentValue::Integer(value) => Ok(*value),
+            _ => Err(CommandError::MissingArgument(name.to_string())),
+        }
+    }
+
+    pub fn get_float(&self, name: &str) -> Result<f32, CommandError> {
+        match self.argument(name)? {
+            ArgumentValue::Float(value) => Ok(*value),
+            _ => Err(CommandError::MissingArgument(name.to_string())),
+        }
+    }
+
+    pub fn get_string(&self, name: &str) -> Result<&str, CommandError> {
+        match self.argument(name)? {
+            ArgumentValue::String(value) => Ok(value),
+            _ => Err(CommandError::MissingArgument(name.to_string())),
+        }
+    }
+
+    pub fn get_entities(&self, name: &str) -> Result<&[Entity], CommandError> {
+        match self.argument(name)? {
+            ArgumentValue::Entities(value) => Ok(value),
+            _ => Err(CommandError::MissingArgument(name.to_string())),
+        }
+    }
+
+    pub fn get_block_pos(&self, name: &str) -> Result<(i32, i32, i32), CommandError> {
+        match self.argument(name)? {
+            ArgumentValue::BlockPos(x, y, z) => Ok((*x, *y, *z)),
+            _ => Err(CommandError::MissingArgument(name.to_string())),
+        }
+    }
+
+    /// Sends a message to the sender. Replies to the console are logged as plain text.
+    pub async fn reply(&self, message: impl Into<TextComponent>) -> CommandResult {
+        let message = message.into();
+        match self.sender {
+            CommandSender::Player(entity) => send_message(&self.state, entity, message).await?,
+            CommandSender::Console => info!("{}", message.to_plain_text()),
+        }
+        Ok(())
+    }
+}
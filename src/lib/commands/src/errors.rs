@@ -0,0 +1,23 @@
+use ferrumc_ecs::errors::ECSError;
+use ferrumc_net::errors::NetError;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum CommandError {
+    #[error("Unknown command")]
+    UnknownCommand,
+    #[error("Incorrect argument for command")]
+    IncorrectArgument,
+    #[error("Incomplete command")]
+    IncompleteCommand,
+    #[error("Invalid argument '{0}': {1}")]
+    InvalidArgument(String, String),
+    #[error("Missing argument: {0}")]
+    MissingArgument(String),
+    #[error("{0}")]
+    Failed(String),
+    #[error("Network error: {0}")]
+    NetError(#[from] NetError),
+    #[error("ECS error: {0}")]
+    ECSError(#[from] ECSError),
+}
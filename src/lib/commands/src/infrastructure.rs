@@ -0,0 +1,264 @@
+use crate::arguments::{ArgumentReader, ArgumentValue};
+use crate::context::CommandContext;
+use crate::errors::CommandError;
+use crate::nodes::{CommandNode, Executor, NodeKind};
+use crate::sender::CommandSender;
+use crate::CommandResult;
+use ferrumc_ecs::Universe;
+use ferrumc_net::packets::outgoing::declare_commands::{CommandNodeData, DeclareCommandsPacket};
+use ferrumc_net_codec::net_types::var_int::VarInt;
+use ferrumc_state::GlobalState;
+use parking_lot::RwLock;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+/// The commands registered with `#[command]`.
+static DISPATCHER: LazyLock<CommandDispatcher> = LazyLock::new(CommandDispatcher::new);
+
+/// Registers a command with the global dispatcher. This is what `#[command]` calls.
+pub fn register_command(command: CommandNode) {
+    DISPATCHER.register(command);
+}
+
+/// The global dispatcher.
+pub fn dispatcher() -> &'static CommandDispatcher {
+    &DISPATCHER
+}
+
+/// Suggestions for the last word of a partially typed command.
+#[derive(Debug, Default, PartialEq)]
+pub struct Suggestions {
+    /// Byte offset of the word being completed.
+    pub start: usize,
+    /// Length of the word being completed.
+    pub length: usize,
+    pub matches: Vec<String>,
+}
+
+/// Holds the command tree, and parses, runs and completes commands against it.
+pub struct CommandDispatcher {
+    root: RwLock<CommandNode>,
+}
+
+impl Default for CommandDispatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CommandDispatcher {
+    pub fn new() -> Self {
+        Self {
+            root: RwLock::new(CommandNode::root()),
+        }
+    }
+
+    /// Adds a command, replacing any command with the same name.
+    pub fn register(&self, command: CommandNode) {
+        let mut root = self.root.write();
+        root.children.retain(|child| child.name() != command.name());
+        root.children.push(command);
+    }
+
+    /// Parses a command (without the leading slash), returning the executor to run and the
+    /// parsed arguments.
+    pub fn parse(
+        &self,
+        input: &str,
+        sender: &CommandSender,
+        universe: &Universe,
+    ) -> Result<(Executor, HashMap<String, ArgumentValue>), CommandError> {
+        let root = self.root.read();
+        let mut arguments = HashMap::new();
+        let executor = parse_children(
+            &root,
+            ArgumentReader::new(input),
+            sender,
+            universe,
+            &mut arguments,
+        )?;
+        Ok((executor, arguments))
+    }
+
+    /// Parses and runs a command (without the leading slash).
+    pub async fn execute(
+        &self,
+        input: &str,
+        sender: CommandSender,
+        state: GlobalState,
+    ) -> CommandResult {
+        let (executor, arguments) = self.parse(input, &sender, &state.universe)?;
+        executor(CommandContext {
+            sender,
+            state,
+            input: input.to_string(),
+            arguments,
+        })
+        .await
+    }
+
+    /// Suggestions for the last word of a partially typed command (without the leading slash).
+    pub fn suggest(&self, input: &str, sender: &CommandSender, universe: &Universe) -> Suggestions {
+        let root = self.root.read();
+        let mut found = Vec::new();
+        suggest_children(
+            &root,
+            ArgumentReader::new(input),
+            sender,
+            universe,
+            &mut found,
+        );
+
+        // Different branches can be completing different words, so keep the furthest along.
+        let start = found.iter().map(|(start, _)| *start).max().unwrap_or(0);
+        let mut matches = found
+            .into_iter()
+            .filter(|(match_start, _)| *match_start == start)
+            .map(|(_, text)| text)
+            .collect::<Vec<_>>();
+        matches.dedup();
+
+        Suggestions {
+            start,
+            length: input.len() - start,
+            matches,
+        }
+    }
+
+    /// The command tree the sender can use, as a Declare Commands packet.
+    pub fn declare_commands_packet(
+        &self,
+        sender: &CommandSender,
+        universe: &Universe,
+    ) -> DeclareCommandsPacket {
+        let root = self.root.read();
+        let mut nodes = Vec::new();
+        let root_index = flatten(&root, sender, universe, &mut nodes);
+        DeclareCommandsPacket::new(nodes, root_index)
+    }
+}
+
+/// Tries each child of `node` against the rest of the input, depth first, returning the
+/// executor of the first branch that uses up the whole input.
+fn parse_children(
+    node: &CommandNode,
+    reader: ArgumentReader,
+    sender: &CommandSender,
+    universe: &Universe,
+    arguments: &mut HashMap<String, ArgumentValue>,
+) -> Result<Executor, CommandError> {
+    let mut error = match node.kind {
+        NodeKind::Root => CommandError::UnknownCommand,
+        _ => CommandError::IncorrectArgument,
+    };
+
+    for child in node.usable_children(sender, universe) {
+        let mut reader = reader.clone();
+        match &child.kind {
+            NodeKind::Literal(name) => {
+                if reader.read_word() != name {
+                    continue;
+                }
+            }
+            NodeKind::Argument { name, parser } => {
+                match parser.parse(&mut reader, sender, universe) {
+                    Ok(value) => {
+                        arguments.insert(name.clone(), value);
+                    }
+                    Err(e) => {
+                        error = CommandError::InvalidArgument(name.clone(), e);
+                        continue;
+                    }
+                }
+            }
+            NodeKind::Root => continue,
+        }
+
+        let result = if reader.is_at_end() {
+            child
+                .executor
+                .clone()
+                .ok_or(CommandError::IncompleteCommand)
+        } else if reader.peek() == Some(' ') {
+            reader.skip();
+            parse_children(child, reader, sender, universe, arguments)
+        } else {
+            // Something like a literal followed by more characters
+            Err(CommandError::IncorrectArgument)
+        };
+
+        match result {
+            Ok(executor) => return Ok(executor),
+            Err(e) => {
+                if let NodeKind::Argument { name, .. } = &child.kind {
+                    arguments.remove(name);
+                }
+                error = e;
+            }
+        }
+    }
+
+    Err(error)
+}
+
+/// Collects suggestions from every branch that the input could be continuing, as
+/// (start, suggestion) pairs.
+fn suggest_children(
+    node: &CommandNode,
+    reader: ArgumentReader,
+    sender: &CommandSender,
+    universe: &Universe,
+    found: &mut Vec<(usize, String)>,
+) {
+    let remaining = reader.remaining();
+
+    for child in node.usable_children(sender, universe) {
+        // The last word is still being typed
+        if !remaining.contains(' ') {
+            let suggestions = match &child.kind {
+                NodeKind::Literal(name) if name.starts_with(remaining) => vec![name.clone()],
+                NodeKind::Argument { parser, .. } => parser.suggest(remaining, universe),
+                _ => vec![],
+            };
+            found.extend(
+                suggestions
+                    .into_iter()
+                    .map(|suggestion| (reader.cursor(), suggestion)),
+            );
+            continue;
+        }
+
+        let mut reader = reader.clone();
+        let parsed = match &child.kind {
+            NodeKind::Literal(name) => reader.read_word() == name,
+            NodeKind::Argument { parser, .. } => {
+                parser.parse(&mut reader, sender, universe).is_ok()
+            }
+            NodeKind::Root => false,
+        };
+        if parsed && reader.peek() == Some(' ') {
+            reader.skip();
+            suggest_children(child, reader, sender, universe, found);
+        }
+    }
+}
+
+/// Adds the node and the children the sender can use to `nodes`, returning the node's index.
+fn flatten(
+    node: &CommandNode,
+    sender: &CommandSender,
+    universe: &Universe,
+    nodes: &mut Vec<CommandNodeData>,
+) -> usize {
+    let index = nodes.len();
+    // Placeholder until the children's indexes are known
+    nodes.push(node.to_data(vec![]));
+
+    let children = node
+        .usable_children(sender, universe)
+        .map(|child| VarInt::new(flatten(child, sender, universe, nodes) as i32))
+        .collect();
+    nodes[index] = node.to_data(children);
+
+    index
+}
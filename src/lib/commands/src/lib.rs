@@ -0,0 +1,22 @@
+//! A Brigadier-style command framework.
+//!
+//! Commands are trees of literal and argument nodes, built with [nodes::literal] and
+//! [nodes::argument], and registered with the `#[command]` attribute:
+//!
+//! ```ignore
+//! #[command]
+//! fn hello() -> CommandNode {
+//!     literal("hello").executes(|ctx| async move { ctx.reply("Hello!").await })
+//! }
+//! ```
+pub mod arguments;
+pub mod context;
+pub mod errors;
+pub mod infrastructure;
+pub mod nodes;
+pub mod sender;
+
+#[cfg(test)]
+mod tests;
+
+pub type CommandResult = Result<(), errors::CommandError>;
@@ -0,0 +1,149 @@
+use crate::arguments::ArgumentParser;
+use crate::context::CommandContext;
+use crate::sender::CommandSender;
+use crate::CommandResult;
+use ferrumc_ecs::Universe;
+use ferrumc_net::packets::outgoing::declare_commands::CommandNodeData;
+use ferrumc_net_codec::net_types::length_prefixed_vec::LengthPrefixedVec;
+use ferrumc_net_codec::net_types::var_int::VarInt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+pub type Executor = Arc<
+    dyn Fn(CommandContext) -> Pin<Box<dyn Future<Output = CommandResult> + Send>> + Send + Sync,
+>;
+pub type Requirement = Arc<dyn Fn(&CommandSender, &Universe) -> bool + Send + Sync>;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum NodeKind {
+    Root,
+    Literal(String),
+    Argument {
+        name: String,
+        parser: ArgumentParser,
+    },
+}
+
+/// A node of a command tree.
+///
+/// A command runs the executor of the last node its input reaches, so every node that can end a
+/// command needs one.
+#[derive(Clone)]
+pub struct CommandNode {
+    pub kind: NodeKind,
+    pub children: Vec<CommandNode>,
+    pub executor: Option<Executor>,
+    pub requirement: Option<Requirement>,
+}
+
+/// A node matching a fixed word, such as the command's name.
+pub fn literal(name: impl Into<String>) -> CommandNode {
+    CommandNode::new(NodeKind::Literal(name.into()))
+}
+
+/// A node parsing an argument, available to the executor by its name.
+pub fn argument(name: impl Into<String>, parser: ArgumentParser) -> CommandNode {
+    CommandNode::new(NodeKind::Argument {
+        name: name.into(),
+        parser,
+    })
+}
+
+impl CommandNode {
+    fn new(kind: NodeKind) -> Self {
+        Self {
+            kind,
+            children: vec![],
+            executor: None,
+            requirement: None,
+        }
+    }
+
+    pub fn root() -> Self {
+        Self::new(NodeKind::Root)
+    }
+
+    /// Adds a child node.
+    pub fn then(mut self, child: CommandNode) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    /// Sets the function run when a command ends at this node.
+    pub fn executes<F, Fut>(mut self, executor: F) -> Self
+    where
+        F: Fn(CommandContext) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = CommandResult> + Send + 'static,
+    {
+        self.executor = Some(Arc::new(move |ctx| Box::pin(executor(ctx))));
+        self
+    }
+
+    /// Only lets senders that pass the check use this node and its children. Nodes a sender
+    /// can't use aren't sent to their client either.
+    pub fn requires<F>(mut self, requirement: F) -> Self
+    where
+        F: Fn(&CommandSender, &Universe) -> bool + Send + Sync + 'static,
+    {
+        self.requirement = Some(Arc::new(requirement));
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        match &self.kind {
+            NodeKind::Root => "",
+            NodeKind::Literal(name) => name,
+            NodeKind::Argument { name, .. } => name,
+        }
+    }
+
+    pub fn can_use(&self, sender: &CommandSender, universe: &Universe) -> bool {
+        self.requirement
+            .as_ref()
+            .is_none_or(|requirement| requirement(sender, universe))
+    }
+
+    /// The children the sender is allowed to use.
+    pub fn usable_children<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        universe: &'a Universe,
+    ) -> impl Iterator<Item = &'a CommandNode> + 'a {
+        self.children
+            .iter()
+            .filter(move |child| child.can_use(sender, universe))
+    }
+
+    /// This node as sent in the Declare Commands packet, given the indexes of its children.
+    pub fn to_data(&self, children: Vec<VarInt>) -> CommandNodeData {
+        let mut flags = match self.kind {
+            NodeKind::Root => CommandNodeData::ROOT,
+            NodeKind::Literal(_) => CommandNodeData::LITERAL,
+            NodeKind::Argument { .. } => CommandNodeData::ARGUMENT,
+        };
+        if self.executor.is_some() {
+            flags |= CommandNodeData::EXECUTABLE;
+        }
+
+        let (name, parser) = match &self.kind {
+            NodeKind::Root => (None, None),
+            NodeKind::Literal(name) => (Some(name.clone()), None),
+            NodeKind::Argument { name, parser } => (Some(name.clone()), Some(parser)),
+        };
+        let suggestions_type = parser.and_then(ArgumentParser::suggestions_type);
+        if suggestions_type.is_some() {
+            flags |= CommandNodeData::HAS_SUGGESTIONS_TYPE;
+        }
+
+        CommandNodeData {
+            flags,
+            children: LengthPrefixedVec::new(children),
+            redirect_node: None,
+            name,
+            parser_id: parser.map(|parser| VarInt::new(parser.id())),
+            properties: parser.and_then(ArgumentParser::properties),
+            suggestions_type: suggestions_type.map(String::from),
+        }
+    }
+}
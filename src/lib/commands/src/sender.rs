@@ -0,0 +1,45 @@
+use ferrumc_core::identity::player_identity::PlayerIdentity;
+use ferrumc_ecs::entities::Entity;
+use ferrumc_ecs::Universe;
+use std::fmt::{Display, Formatter};
+
+/// Who ran a command.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CommandSender {
+    Player(Entity),
+    Console,
+}
+
+impl CommandSender {
+    /// The sender's entity, if it has one.
+    pub fn entity(&self) -> Option<Entity> {
+        match self {
+            Self::Player(entity) => Some(*entity),
+            Self::Console => None,
+        }
+    }
+
+    pub fn is_console(&self) -> bool {
+        matches!(self, Self::Console)
+    }
+
+    /// The name shown to other players, such as in `/say`.
+    pub fn name(&self, universe: &Universe) -> String {
+        match self {
+            Self::Player(entity) => universe
+                .get::<PlayerIdentity>(*entity)
+                .map(|identity| identity.username.clone())
+                .unwrap_or_else(|_| format!("Entity {}", entity)),
+            Self::Console => "Server".to_string(),
+        }
+    }
+}
+
+impl Display for CommandSender {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Player(entity) => write!(f, "entity {}", entity),
+            Self::Console => write!(f, "the console"),
+        }
+    }
+}
@@ -0,0 +1,185 @@
+use crate::arguments::{ArgumentParser, ArgumentReader, ArgumentValue};
+use crate::errors::CommandError;
+use crate::infrastructure::CommandDispatcher;
+use crate::nodes::{argument, literal, CommandNode};
+use crate::sender::CommandSender;
+use ferrumc_core::identity::player_identity::PlayerIdentity;
+use ferrumc_ecs::Universe;
+use ferrumc_net::packets::outgoing::declare_commands::CommandNodeData;
+
+fn command() -> CommandNode {
+    literal("give").then(
+        argument("target", ArgumentParser::player()).then(
+            argument("count", ArgumentParser::integer_range(1, 64))
+                .executes(|_| async { Ok(()) })
+                .then(
+                    argument("note", ArgumentParser::greedy_string())
+                        .executes(|_| async { Ok(()) }),
+                ),
+        ),
+    )
+}
+
+fn setup() -> (CommandDispatcher, Universe, CommandSender) {
+    let dispatcher = CommandDispatcher::new();
+    dispatcher.register(command());
+    dispatcher.register(
+        literal("stop")
+            .requires(|_, _| false)
+            .executes(|_| async { Ok(()) }),
+    );
+
+    let universe = Universe::new();
+    let sender = universe.create_entity();
+    universe
+        .add_component(sender, PlayerIdentity::new("Steve".to_string(), 1))
+        .unwrap();
+    let other = universe.create_entity();
+    universe
+        .add_component(other, PlayerIdentity::new("Alex".to_string(), 2))
+        .unwrap();
+
+    (dispatcher, universe, CommandSender::Player(sender))
+}
+
+#[test]
+fn test_parse_arguments() {
+    let (dispatcher, universe, sender) = setup();
+
+    let (_, arguments) = dispatcher.parse("give @s 5", &sender, &universe).unwrap();
+    assert_eq!(
+        arguments.get("target"),
+        Some(&ArgumentValue::Entities(vec![sender.entity().unwrap()]))
+    );
+    assert_eq!(arguments.get("count"), Some(&ArgumentValue::Integer(5)));
+
+    let (_, arguments) = dispatcher
+        .parse("give alex 64 for the road", &sender, &universe)
+        .unwrap();
+    assert_eq!(
+        arguments.get("note"),
+        Some(&ArgumentValue::String("for the road".to_string()))
+    );
+}
+
+#[test]
+fn test_parse_errors() {
+    let (dispatcher, universe, sender) = setup();
+
+    let error = |input| dispatcher.parse(input, &sender, &universe).err().unwrap();
+    assert!(matches!(error("take"), CommandError::UnknownCommand));
+    assert!(matches!(error("give @s"), CommandError::IncompleteCommand));
+    assert!(matches!(
+        error("give @s 65"),
+        CommandError::InvalidArgument(name, _) if name == "count"
+    ));
+    assert!(matches!(
+        error("give @a 1"),
+        CommandError::InvalidArgument(name, _) if name == "target"
+    ));
+    assert!(matches!(error("givex"), CommandError::UnknownCommand));
+    // Hidden by its requirement
+    assert!(matches!(error("stop"), CommandError::UnknownCommand));
+}
+
+#[test]
+fn test_suggestions() {
+    let (dispatcher, universe, sender) = setup();
+
+    let suggestions = dispatcher.suggest("gi", &sender, &universe);
+    assert_eq!(suggestions.start, 0);
+    assert_eq!(suggestions.length, 2);
+    assert_eq!(suggestions.matches, vec!["give"]);
+
+    let suggestions = dispatcher.suggest("give ", &sender, &universe);
+    assert_eq!(suggestions.start, 5);
+    assert_eq!(suggestions.matches, vec!["Alex", "Steve", "@a", "@p", "@s"]);
+
+    let suggestions = dispatcher.suggest("give st", &sender, &universe);
+    assert_eq!(suggestions.matches, vec!["Steve"]);
+
+    assert!(dispatcher
+        .suggest("st", &sender, &universe)
+        .matches
+        .is_empty());
+}
+
+#[test]
+fn test_declare_commands() {
+    let (dispatcher, universe, sender) = setup();
+
+    let packet = dispatcher.declare_commands_packet(&sender, &universe);
+    let nodes = &packet.nodes.data;
+    // root, give, target, count, note; stop is hidden
+    assert_eq!(nodes.len(), 5);
+    assert_eq!(packet.root_index.val, 0);
+    assert_eq!(nodes[0].flags, CommandNodeData::ROOT);
+    assert_eq!(nodes[1].name.as_deref(), Some("give"));
+    assert_eq!(
+        nodes[2].flags,
+        CommandNodeData::ARGUMENT | CommandNodeData::HAS_SUGGESTIONS_TYPE
+    );
+    assert_eq!(nodes[2].properties, Some(vec![0x03]));
+    assert_eq!(
+        nodes[3].flags,
+        CommandNodeData::ARGUMENT | CommandNodeData::EXECUTABLE
+    );
+    assert_eq!(
+        nodes[3].properties,
+        Some(vec![0x03, 0, 0, 0, 1, 0, 0, 0, 64])
+    );
+    assert_eq!(
+        nodes[3]
+            .children
+            .data
+            .iter()
+            .map(|c| c.val)
+            .collect::<Vec<_>>(),
+        vec![4]
+    );
+}
+
+#[test]
+fn test_block_pos_and_quoted_strings() {
+    let universe = Universe::new();
+    let entity = universe.create_entity();
+    universe
+        .add_component(
+            entity,
+            ferrumc_core::transform::position::Position::new(10.5, 64.0, -3.2),
+        )
+        .unwrap();
+    let sender = CommandSender::Player(entity);
+
+    let mut reader = ArgumentReader::new("~ ~-1 5");
+    assert_eq!(
+        ArgumentParser::BlockPos.parse(&mut reader, &sender, &universe),
+        Ok(ArgumentValue::BlockPos(10, 63, 5))
+    );
+    assert!(reader.is_at_end());
+
+    let mut reader = ArgumentReader::new(r#""hello \"world\"" rest"#);
+    assert_eq!(
+        ArgumentParser::string().parse(&mut reader, &sender, &universe),
+        Ok(ArgumentValue::String("hello \"world\"".to_string()))
+    );
+    assert_eq!(reader.remaining(), " rest");
+}
+
+#[test]
+fn test_console_sender() {
+    let (dispatcher, universe, _) = setup();
+    let console = CommandSender::Console;
+
+    assert!(dispatcher.parse("give alex 1", &console, &universe).is_ok());
+    assert!(matches!(
+        dispatcher.parse("give @s 1", &console, &universe),
+        Err(CommandError::InvalidArgument(name, _)) if name == "target"
+    ));
+
+    let mut reader = ArgumentReader::new("~1 ~ ~");
+    assert_eq!(
+        ArgumentParser::BlockPos.parse(&mut reader, &console, &universe),
+        Ok(ArgumentValue::BlockPos(1, 0, 0))
+    );
+}
@@ -0,0 +1,138 @@
+use std::collections::HashSet;
+
+/// The chunks a player's client currently has loaded, and the view they were loaded for.
+///
+/// The chunk sender diffs this against the player's current view, so only chunks that enter
+/// the view are sent and only chunks that leave it are unloaded.
+#[derive(Debug, Default)]
+pub struct LoadedChunks {
+    pub chunks: HashSet<(i32, i32)>,
+    /// The center chunk of the last update, if there has been one.
+    pub center: Option<(i32, i32)>,
+    /// The view distance (in chunks) of the last update.
+    pub radius: i32,
+}
+
+/// The result of [LoadedChunks::update].
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ChunkViewChange {
+    /// Chunks that entered the view, closest to the center first.
+    pub load: Vec<(i32, i32)>,
+    /// Chunks that left the view.
+    pub unload: Vec<(i32, i32)>,
+    /// Whether the center chunk moved.
+    pub center_changed: bool,
+}
+
+impl ChunkViewChange {
+    pub fn is_empty(&self) -> bool {
+        self.load.is_empty() && self.unload.is_empty() && !self.center_changed
+    }
+}
+
+impl LoadedChunks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether the chunk at (`x`, `z`) is within `radius` chunks of `center`.
+    ///
+    /// Same cylindrical check as vanilla, so the corners of the square aren't sent.
+    pub fn is_in_view(center: (i32, i32), radius: i32, (x, z): (i32, i32)) -> bool {
+        let dx = i64::from(((x - center.0).abs() - 1).max(0));
+        let dz = i64::from(((z - center.1).abs() - 1).max(0));
+        dx * dx + dz * dz < i64::from(radius) * i64::from(radius)
+    }
+
+    /// Moves the view to `center` with the given `radius`, marking chunks that entered it as
+    /// loaded and chunks that left it as unloaded, and returns what changed.
+    pub fn update(&mut self, center: (i32, i32), radius: i32) -> ChunkViewChange {
+        if self.center == Some(center) && self.radius == radius {
+            return ChunkViewChange::default();
+        }
+
+        let unload: Vec<_> = self
+            .chunks
+            .iter()
+            .copied()
+            .filter(|&chunk| !Self::is_in_view(center, radius, chunk))
+            .collect();
+        for chunk in &unload {
+            self.chunks.remove(chunk);
+        }
+
+        let mut load = Vec::new();
+        for x in center.0 - radius..=center.0 + radius {
+            for z in center.1 - radius..=center.1 + radius {
+                if Self::is_in_view(center, radius, (x, z)) && self.chunks.insert((x, z)) {
+                    load.push((x, z));
+                }
+            }
+        }
+        load.sort_by_key(|&(x, z)| {
+            let (dx, dz) = (x - center.0, z - center.1);
+            dx * dx + dz * dz
+        });
+
+        let center_changed = self.center != Some(center);
+        self.center = Some(center);
+        self.radius = radius;
+
+        ChunkViewChange {
+            load,
+            unload,
+            center_changed,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_initial_update_loads_view() {
+        let mut loaded = LoadedChunks::new();
+        let change = loaded.update((0, 0), 2);
+
+        assert!(change.center_changed);
+        assert!(change.unload.is_empty());
+        assert_eq!(change.load[0], (0, 0));
+        assert_eq!(change.load.len(), loaded.chunks.len());
+        assert!(loaded.chunks.contains(&(2, 2)));
+        assert!(loaded.update((0, 0), 2).is_empty());
+    }
+
+    #[test]
+    fn test_moving_only_sends_the_difference() {
+        let mut loaded = LoadedChunks::new();
+        loaded.update((0, 0), 4);
+        let before = loaded.chunks.clone();
+
+        let change = loaded.update((1, 0), 4);
+        assert!(change.center_changed);
+        for chunk in &change.load {
+            assert!(!before.contains(chunk));
+        }
+        for chunk in &change.unload {
+            assert!(before.contains(chunk));
+            assert!(!loaded.chunks.contains(chunk));
+        }
+        assert!(change.load.iter().all(|&(x, _)| x == 5 || x == 4));
+    }
+
+    #[test]
+    fn test_shrinking_radius_unloads() {
+        let mut loaded = LoadedChunks::new();
+        loaded.update((0, 0), 8);
+        let change = loaded.update((0, 0), 2);
+
+        assert!(!change.center_changed);
+        assert!(change.load.is_empty());
+        assert!(loaded
+            .chunks
+            .iter()
+            .all(|&chunk| LoadedChunks::is_in_view((0, 0), 2, chunk)));
+        assert!(change.unload.contains(&(8, 0)));
+    }
+}
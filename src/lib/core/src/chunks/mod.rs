@@ -0,0 +1 @@
+pub mod loaded_chunks;
@@ -0,0 +1,127 @@
+use crate::errors::CoreError;
+use std::fmt;
+use std::str::FromStr;
+
+/// One of the world's dimensions. As a component, the dimension a player is in.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Dimension {
+    #[default]
+    Overworld,
+    Nether,
+    End,
+}
+
+impl Dimension {
+    pub const ALL: [Dimension; 3] = [Dimension::Overworld, Dimension::Nether, Dimension::End];
+
+    /// The name without the `minecraft:` namespace, as used in commands and to key chunks in
+    /// the world.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Overworld => "overworld",
+            Self::Nether => "the_nether",
+            Self::End => "the_end",
+        }
+    }
+
+    /// The dimension's identifier, as used in packets.
+    pub const fn identifier(self) -> &'static str {
+        match self {
+            Self::Overworld => "minecraft:overworld",
+            Self::Nether => "minecraft:the_nether",
+            Self::End => "minecraft:the_end",
+        }
+    }
+
+    /// Looks a dimension up by its name, with or without the `minecraft:` namespace.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim_start_matches("minecraft:");
+        Self::ALL
+            .into_iter()
+            .find(|dimension| dimension.name().eq_ignore_ascii_case(name))
+    }
+
+    /// The ID of the dimension's type in the `minecraft:dimension_type` registry the client is
+    /// sent during configuration.
+    pub fn type_id(self) -> i32 {
+        match self {
+            Self::Overworld => 0,
+            Self::End => 2,
+            Self::Nether => 3,
+        }
+    }
+
+    /// Where a vanilla world keeps the dimension's region files, relative to the world folder.
+    pub fn region_dir(self) -> &'static str {
+        match self {
+            Self::Overworld => "region",
+            Self::Nether => "DIM-1/region",
+            Self::End => "DIM1/region",
+        }
+    }
+
+    /// The lowest block y.
+    pub fn min_y(self) -> i32 {
+        match self {
+            Self::Overworld => -64,
+            Self::Nether | Self::End => 0,
+        }
+    }
+
+    /// How many blocks tall the dimension is.
+    pub fn height(self) -> i32 {
+        match self {
+            Self::Overworld => 384,
+            Self::Nether | Self::End => 256,
+        }
+    }
+
+    /// How many blocks up from the bottom players can be put, e.g. by spawning. The nether's
+    /// roof is at the top of this.
+    pub fn logical_height(self) -> i32 {
+        match self {
+            Self::Nether => 128,
+            _ => self.height(),
+        }
+    }
+
+    /// Whether light comes down from the sky. The nether and the end have no sky light.
+    pub fn has_skylight(self) -> bool {
+        self == Self::Overworld
+    }
+}
+
+impl FromStr for Dimension {
+    type Err = CoreError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_name(s.trim()).ok_or_else(|| CoreError::InvalidDimension(s.to_string()))
+    }
+}
+
+impl fmt::Display for Dimension {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dimension_names() {
+        for dimension in Dimension::ALL {
+            assert_eq!(Dimension::from_name(dimension.name()), Some(dimension));
+            assert_eq!(
+                Dimension::from_name(dimension.identifier()),
+                Some(dimension)
+            );
+        }
+        assert_eq!(
+            "The_Nether".parse::<Dimension>().unwrap(),
+            Dimension::Nether
+        );
+        assert!("nether".parse::<Dimension>().is_err());
+    }
+}
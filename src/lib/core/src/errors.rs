@@ -4,4 +4,10 @@ use thiserror::Error;
 pub enum CoreError {
     #[error("Something failed lol")]
     SomeError,
+    #[error("Invalid inventory slot: {0}")]
+    InvalidSlot(usize),
+    #[error("Invalid game mode: {0}")]
+    InvalidGameMode(String),
+    #[error("Invalid dimension: {0}")]
+    InvalidDimension(String),
 }
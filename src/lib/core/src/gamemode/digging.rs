@@ -0,0 +1,51 @@
+/// Tracks the block a player is digging, so a finished dig can be matched up with where it
+/// started.
+#[derive(Debug, Default)]
+pub struct DiggingTracker {
+    digging: Option<(i32, i32, i32)>,
+}
+
+impl DiggingTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts digging the block at a location, replacing any other dig.
+    pub fn start(&mut self, x: i32, y: i32, z: i32) {
+        self.digging = Some((x, y, z));
+    }
+
+    pub fn cancel(&mut self) {
+        self.digging = None;
+    }
+
+    /// Finishes digging the block at a location, returning whether that's the block the player
+    /// started digging.
+    pub fn finish(&mut self, x: i32, y: i32, z: i32) -> bool {
+        self.digging.take() == Some((x, y, z))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_finish() {
+        let mut tracker = DiggingTracker::new();
+        assert!(!tracker.finish(1, 2, 3));
+
+        tracker.start(1, 2, 3);
+        assert!(!tracker.finish(1, 2, 4));
+        // A mismatched finish ends the dig too
+        assert!(!tracker.finish(1, 2, 3));
+
+        tracker.start(1, 2, 3);
+        tracker.start(5, 6, 7);
+        assert!(tracker.finish(5, 6, 7));
+
+        tracker.start(1, 2, 3);
+        tracker.cancel();
+        assert!(!tracker.finish(1, 2, 3));
+    }
+}
@@ -0,0 +1,121 @@
+use crate::errors::CoreError;
+use std::fmt;
+use std::str::FromStr;
+
+/// A player's game mode, and the rules that come with it.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum GameMode {
+    #[default]
+    Survival,
+    Creative,
+    Adventure,
+    Spectator,
+}
+
+impl GameMode {
+    pub const ALL: [GameMode; 4] = [
+        GameMode::Survival,
+        GameMode::Creative,
+        GameMode::Adventure,
+        GameMode::Spectator,
+    ];
+
+    /// The ID used for the game mode in packets and vanilla player data.
+    pub fn id(self) -> u8 {
+        match self {
+            Self::Survival => 0,
+            Self::Creative => 1,
+            Self::Adventure => 2,
+            Self::Spectator => 3,
+        }
+    }
+
+    pub fn from_id(id: u8) -> Option<Self> {
+        Self::ALL.into_iter().find(|mode| mode.id() == id)
+    }
+
+    /// The lowercase name, as used in commands and the config.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Survival => "survival",
+            Self::Creative => "creative",
+            Self::Adventure => "adventure",
+            Self::Spectator => "spectator",
+        }
+    }
+
+    /// Whether the player can break and place blocks.
+    pub fn can_build(self) -> bool {
+        matches!(self, Self::Survival | Self::Creative)
+    }
+
+    /// How far away, in blocks, the player can break and place blocks.
+    pub fn block_reach(self) -> f64 {
+        if self == Self::Creative {
+            5.0
+        } else {
+            4.5
+        }
+    }
+
+    /// Whether blocks break as soon as the player starts digging them.
+    pub fn breaks_instantly(self) -> bool {
+        self == Self::Creative
+    }
+
+    /// Whether the player can put any item they like in their inventory.
+    pub fn can_spawn_items(self) -> bool {
+        self == Self::Creative
+    }
+
+    /// Whether the player can move items around in their inventory.
+    pub fn can_use_inventory(self) -> bool {
+        self != Self::Spectator
+    }
+
+    pub fn can_fly(self) -> bool {
+        matches!(self, Self::Creative | Self::Spectator)
+    }
+
+    pub fn is_invulnerable(self) -> bool {
+        matches!(self, Self::Creative | Self::Spectator)
+    }
+
+    /// Whether the player is stopped by solid blocks.
+    pub fn has_collisions(self) -> bool {
+        self != Self::Spectator
+    }
+}
+
+impl FromStr for GameMode {
+    type Err = CoreError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|mode| mode.name().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| CoreError::InvalidGameMode(s.to_string()))
+    }
+}
+
+impl fmt::Display for GameMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_game_mode_ids_and_names() {
+        for mode in GameMode::ALL {
+            assert_eq!(GameMode::from_id(mode.id()), Some(mode));
+            assert_eq!(mode.name().parse::<GameMode>().unwrap(), mode);
+        }
+        assert_eq!(GameMode::from_id(4), None);
+        assert_eq!("Creative".parse::<GameMode>().unwrap(), GameMode::Creative);
+        assert!("hardcore".parse::<GameMode>().is_err());
+    }
+}
@@ -0,0 +1,2 @@
+pub mod digging;
+pub mod game_mode;
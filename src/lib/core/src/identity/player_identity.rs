@@ -2,10 +2,24 @@
 pub struct PlayerIdentity {
     pub username: String,
     pub uuid: u128,
+    /// Profile properties from the session server, usually `textures` (the skin and cape).
+    /// Empty in offline mode.
+    pub properties: Vec<PlayerProperty>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlayerProperty {
+    pub name: String,
+    pub value: String,
+    pub signature: Option<String>,
 }
 
 impl PlayerIdentity {
     pub fn new(username: String, uuid: u128) -> Self {
-        Self { username, uuid }
+        Self {
+            username,
+            uuid,
+            properties: Vec::new(),
+        }
     }
 }
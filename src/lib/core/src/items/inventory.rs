@@ -0,0 +1,197 @@
+use crate::errors::CoreError;
+use ferrumc_net_codec::net_types::slot::ItemStack;
+
+/// The number of slots in the player inventory window: the crafting output and grid, armor, the
+/// main inventory, the hotbar and the offhand.
+pub const PLAYER_INVENTORY_SIZE: usize = 46;
+/// The first hotbar slot.
+pub const HOTBAR_START: usize = 36;
+pub const HOTBAR_SIZE: usize = 9;
+pub const OFFHAND_SLOT: usize = 45;
+
+/// A player's inventory, indexed like the player inventory window (window 0).
+#[derive(Debug, Clone)]
+pub struct Inventory {
+    slots: Vec<Option<ItemStack>>,
+    /// The stack held by the cursor while the inventory is open.
+    pub carried: Option<ItemStack>,
+    /// The selected hotbar slot, from 0 to 8.
+    selected_slot: usize,
+    /// Changes whenever the server sends the contents, so a click can be matched up with the
+    /// contents the client saw when it was made.
+    state_id: i32,
+}
+
+impl Default for Inventory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Inventory {
+    pub fn new() -> Self {
+        Self {
+            slots: vec![None; PLAYER_INVENTORY_SIZE],
+            carried: None,
+            selected_slot: 0,
+            state_id: 0,
+        }
+    }
+
+    pub fn slots(&self) -> &[Option<ItemStack>] {
+        &self.slots
+    }
+
+    pub fn get(&self, slot: usize) -> Option<&ItemStack> {
+        self.slots.get(slot).and_then(Option::as_ref)
+    }
+
+    /// Replaces the stack in a slot, returning the stack that was there. Empty stacks are
+    /// stored as nothing.
+    pub fn set(
+        &mut self,
+        slot: usize,
+        stack: Option<ItemStack>,
+    ) -> Result<Option<ItemStack>, CoreError> {
+        let entry = self
+            .slots
+            .get_mut(slot)
+            .ok_or(CoreError::InvalidSlot(slot))?;
+        Ok(std::mem::replace(
+            entry,
+            stack.filter(|stack| stack.count > 0),
+        ))
+    }
+
+    pub fn state_id(&self) -> i32 {
+        self.state_id
+    }
+
+    /// Moves on to a new state ID, for sending the contents with.
+    pub fn next_state_id(&mut self) -> i32 {
+        self.state_id = self.state_id.wrapping_add(1);
+        self.state_id
+    }
+
+    pub fn selected_slot(&self) -> usize {
+        self.selected_slot
+    }
+
+    /// Selects a hotbar slot, from 0 to 8.
+    pub fn select(&mut self, hotbar_slot: usize) -> Result<(), CoreError> {
+        if hotbar_slot >= HOTBAR_SIZE {
+            return Err(CoreError::InvalidSlot(hotbar_slot));
+        }
+        self.selected_slot = hotbar_slot;
+        Ok(())
+    }
+
+    /// The stack in the selected hotbar slot.
+    pub fn main_hand(&self) -> Option<&ItemStack> {
+        self.get(HOTBAR_START + self.selected_slot)
+    }
+
+    pub fn offhand(&self) -> Option<&ItemStack> {
+        self.get(OFFHAND_SLOT)
+    }
+
+    /// Whether changing these slots and the carried stack would only move items around, leaving
+    /// as many of each item in the inventory and on the cursor as there are now. Changes to
+    /// slots outside the inventory make it false.
+    pub fn keeps_items(
+        &self,
+        changed_slots: &[(usize, Option<ItemStack>)],
+        carried: Option<&ItemStack>,
+    ) -> bool {
+        let mut slots = self.slots.clone();
+        for (slot, stack) in changed_slots {
+            let Some(entry) = slots.get_mut(*slot) else {
+                return false;
+            };
+            *entry = stack.clone();
+        }
+        let before = item_totals(self.slots.iter().flatten().chain(&self.carried));
+        let after = item_totals(slots.iter().flatten().chain(carried));
+        before.len() == after.len() && before.iter().all(|total| after.contains(total))
+    }
+}
+
+/// How many there are of each kind of item among some stacks. Stacks are the same kind of item
+/// if only their counts differ.
+fn item_totals<'a>(stacks: impl Iterator<Item = &'a ItemStack>) -> Vec<(ItemStack, i64)> {
+    let mut totals: Vec<(ItemStack, i64)> = Vec::new();
+    for stack in stacks.filter(|stack| stack.count > 0) {
+        let kind = ItemStack {
+            count: 1,
+            ..stack.clone()
+        };
+        match totals.iter_mut().find(|(other, _)| *other == kind) {
+            Some((_, total)) => *total += i64::from(stack.count),
+            None => totals.push((kind, i64::from(stack.count))),
+        }
+    }
+    totals
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_and_hands() {
+        let mut inventory = Inventory::new();
+        let stone = ItemStack::new(1, 64);
+
+        assert_eq!(
+            inventory
+                .set(HOTBAR_START + 2, Some(stone.clone()))
+                .unwrap(),
+            None
+        );
+        assert_eq!(inventory.main_hand(), None);
+
+        inventory.select(2).unwrap();
+        assert_eq!(inventory.main_hand(), Some(&stone));
+        assert!(inventory.select(9).is_err());
+
+        // Empty stacks are removed
+        assert_eq!(
+            inventory
+                .set(HOTBAR_START + 2, Some(ItemStack::new(1, 0)))
+                .unwrap(),
+            Some(stone)
+        );
+        assert_eq!(inventory.main_hand(), None);
+
+        assert!(inventory.set(PLAYER_INVENTORY_SIZE, None).is_err());
+        assert_eq!(inventory.state_id(), 0);
+        assert_eq!(inventory.next_state_id(), 1);
+    }
+
+    #[test]
+    fn test_keeps_items() {
+        let mut inventory = Inventory::new();
+        inventory.set(9, Some(ItemStack::new(1, 10))).unwrap();
+        inventory.set(10, Some(ItemStack::new(2, 3))).unwrap();
+
+        // Picking up half a stack
+        assert!(inventory.keeps_items(
+            &[(9, Some(ItemStack::new(1, 5)))],
+            Some(&ItemStack::new(1, 5))
+        ));
+        // Swapping two stacks
+        assert!(inventory.keeps_items(
+            &[
+                (9, Some(ItemStack::new(2, 3))),
+                (10, Some(ItemStack::new(1, 10)))
+            ],
+            None
+        ));
+        // Making more of an item, or turning it into another
+        assert!(!inventory.keeps_items(&[(9, Some(ItemStack::new(1, 64)))], None));
+        assert!(!inventory.keeps_items(&[(9, Some(ItemStack::new(3, 10)))], None));
+        // Losing items
+        assert!(!inventory.keeps_items(&[(9, None)], None));
+        assert!(!inventory.keeps_items(&[(PLAYER_INVENTORY_SIZE, None)], None));
+    }
+}
@@ -1,6 +1,11 @@
 pub mod errors;
 
 // Core structs/types. Usually used in ECS Components.
+pub mod chunks;
+pub mod dimensions;
+pub mod gamemode;
 pub mod identity;
+pub mod items;
 pub mod state;
+pub mod tracking;
 pub mod transform;
@@ -0,0 +1 @@
+pub mod tracked_entities;
@@ -0,0 +1,17 @@
+use ferrumc_ecs::entities::Entity;
+use std::collections::HashSet;
+
+/// The entities a player's client has been told to spawn.
+///
+/// Entities are spawned for a player when their chunk is loaded by that player, and removed
+/// when it's unloaded, so only players who have an entity spawned are sent its movement.
+#[derive(Debug, Default)]
+pub struct TrackedEntities {
+    pub entities: HashSet<Entity>,
+}
+
+impl TrackedEntities {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
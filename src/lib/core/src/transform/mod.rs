@@ -1,3 +1,4 @@
 pub mod grounded;
 pub mod position;
 pub mod rotation;
+pub mod teleport;
@@ -1,16 +1,34 @@
 use std::fmt::{Debug, Display, Formatter};
 
+#[derive(Clone, Copy, PartialEq)]
 pub struct Position {
     pub x: f64,
     pub y: f64,
     pub z: f64,
 }
 
+/// How high a standing player's eyes are above their feet.
+pub const EYE_HEIGHT: f64 = 1.62;
+
 // Helper functions:
 impl Position {
     pub fn new(x: f64, y: f64, z: f64) -> Self {
         Self { x, y, z }
     }
+
+    /// Whether a player standing here can reach the block at (`x`, `y`, `z`), measuring from
+    /// their eyes to the closest point of the block. Like vanilla, a block more than `reach` is
+    /// allowed, since the client's position may be ahead of ours.
+    pub fn can_reach_block(&self, x: i32, y: i32, z: i32, reach: f64) -> bool {
+        let distance = |eye: f64, block: i32| {
+            let block = f64::from(block);
+            (block - eye).max(eye - (block + 1.0)).max(0.0)
+        };
+        let dx = distance(self.x, x);
+        let dy = distance(self.y + EYE_HEIGHT, y);
+        let dz = distance(self.z, z);
+        dx * dx + dy * dy + dz * dz < (reach + 1.0) * (reach + 1.0)
+    }
 }
 
 // Implementations:
@@ -47,3 +65,20 @@ impl Display for Position {
         write!(f, "({:.2}, {:.2}, {:.2})", self.x, self.y, self.z)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_can_reach_block() {
+        let position = Position::new(0.5, 64.0, 0.5);
+        // The block under the player's feet, and one at eye level a few blocks away
+        assert!(position.can_reach_block(0, 63, 0, 4.5));
+        assert!(position.can_reach_block(5, 65, 0, 4.5));
+        assert!(!position.can_reach_block(7, 65, 0, 4.5));
+        assert!(position.can_reach_block(6, 65, 0, 5.0));
+        assert!(!position.can_reach_block(0, 40, 0, 5.0));
+        assert!(!position.can_reach_block(-100, 64, 1000, 5.0));
+    }
+}
@@ -0,0 +1,64 @@
+/// Tracks the teleports sent to a player, so their confirmations can be matched up.
+///
+/// Every Synchronize Player Position packet carries an ID that the client sends back once it
+/// has moved. Until then, the client may still send movement from before the teleport, which
+/// has to be ignored.
+#[derive(Debug, Default)]
+pub struct TeleportTracker {
+    last_id: i32,
+    awaiting: Option<i32>,
+}
+
+impl TeleportTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts a new teleport, returning the ID to send with it. Any earlier teleport that
+    /// hasn't been confirmed yet is superseded.
+    pub fn start(&mut self) -> i32 {
+        self.last_id = self.last_id.wrapping_add(1);
+        self.awaiting = Some(self.last_id);
+        self.last_id
+    }
+
+    /// Handles a confirmation from the client, returning whether it matches the latest
+    /// teleport.
+    pub fn confirm(&mut self, id: i32) -> bool {
+        if self.awaiting == Some(id) {
+            self.awaiting = None;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Whether a teleport has been sent that the client hasn't confirmed yet.
+    pub fn is_awaiting(&self) -> bool {
+        self.awaiting.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_confirmations() {
+        let mut tracker = TeleportTracker::new();
+        assert!(!tracker.is_awaiting());
+
+        let first = tracker.start();
+        let second = tracker.start();
+        assert_ne!(first, second);
+        assert!(tracker.is_awaiting());
+
+        // Superseded by the second teleport
+        assert!(!tracker.confirm(first));
+        assert!(tracker.is_awaiting());
+
+        assert!(tracker.confirm(second));
+        assert!(!tracker.is_awaiting());
+        assert!(!tracker.confirm(second));
+    }
+}
@@ -1,11 +1,22 @@
+pub mod ticks;
+
+use crate::ticks::TickStats;
 use ferrumc_ecs::Universe;
+use ferrumc_net_encryption::session::SessionVerifier;
 use ferrumc_world::World;
 use std::sync::Arc;
 use tokio::net::TcpListener;
+use tokio_util::sync::CancellationToken;
 pub struct ServerState {
     pub universe: Universe,
     pub tcp_listener: TcpListener,
     pub world: World,
+    /// Verifies online-mode logins against the session server.
+    pub session_verifier: Arc<dyn SessionVerifier>,
+    /// Cancelled to shut the server down, e.g. by the `stop` command.
+    pub shutdown: CancellationToken,
+    /// The tick rate, and how well the server is keeping up with it.
+    pub ticks: TickStats,
 }
 
 pub type GlobalState = Arc<ServerState>;
@@ -0,0 +1,192 @@
+//! Measures how fast the server is actually ticking.
+//!
+//! Every recorded tick is also reported through the [metrics] facade, so whatever exporter is
+//! installed as the global recorder can publish it. Without one, reporting does nothing.
+//!
+//! - `ferrumc_tps`: a gauge of each TPS average, labelled with its `window` (`1m`, `5m` or
+//!   `15m`).
+//! - `ferrumc_mspt`: a histogram of how long each tick took to run, in milliseconds.
+
+use metrics::{gauge, histogram};
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// How many of the latest tick durations are kept for percentiles.
+const DURATION_HISTORY: usize = 1200;
+
+/// The windows the tick rate is averaged over: 1, 5 and 15 minutes.
+const TPS_WINDOWS: [Duration; 3] = [
+    Duration::from_secs(60),
+    Duration::from_secs(5 * 60),
+    Duration::from_secs(15 * 60),
+];
+/// The `window` label of each TPS gauge.
+const TPS_WINDOW_LABELS: [&str; 3] = ["1m", "5m", "15m"];
+
+/// How long ticks are taking, over the latest ticks. All values are in milliseconds.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MsptSummary {
+    pub mean: f64,
+    pub p50: f64,
+    pub p95: f64,
+    pub p99: f64,
+    pub max: f64,
+}
+
+/// The server's tick rate and how well it's keeping up with it.
+///
+/// The TPS averages are exponentially weighted, like load averages, so recent ticks count for
+/// more. They start at the target rate.
+pub struct TickStats {
+    tick_rate: u32,
+    inner: Mutex<TickHistory>,
+}
+
+struct TickHistory {
+    tps: [f64; 3],
+    durations: VecDeque<Duration>,
+}
+
+impl TickStats {
+    pub fn new(tick_rate: u32) -> Self {
+        Self {
+            tick_rate,
+            inner: Mutex::new(TickHistory {
+                tps: [tick_rate as f64; 3],
+                durations: VecDeque::with_capacity(DURATION_HISTORY),
+            }),
+        }
+    }
+
+    /// How many ticks are meant to run each second.
+    pub fn tick_rate(&self) -> u32 {
+        self.tick_rate
+    }
+
+    /// How long each tick is meant to take, including the time spent waiting for the next one.
+    pub fn tick_period(&self) -> Duration {
+        Duration::from_secs(1) / self.tick_rate
+    }
+
+    /// Records a tick that started `interval` after the one before it and took `duration` to
+    /// run, and reports it to the metrics recorder.
+    pub fn record(&self, interval: Duration, duration: Duration) {
+        let mut inner = self.inner.lock();
+        if !interval.is_zero() {
+            let instant_tps = 1.0 / interval.as_secs_f64();
+            for (average, window) in inner.tps.iter_mut().zip(TPS_WINDOWS) {
+                let decay = (-interval.as_secs_f64() / window.as_secs_f64()).exp();
+                *average = *average * decay + instant_tps * (1.0 - decay);
+            }
+        }
+        if inner.durations.len() == DURATION_HISTORY {
+            inner.durations.pop_front();
+        }
+        inner.durations.push_back(duration);
+
+        for (average, label) in inner.tps.iter().zip(TPS_WINDOW_LABELS) {
+            gauge!("ferrumc_tps", "window" => label).set(*average);
+        }
+        histogram!("ferrumc_mspt").record(duration.as_secs_f64() * 1000.0);
+    }
+
+    /// The average ticks per second over the last 1, 5 and 15 minutes.
+    pub fn tps(&self) -> [f64; 3] {
+        self.inner.lock().tps
+    }
+
+    /// How long the latest ticks took to run.
+    pub fn mspt(&self) -> MsptSummary {
+        let mut durations = self
+            .inner
+            .lock()
+            .durations
+            .iter()
+            .map(|duration| duration.as_secs_f64() * 1000.0)
+            .collect::<Vec<_>>();
+        if durations.is_empty() {
+            return MsptSummary::default();
+        }
+        durations.sort_by(f64::total_cmp);
+
+        let percentile = |p: f64| {
+            let rank = (p / 100.0 * durations.len() as f64).ceil() as usize;
+            durations[rank.clamp(1, durations.len()) - 1]
+        };
+        MsptSummary {
+            mean: durations.iter().sum::<f64>() / durations.len() as f64,
+            p50: percentile(50.0),
+            p95: percentile(95.0),
+            p99: percentile(99.0),
+            max: durations[durations.len() - 1],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+
+    #[test]
+    fn test_tps_follows_the_tick_interval() {
+        let stats = TickStats::new(20);
+        assert_eq!(stats.tps(), [20.0; 3]);
+
+        // Five minutes at 10 TPS
+        for _ in 0..3000 {
+            stats.record(Duration::from_millis(100), Duration::from_millis(90));
+        }
+        let [one, five, fifteen] = stats.tps();
+        assert!((one - 10.0).abs() < 0.1);
+        assert!(one < five && five < fifteen && fifteen < 20.0);
+    }
+
+    #[test]
+    fn test_mspt_percentiles() {
+        let stats = TickStats::new(20);
+        assert_eq!(stats.mspt(), MsptSummary::default());
+
+        for ms in 1..=100 {
+            stats.record(Duration::from_millis(50), Duration::from_millis(ms));
+        }
+        let mspt = stats.mspt();
+        assert_eq!(mspt.p50, 50.0);
+        assert_eq!(mspt.p95, 95.0);
+        assert_eq!(mspt.p99, 99.0);
+        assert_eq!(mspt.max, 100.0);
+        assert!((mspt.mean - 50.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_reports_metrics() {
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        metrics::with_local_recorder(&recorder, || {
+            let stats = TickStats::new(20);
+            stats.record(Duration::from_millis(50), Duration::from_millis(12));
+            stats.record(Duration::from_millis(50), Duration::from_millis(30));
+        });
+
+        let snapshot = snapshotter.snapshot().into_hashmap();
+        let tps = snapshot
+            .iter()
+            .filter(|(key, _)| key.key().name() == "ferrumc_tps")
+            .map(|(_, (_, _, value))| value)
+            .collect::<Vec<_>>();
+        assert_eq!(tps.len(), 3);
+        assert!(tps
+            .iter()
+            .all(|value| matches!(value, DebugValue::Gauge(tps) if tps.0 == 20.0)));
+
+        let (_, (_, _, mspt)) = snapshot
+            .iter()
+            .find(|(key, _)| key.key().name() == "ferrumc_mspt")
+            .unwrap();
+        assert!(matches!(
+            mspt,
+            DebugValue::Histogram(values) if values.iter().map(|v| v.0).eq([12.0, 30.0])
+        ));
+    }
+}
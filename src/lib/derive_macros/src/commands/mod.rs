@@ -0,0 +1,34 @@
+use proc_macro::TokenStream;
+use quote::{format_ident, quote};
+use syn::parse_macro_input;
+
+/// Registers the command tree returned by the function with the global command dispatcher,
+/// before `main` runs.
+pub fn command_fn(attr: TokenStream, input: TokenStream) -> TokenStream {
+    if !attr.is_empty() {
+        return TokenStream::from(quote! {
+            compile_error!("The command attribute doesn't take any arguments");
+        });
+    }
+    let input = parse_macro_input!(input as syn::ItemFn);
+
+    if !input.sig.inputs.is_empty() || input.sig.asyncness.is_some() {
+        return TokenStream::from(quote! {
+            compile_error!("Expected a command to be a non-async function without arguments that returns a CommandNode");
+        });
+    }
+
+    let fn_name = &input.sig.ident;
+    let register_fn_name = format_ident!("__register_command_{}", fn_name);
+
+    let output = quote! {
+        #input
+
+        #[ctor::ctor]
+        fn #register_fn_name() {
+            ::ferrumc_commands::infrastructure::register_command(#fn_name());
+        }
+    };
+
+    output.into()
+}
@@ -2,6 +2,7 @@
 
 use proc_macro::TokenStream;
 
+mod commands;
 mod events;
 mod helpers;
 mod nbt;
@@ -18,6 +19,11 @@ pub fn event_handler(attr: TokenStream, item: TokenStream) -> TokenStream {
     events::event_handler_fn(attr, item)
 }
 
+#[proc_macro_attribute]
+pub fn command(attr: TokenStream, item: TokenStream) -> TokenStream {
+    commands::command_fn(attr, item)
+}
+
 #[proc_macro_derive(Event, attributes(event))]
 pub fn event(input: TokenStream) -> TokenStream {
     events::derive(input)
@@ -16,4 +16,7 @@ pub enum NetDecodeError {
 
     #[error("Invalid Enum Variant")]
     InvalidEnumVariant,
+
+    #[error("Unsupported item component: {0}")]
+    UnsupportedItemComponent(i32),
 }
@@ -0,0 +1,54 @@
+use crate::decode::{NetDecode, NetDecodeOpts, NetDecodeResult};
+use crate::encode::{NetEncode, NetEncodeOpts, NetEncodeResult};
+use std::io::{Read, Write};
+use tokio::io::AsyncWrite;
+
+/// A rotation angle in steps of 1/256 of a full turn.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Angle(pub u8);
+
+impl Angle {
+    /// Wraps the angle around, so -90° and 270° are the same angle.
+    pub fn from_degrees(degrees: f32) -> Self {
+        Self(((degrees / 360.0 * 256.0).round() as i32).rem_euclid(256) as u8)
+    }
+
+    pub fn to_degrees(self) -> f32 {
+        f32::from(self.0) * 360.0 / 256.0
+    }
+}
+
+impl NetEncode for Angle {
+    fn encode<W: Write>(&self, writer: &mut W, opts: &NetEncodeOpts) -> NetEncodeResult<()> {
+        self.0.encode(writer, opts)
+    }
+
+    async fn encode_async<W: AsyncWrite + Unpin>(
+        &self,
+        writer: &mut W,
+        opts: &NetEncodeOpts,
+    ) -> NetEncodeResult<()> {
+        self.0.encode_async(writer, opts).await
+    }
+}
+
+impl NetDecode for Angle {
+    fn decode<R: Read>(reader: &mut R, opts: &NetDecodeOpts) -> NetDecodeResult<Self> {
+        Ok(Self(u8::decode(reader, opts)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_degrees() {
+        assert_eq!(Angle::from_degrees(0.0), Angle(0));
+        assert_eq!(Angle::from_degrees(90.0), Angle(64));
+        assert_eq!(Angle::from_degrees(-90.0), Angle(192));
+        assert_eq!(Angle::from_degrees(360.0), Angle(0));
+        assert_eq!(Angle::from_degrees(-540.0), Angle(128));
+        assert_eq!(Angle(64).to_degrees(), 90.0);
+    }
+}
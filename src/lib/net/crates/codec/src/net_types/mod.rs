@@ -1,6 +1,8 @@
+pub mod angle;
 pub mod bitset;
 pub mod length_prefixed_vec;
 pub mod network_position;
+pub mod slot;
 pub mod var_int;
 
 #[derive(Debug, thiserror::Error)]
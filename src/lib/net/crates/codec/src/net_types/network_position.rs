@@ -1,8 +1,9 @@
 // I have no clue why it is saving i32 and i16. There is no precision. The actual player position is saved in f32.
 
+use crate::decode::{NetDecode, NetDecodeOpts, NetDecodeResult};
 use crate::encode::{NetEncode, NetEncodeOpts, NetEncodeResult};
 use std::fmt::Display;
-use std::io::Write;
+use std::io::{Read, Write};
 use tokio::io::AsyncWrite;
 
 /// The definition of a "Position" in the Minecraft protocol.
@@ -46,10 +47,41 @@ impl NetEncode for NetworkPosition {
         Ok(())
     }
 }
+
+impl NetDecode for NetworkPosition {
+    fn decode<R: Read>(reader: &mut R, opts: &NetDecodeOpts) -> NetDecodeResult<Self> {
+        Ok(Self::from_u64(u64::decode(reader, opts)?))
+    }
+}
+
 impl NetworkPosition {
     pub fn as_u64(&self) -> u64 {
         ((self.x as u64 & 0x3FFFFFF) << 38)
             | ((self.z as u64 & 0x3FFFFFF) << 12)
             | (self.y as u64 & 0xFFF)
     }
+
+    pub fn from_u64(value: u64) -> Self {
+        // Shifting the signed value back down sign-extends each field.
+        let value = value as i64;
+        Self {
+            x: (value >> 38) as i32,
+            z: (value << 26 >> 38) as i32,
+            y: (value << 52 >> 52) as i16,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        for (x, y, z) in [(0, 0, 0), (18357644, 831, -20882616), (-1, -64, -1)] {
+            let position = NetworkPosition::new(x, y, z);
+            let decoded = NetworkPosition::from_u64(position.as_u64());
+            assert_eq!((decoded.x, decoded.y, decoded.z), (x, y, z));
+        }
+    }
 }
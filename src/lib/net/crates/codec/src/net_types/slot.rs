@@ -0,0 +1,335 @@
+use crate::decode::errors::NetDecodeError;
+use crate::decode::{NetDecode, NetDecodeOpts, NetDecodeResult};
+use crate::encode::{NetEncode, NetEncodeOpts, NetEncodeResult};
+use crate::net_types::var_int::VarInt;
+use bitcode::{Decode, Encode};
+use std::io::{Read, Write};
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+/// A stack of items: an item from the `minecraft:item` registry, how many there are, and how its
+/// data components differ from the item's defaults.
+#[derive(Clone, Debug, PartialEq, Encode, Decode)]
+pub struct ItemStack {
+    pub item: i32,
+    pub count: i32,
+    /// Components added to, or replacing, the item's defaults.
+    pub components: Vec<ItemComponent>,
+    /// IDs of default components the stack doesn't have.
+    pub removed_components: Vec<i32>,
+}
+
+impl ItemStack {
+    pub fn new(item: i32, count: i32) -> Self {
+        Self {
+            item,
+            count,
+            components: Vec::new(),
+            removed_components: Vec::new(),
+        }
+    }
+}
+
+/// An enchantment and its level, as in the `enchantments` component.
+#[derive(Clone, Debug, PartialEq, Encode, Decode)]
+pub struct EnchantmentLevel {
+    /// The ID in the `minecraft:enchantment` registry.
+    pub enchantment: i32,
+    pub level: i32,
+}
+
+/// A data component of an item stack.
+///
+/// Only components whose network format is simple are supported. Stacks with any other
+/// component fail to decode with [NetDecodeError::UnsupportedItemComponent].
+#[derive(Clone, Debug, PartialEq, Encode, Decode)]
+pub enum ItemComponent {
+    MaxStackSize(i32),
+    MaxDamage(i32),
+    Damage(i32),
+    Unbreakable {
+        show_in_tooltip: bool,
+    },
+    /// 0 to 3, for common to epic.
+    Rarity(i32),
+    Enchantments {
+        enchantments: Vec<EnchantmentLevel>,
+        show_in_tooltip: bool,
+    },
+    CustomModelData(i32),
+    HideAdditionalTooltip,
+    HideTooltip,
+    RepairCost(i32),
+    CreativeSlotLock,
+    EnchantmentGlintOverride(bool),
+    FireResistant,
+    StoredEnchantments {
+        enchantments: Vec<EnchantmentLevel>,
+        show_in_tooltip: bool,
+    },
+    DyedColor {
+        rgb: i32,
+        show_in_tooltip: bool,
+    },
+}
+
+impl ItemComponent {
+    /// The component's ID in the `minecraft:data_component_type` registry.
+    pub fn id(&self) -> i32 {
+        match self {
+            Self::MaxStackSize(_) => 1,
+            Self::MaxDamage(_) => 2,
+            Self::Damage(_) => 3,
+            Self::Unbreakable { .. } => 4,
+            Self::Rarity(_) => 8,
+            Self::Enchantments { .. } => 9,
+            Self::CustomModelData(_) => 13,
+            Self::HideAdditionalTooltip => 14,
+            Self::HideTooltip => 15,
+            Self::RepairCost(_) => 16,
+            Self::CreativeSlotLock => 17,
+            Self::EnchantmentGlintOverride(_) => 18,
+            Self::FireResistant => 21,
+            Self::StoredEnchantments { .. } => 23,
+            Self::DyedColor { .. } => 24,
+        }
+    }
+}
+
+fn encode_enchantments<W: Write>(
+    writer: &mut W,
+    enchantments: &[EnchantmentLevel],
+    show_in_tooltip: bool,
+) -> NetEncodeResult<()> {
+    VarInt::from(enchantments.len()).encode(writer, &NetEncodeOpts::None)?;
+    for enchantment in enchantments {
+        VarInt::new(enchantment.enchantment).encode(writer, &NetEncodeOpts::None)?;
+        VarInt::new(enchantment.level).encode(writer, &NetEncodeOpts::None)?;
+    }
+    show_in_tooltip.encode(writer, &NetEncodeOpts::None)
+}
+
+fn decode_enchantments<R: Read>(reader: &mut R) -> NetDecodeResult<(Vec<EnchantmentLevel>, bool)> {
+    let count = VarInt::decode(reader, &NetDecodeOpts::None)?.val;
+    let mut enchantments = Vec::new();
+    for _ in 0..count {
+        enchantments.push(EnchantmentLevel {
+            enchantment: VarInt::decode(reader, &NetDecodeOpts::None)?.val,
+            level: VarInt::decode(reader, &NetDecodeOpts::None)?.val,
+        });
+    }
+    let show_in_tooltip = bool::decode(reader, &NetDecodeOpts::None)?;
+    Ok((enchantments, show_in_tooltip))
+}
+
+impl NetEncode for ItemComponent {
+    fn encode<W: Write>(&self, writer: &mut W, opts: &NetEncodeOpts) -> NetEncodeResult<()> {
+        VarInt::new(self.id()).encode(writer, opts)?;
+        match self {
+            Self::MaxStackSize(value)
+            | Self::MaxDamage(value)
+            | Self::Damage(value)
+            | Self::Rarity(value)
+            | Self::CustomModelData(value)
+            | Self::RepairCost(value) => VarInt::new(*value).encode(writer, opts),
+            Self::Unbreakable { show_in_tooltip } => show_in_tooltip.encode(writer, opts),
+            Self::Enchantments {
+                enchantments,
+                show_in_tooltip,
+            }
+            | Self::StoredEnchantments {
+                enchantments,
+                show_in_tooltip,
+            } => encode_enchantments(writer, enchantments, *show_in_tooltip),
+            Self::HideAdditionalTooltip
+            | Self::HideTooltip
+            | Self::CreativeSlotLock
+            | Self::FireResistant => Ok(()),
+            Self::EnchantmentGlintOverride(value) => value.encode(writer, opts),
+            Self::DyedColor {
+                rgb,
+                show_in_tooltip,
+            } => {
+                rgb.encode(writer, opts)?;
+                show_in_tooltip.encode(writer, opts)
+            }
+        }
+    }
+
+    async fn encode_async<W: AsyncWrite + Unpin>(
+        &self,
+        writer: &mut W,
+        opts: &NetEncodeOpts,
+    ) -> NetEncodeResult<()> {
+        let mut buffer = Vec::new();
+        self.encode(&mut buffer, opts)?;
+        writer.write_all(&buffer).await?;
+        Ok(())
+    }
+}
+
+impl NetDecode for ItemComponent {
+    fn decode<R: Read>(reader: &mut R, opts: &NetDecodeOpts) -> NetDecodeResult<Self> {
+        let id = VarInt::decode(reader, opts)?.val;
+        let varint = |reader: &mut R| VarInt::decode(reader, opts).map(|value| value.val);
+        Ok(match id {
+            1 => Self::MaxStackSize(varint(reader)?),
+            2 => Self::MaxDamage(varint(reader)?),
+            3 => Self::Damage(varint(reader)?),
+            4 => Self::Unbreakable {
+                show_in_tooltip: bool::decode(reader, opts)?,
+            },
+            8 => Self::Rarity(varint(reader)?),
+            9 => {
+                let (enchantments, show_in_tooltip) = decode_enchantments(reader)?;
+                Self::Enchantments {
+                    enchantments,
+                    show_in_tooltip,
+                }
+            }
+            13 => Self::CustomModelData(varint(reader)?),
+            14 => Self::HideAdditionalTooltip,
+            15 => Self::HideTooltip,
+            16 => Self::RepairCost(varint(reader)?),
+            17 => Self::CreativeSlotLock,
+            18 => Self::EnchantmentGlintOverride(bool::decode(reader, opts)?),
+            21 => Self::FireResistant,
+            23 => {
+                let (enchantments, show_in_tooltip) = decode_enchantments(reader)?;
+                Self::StoredEnchantments {
+                    enchantments,
+                    show_in_tooltip,
+                }
+            }
+            24 => Self::DyedColor {
+                rgb: i32::decode(reader, opts)?,
+                show_in_tooltip: bool::decode(reader, opts)?,
+            },
+            _ => return Err(NetDecodeError::UnsupportedItemComponent(id)),
+        })
+    }
+}
+
+/// The network format of an inventory slot: an item stack, or nothing.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Slot(pub Option<ItemStack>);
+
+impl Slot {
+    pub fn empty() -> Self {
+        Self(None)
+    }
+}
+
+impl From<Option<ItemStack>> for Slot {
+    fn from(stack: Option<ItemStack>) -> Self {
+        Self(stack)
+    }
+}
+
+impl NetEncode for Slot {
+    fn encode<W: Write>(&self, writer: &mut W, opts: &NetEncodeOpts) -> NetEncodeResult<()> {
+        let Some(stack) = self.0.as_ref().filter(|stack| stack.count > 0) else {
+            return VarInt::new(0).encode(writer, opts);
+        };
+        VarInt::new(stack.count).encode(writer, opts)?;
+        VarInt::new(stack.item).encode(writer, opts)?;
+        VarInt::from(stack.components.len()).encode(writer, opts)?;
+        VarInt::from(stack.removed_components.len()).encode(writer, opts)?;
+        for component in &stack.components {
+            component.encode(writer, opts)?;
+        }
+        for id in &stack.removed_components {
+            VarInt::new(*id).encode(writer, opts)?;
+        }
+        Ok(())
+    }
+
+    async fn encode_async<W: AsyncWrite + Unpin>(
+        &self,
+        writer: &mut W,
+        opts: &NetEncodeOpts,
+    ) -> NetEncodeResult<()> {
+        let mut buffer = Vec::new();
+        self.encode(&mut buffer, opts)?;
+        writer.write_all(&buffer).await?;
+        Ok(())
+    }
+}
+
+impl NetDecode for Slot {
+    fn decode<R: Read>(reader: &mut R, opts: &NetDecodeOpts) -> NetDecodeResult<Self> {
+        let count = VarInt::decode(reader, opts)?.val;
+        if count <= 0 {
+            return Ok(Self::empty());
+        }
+        let item = VarInt::decode(reader, opts)?.val;
+        let added = VarInt::decode(reader, opts)?.val;
+        let removed = VarInt::decode(reader, opts)?.val;
+
+        let mut components = Vec::new();
+        for _ in 0..added {
+            components.push(ItemComponent::decode(reader, opts)?);
+        }
+        let mut removed_components = Vec::new();
+        for _ in 0..removed {
+            removed_components.push(VarInt::decode(reader, opts)?.val);
+        }
+
+        Ok(Self(Some(ItemStack {
+            item,
+            count,
+            components,
+            removed_components,
+        })))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn round_trip(slot: &Slot) -> Slot {
+        let mut bytes = Vec::new();
+        slot.encode(&mut bytes, &NetEncodeOpts::None).unwrap();
+        Slot::decode(&mut Cursor::new(bytes), &NetDecodeOpts::None).unwrap()
+    }
+
+    #[test]
+    fn test_empty_slot() {
+        let mut bytes = Vec::new();
+        Slot::empty()
+            .encode(&mut bytes, &NetEncodeOpts::None)
+            .unwrap();
+        assert_eq!(bytes, vec![0]);
+        assert_eq!(round_trip(&Slot::empty()), Slot::empty());
+    }
+
+    #[test]
+    fn test_slot_round_trip() {
+        let mut stack = ItemStack::new(812, 1);
+        stack.components = vec![
+            ItemComponent::Damage(12),
+            ItemComponent::Enchantments {
+                enchantments: vec![EnchantmentLevel {
+                    enchantment: 5,
+                    level: 3,
+                }],
+                show_in_tooltip: true,
+            },
+            ItemComponent::FireResistant,
+        ];
+        stack.removed_components = vec![8];
+        let slot = Slot(Some(stack));
+        assert_eq!(round_trip(&slot), slot);
+    }
+
+    #[test]
+    fn test_unsupported_component() {
+        // One stone with a custom name, which isn't supported
+        let bytes = vec![1, 1, 1, 0, 5];
+        assert!(matches!(
+            Slot::decode(&mut Cursor::new(bytes), &NetDecodeOpts::None),
+            Err(NetDecodeError::UnsupportedItemComponent(5))
+        ));
+    }
+}
@@ -0,0 +1,213 @@
+//! AES-128-CFB8 adapters for the connection halves.
+//!
+//! Both adapters pass data through untouched until encryption is enabled, so the connection
+//! can be wrapped from the start and switched over once the shared secret is known.
+
+use crate::errors::NetEncryptionError;
+use aes::Aes128;
+use cfb8::cipher::{BlockDecryptMut, BlockEncryptMut, KeyIvInit};
+use std::io;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+type Aes128Cfb8Enc = cfb8::Encryptor<Aes128>;
+type Aes128Cfb8Dec = cfb8::Decryptor<Aes128>;
+
+/// Minecraft uses the shared secret as both the key and the IV.
+fn parse_shared_secret(shared_secret: &[u8]) -> Result<&[u8; 16], NetEncryptionError> {
+    shared_secret
+        .try_into()
+        .map_err(|_| NetEncryptionError::InvalidSharedSecretLength(shared_secret.len()))
+}
+
+/// Wraps a reader and decrypts everything read from it once enabled.
+pub struct EncryptedReader<R> {
+    inner: R,
+    cipher: Option<Aes128Cfb8Dec>,
+}
+
+impl<R> EncryptedReader<R> {
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            cipher: None,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.cipher.is_some()
+    }
+
+    /// Enables decryption for all data read from now on.
+    pub fn enable(&mut self, shared_secret: &[u8]) -> Result<(), NetEncryptionError> {
+        let key = parse_shared_secret(shared_secret)?;
+        self.cipher = Some(Aes128Cfb8Dec::new(key.into(), key.into()));
+        Ok(())
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for EncryptedReader<R> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        let already_filled = buf.filled().len();
+
+        ready!(Pin::new(&mut this.inner).poll_read(cx, buf))?;
+
+        if let Some(cipher) = this.cipher.as_mut() {
+            for byte in buf.filled_mut()[already_filled..].chunks_mut(1) {
+                cipher.decrypt_block_mut(byte.into());
+            }
+        }
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// Wraps a writer and encrypts everything written to it once enabled.
+///
+/// CFB8 is stateful, so bytes are encrypted exactly once when they are accepted and kept in a
+/// pending buffer until the inner writer has taken them.
+pub struct EncryptedWriter<W> {
+    inner: W,
+    cipher: Option<Aes128Cfb8Enc>,
+    pending: Vec<u8>,
+}
+
+impl<W> EncryptedWriter<W> {
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            cipher: None,
+            pending: Vec::new(),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.cipher.is_some()
+    }
+
+    /// Enables encryption for all data written from now on.
+    pub fn enable(&mut self, shared_secret: &[u8]) -> Result<(), NetEncryptionError> {
+        let key = parse_shared_secret(shared_secret)?;
+        self.cipher = Some(Aes128Cfb8Enc::new(key.into(), key.into()));
+        Ok(())
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+}
+
+impl<W: AsyncWrite + Unpin> EncryptedWriter<W> {
+    fn poll_write_pending(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        while !self.pending.is_empty() {
+            let written = ready!(Pin::new(&mut self.inner).poll_write(cx, &self.pending))?;
+            if written == 0 {
+                return Poll::Ready(Err(io::ErrorKind::WriteZero.into()));
+            }
+            self.pending.drain(..written);
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<W: AsyncWrite + Unpin> AsyncWrite for EncryptedWriter<W> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+
+        if this.cipher.is_none() {
+            return Pin::new(&mut this.inner).poll_write(cx, buf);
+        }
+
+        ready!(this.poll_write_pending(cx))?;
+
+        // Once encrypted, the bytes count as written; the cipher state has already moved on.
+        let start = this.pending.len();
+        this.pending.extend_from_slice(buf);
+        if let Some(cipher) = this.cipher.as_mut() {
+            for byte in this.pending[start..].chunks_mut(1) {
+                cipher.encrypt_block_mut(byte.into());
+            }
+        }
+
+        // Opportunistically push the data out, it will be retried on the next write/flush.
+        if let Poll::Ready(Err(e)) = this.poll_write_pending(cx) {
+            return Poll::Ready(Err(e));
+        }
+
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        ready!(this.poll_write_pending(cx))?;
+        Pin::new(&mut this.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        ready!(this.poll_write_pending(cx))?;
+        Pin::new(&mut this.inner).poll_shutdown(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    const SECRET: [u8; 16] = *b"0123456789abcdef";
+
+    #[tokio::test]
+    async fn test_round_trip() {
+        let (client, server) = tokio::io::duplex(64);
+        let mut writer = EncryptedWriter::new(client);
+        let mut reader = EncryptedReader::new(server);
+
+        // Nothing is touched until encryption is enabled.
+        writer.write_all(b"plain").await.unwrap();
+        let mut plain = [0u8; 5];
+        reader.read_exact(&mut plain).await.unwrap();
+        assert_eq!(&plain, b"plain");
+
+        writer.enable(&SECRET).unwrap();
+        reader.enable(&SECRET).unwrap();
+
+        let payload: Vec<u8> = (0..=255u8).cycle().take(1000).collect();
+        let expected = payload.clone();
+        let write_task = tokio::spawn(async move {
+            writer.write_all(&payload).await.unwrap();
+            writer.flush().await.unwrap();
+            writer
+        });
+
+        let mut received = vec![0u8; expected.len()];
+        reader.read_exact(&mut received).await.unwrap();
+        assert_eq!(received, expected);
+
+        let writer = write_task.await.unwrap();
+        assert!(writer.is_enabled());
+    }
+
+    #[test]
+    fn test_invalid_secret_length() {
+        let mut writer = EncryptedWriter::new(Vec::<u8>::new());
+        assert!(matches!(
+            writer.enable(&[0u8; 8]),
+            Err(NetEncryptionError::InvalidSharedSecretLength(8))
+        ));
+    }
+}
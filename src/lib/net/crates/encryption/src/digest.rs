@@ -0,0 +1,59 @@
+use sha1::{Digest, Sha1};
+
+/// Computes the server hash sent to the session server.
+///
+/// Minecraft treats the SHA-1 digest as a signed two's complement big integer and prints it
+/// in hex, so the result can be negative and has no leading zeros.
+pub fn minecraft_digest(server_id: &str, shared_secret: &[u8], public_key_der: &[u8]) -> String {
+    let mut hasher = Sha1::new();
+    hasher.update(server_id.as_bytes());
+    hasher.update(shared_secret);
+    hasher.update(public_key_der);
+
+    let mut hash: [u8; 20] = hasher.finalize().into();
+
+    let negative = hash[0] & 0x80 != 0;
+    if negative {
+        // Two's complement negation: invert and add one.
+        let mut carry = true;
+        for byte in hash.iter_mut().rev() {
+            *byte = !*byte;
+            if carry {
+                let (sum, overflow) = byte.overflowing_add(1);
+                *byte = sum;
+                carry = overflow;
+            }
+        }
+    }
+
+    let hex: String = hash.iter().map(|byte| format!("{:02x}", byte)).collect();
+    let hex = hex.trim_start_matches('0');
+
+    if negative {
+        format!("-{}", hex)
+    } else {
+        hex.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Known values from https://wiki.vg/Protocol_Encryption#Sample_Code
+    #[test]
+    fn test_known_digests() {
+        assert_eq!(
+            minecraft_digest("Notch", &[], &[]),
+            "4ed1f46bbe04bc756bcb17c0c7ce3e4632f06a48"
+        );
+        assert_eq!(
+            minecraft_digest("jeb_", &[], &[]),
+            "-7c9d5b0044c130109a5d7b5fb5c317c02b4e28c1"
+        );
+        assert_eq!(
+            minecraft_digest("simon", &[], &[]),
+            "88e16a1019277b15d58faf0541e11910eb756f6"
+        );
+    }
+}
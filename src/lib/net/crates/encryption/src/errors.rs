@@ -2,6 +2,21 @@ use thiserror::Error;
 
 #[derive(Debug, Clone, Error)]
 pub enum NetEncryptionError {
-    #[error("Something failed lol")]
-    SomeError,
+    #[error("Failed to generate the server keypair: {0}")]
+    KeyGeneration(String),
+
+    #[error("Failed to decrypt data with the server key: {0}")]
+    Decryption(String),
+
+    #[error("Invalid shared secret length: expected 16 bytes, got {0}")]
+    InvalidSharedSecretLength(usize),
+
+    #[error("Verify token mismatch")]
+    VerifyTokenMismatch,
+
+    #[error("Player {0} has not joined through the session server")]
+    NotAuthenticated(String),
+
+    #[error("Session server request failed: {0}")]
+    SessionServer(String),
 }
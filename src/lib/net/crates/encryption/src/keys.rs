@@ -0,0 +1,83 @@
+use crate::errors::NetEncryptionError;
+use lazy_static::lazy_static;
+use rsa::pkcs8::EncodePublicKey;
+use rsa::rand_core::{OsRng, RngCore};
+use rsa::{Pkcs1v15Encrypt, RsaPrivateKey};
+
+/// The vanilla server uses a 1024-bit key, and clients expect nothing larger.
+const KEY_BITS: usize = 1024;
+
+lazy_static! {
+    static ref SERVER_KEYS: ServerKeys =
+        ServerKeys::generate().expect("Failed to generate the server RSA keypair");
+}
+
+/// The RSA keypair used for the login encryption handshake.
+///
+/// A new keypair is generated every time the server starts, same as vanilla.
+pub struct ServerKeys {
+    private_key: RsaPrivateKey,
+    public_key_der: Vec<u8>,
+}
+
+impl ServerKeys {
+    pub fn generate() -> Result<Self, NetEncryptionError> {
+        let private_key = RsaPrivateKey::new(&mut OsRng, KEY_BITS)
+            .map_err(|e| NetEncryptionError::KeyGeneration(e.to_string()))?;
+        let public_key_der = private_key
+            .to_public_key()
+            .to_public_key_der()
+            .map_err(|e| NetEncryptionError::KeyGeneration(e.to_string()))?
+            .into_vec();
+
+        Ok(Self {
+            private_key,
+            public_key_der,
+        })
+    }
+
+    /// The public key, encoded as an ASN.1 DER `SubjectPublicKeyInfo` structure.
+    /// This is the format expected by the Encryption Request packet.
+    pub fn public_key_der(&self) -> &[u8] {
+        &self.public_key_der
+    }
+
+    /// Decrypts data the client encrypted with our public key (PKCS#1 v1.5 padding).
+    pub fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>, NetEncryptionError> {
+        self.private_key
+            .decrypt(Pkcs1v15Encrypt, data)
+            .map_err(|e| NetEncryptionError::Decryption(e.to_string()))
+    }
+}
+
+/// Returns the server keypair, generating it on first use.
+pub fn get_server_keys() -> &'static ServerKeys {
+    &SERVER_KEYS
+}
+
+/// Generates a random verify token for an Encryption Request.
+pub fn generate_verify_token() -> [u8; 4] {
+    let mut token = [0u8; 4];
+    OsRng.fill_bytes(&mut token);
+    token
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rsa::pkcs8::DecodePublicKey;
+    use rsa::RsaPublicKey;
+
+    #[test]
+    fn test_round_trip() {
+        let keys = get_server_keys();
+        let public_key = RsaPublicKey::from_public_key_der(keys.public_key_der()).unwrap();
+
+        let secret = [7u8; 16];
+        let encrypted = public_key
+            .encrypt(&mut OsRng, Pkcs1v15Encrypt, &secret)
+            .unwrap();
+
+        assert_eq!(keys.decrypt(&encrypted).unwrap(), secret);
+    }
+}
@@ -1,16 +1,12 @@
-pub mod errors;
-
-pub fn add(left: u64, right: u64) -> u64 {
-    left + right
-}
+//! # Protocol encryption and online-mode authentication.
+//!
+//! - [keys]: The server's RSA keypair, used during the login encryption handshake.
+//! - [cipher]: AES-128-CFB8 stream adapters that wrap the connection halves.
+//! - [digest]: The (non-standard) SHA-1 hex digest used as the session server hash.
+//! - [session]: Session verification, against Mojang's session server or a local mock.
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn it_works() {
-        let result = add(2, 2);
-        assert_eq!(result, 4);
-    }
-}
+pub mod cipher;
+pub mod digest;
+pub mod errors;
+pub mod keys;
+pub mod session;
@@ -0,0 +1,173 @@
+//! Session verification for online-mode logins.
+//!
+//! After the encryption handshake, the server asks the session server whether the player
+//! has actually joined with the given server hash. The check is behind the [SessionVerifier]
+//! trait so it can be swapped out, e.g. for [MockSessionVerifier] in tests.
+
+use crate::errors::NetEncryptionError;
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+const MOJANG_SESSION_SERVER: &str = "https://sessionserver.mojang.com/session/minecraft/hasJoined";
+
+/// A verified player profile, as returned by the session server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameProfile {
+    pub uuid: u128,
+    pub username: String,
+    pub properties: Vec<ProfileProperty>,
+}
+
+/// A profile property, usually `textures` (the player's skin and cape).
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ProfileProperty {
+    pub name: String,
+    pub value: String,
+    pub signature: Option<String>,
+}
+
+#[async_trait]
+pub trait SessionVerifier: Send + Sync {
+    /// Checks that `username` has joined a server with the given `server_hash`
+    /// (see [crate::digest::minecraft_digest]) and returns their verified profile.
+    async fn has_joined(
+        &self,
+        username: &str,
+        server_hash: &str,
+    ) -> Result<GameProfile, NetEncryptionError>;
+}
+
+/// Verifies sessions against Mojang's session server.
+pub struct MojangSessionVerifier {
+    client: reqwest::Client,
+    url: String,
+}
+
+#[derive(Deserialize)]
+struct HasJoinedResponse {
+    id: String,
+    name: String,
+    #[serde(default)]
+    properties: Vec<ProfileProperty>,
+}
+
+impl MojangSessionVerifier {
+    pub fn new() -> Self {
+        Self::with_url(MOJANG_SESSION_SERVER)
+    }
+
+    /// Uses a different `hasJoined` endpoint, for third-party session servers.
+    pub fn with_url(url: impl Into<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url: url.into(),
+        }
+    }
+}
+
+impl Default for MojangSessionVerifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl SessionVerifier for MojangSessionVerifier {
+    async fn has_joined(
+        &self,
+        username: &str,
+        server_hash: &str,
+    ) -> Result<GameProfile, NetEncryptionError> {
+        let response = self
+            .client
+            .get(&self.url)
+            .query(&[("username", username), ("serverId", server_hash)])
+            .send()
+            .await
+            .map_err(|e| NetEncryptionError::SessionServer(e.to_string()))?;
+
+        // The session server answers with 204 No Content if the player hasn't joined.
+        if response.status() == reqwest::StatusCode::NO_CONTENT {
+            return Err(NetEncryptionError::NotAuthenticated(username.to_string()));
+        }
+
+        let response = response
+            .error_for_status()
+            .map_err(|e| NetEncryptionError::SessionServer(e.to_string()))?
+            .json::<HasJoinedResponse>()
+            .await
+            .map_err(|e| NetEncryptionError::SessionServer(e.to_string()))?;
+
+        let uuid = u128::from_str_radix(&response.id, 16).map_err(|e| {
+            NetEncryptionError::SessionServer(format!("Invalid uuid {}: {}", response.id, e))
+        })?;
+
+        Ok(GameProfile {
+            uuid,
+            username: response.name,
+            properties: response.properties,
+        })
+    }
+}
+
+/// A local session verifier that only knows about the profiles it was given.
+/// Any other username is rejected as not authenticated.
+#[derive(Default)]
+pub struct MockSessionVerifier {
+    profiles: HashMap<String, GameProfile>,
+}
+
+impl MockSessionVerifier {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_profile(mut self, profile: GameProfile) -> Self {
+        self.profiles.insert(profile.username.clone(), profile);
+        self
+    }
+}
+
+#[async_trait]
+impl SessionVerifier for MockSessionVerifier {
+    async fn has_joined(
+        &self,
+        username: &str,
+        _server_hash: &str,
+    ) -> Result<GameProfile, NetEncryptionError> {
+        self.profiles
+            .get(username)
+            .cloned()
+            .ok_or_else(|| NetEncryptionError::NotAuthenticated(username.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_mock_verifier() {
+        let profile = GameProfile {
+            uuid: 0x069a79f444e94726a5befca90e38aaf5,
+            username: "Notch".to_string(),
+            properties: vec![],
+        };
+        let verifier = MockSessionVerifier::new().with_profile(profile.clone());
+
+        assert_eq!(verifier.has_joined("Notch", "hash").await.unwrap(), profile);
+        assert!(matches!(
+            verifier.has_joined("jeb_", "hash").await,
+            Err(NetEncryptionError::NotAuthenticated(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_has_joined_response() {
+        let json = r#"{"id":"069a79f444e94726a5befca90e38aaf5","name":"Notch","properties":[{"name":"textures","value":"abc","signature":"def"}]}"#;
+        let response: HasJoinedResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(response.name, "Notch");
+        assert_eq!(response.properties[0].signature.as_deref(), Some("def"));
+    }
+}
@@ -0,0 +1,114 @@
+//! Packet framing for compressed connections.
+//!
+//! Once Set Compression has been sent, every packet is framed as
+//! `[packet length][data length][id + data]`, where the id and data are zlib compressed if they
+//! are at least as big as the threshold, and `data length` is 0 if they are not.
+//! <https://minecraft.wiki/w/Minecraft_Wiki:Projects/wiki.vg_merge/Protocol#With_compression>
+
+use crate::NetResult;
+use ferrumc_net_codec::encode::{NetEncode, NetEncodeOpts};
+use ferrumc_net_codec::net_types::var_int::VarInt;
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+use std::io::Write;
+
+/// Frames an encoded packet (id and data, without a length) for a compressed connection.
+pub fn compress_packet(packet: &[u8], threshold: usize) -> NetResult<Vec<u8>> {
+    let mut body = Vec::new();
+
+    if packet.len() >= threshold {
+        VarInt::from(packet.len()).encode(&mut body, &NetEncodeOpts::None)?;
+        let mut encoder = ZlibEncoder::new(&mut body, Compression::default());
+        encoder.write_all(packet)?;
+        encoder.finish()?;
+    } else {
+        VarInt::new(0).encode(&mut body, &NetEncodeOpts::None)?;
+        body.extend_from_slice(packet);
+    }
+
+    let mut frame = Vec::with_capacity(body.len() + 5);
+    VarInt::from(body.len()).encode(&mut frame, &NetEncodeOpts::None)?;
+    frame.extend_from_slice(&body);
+
+    Ok(frame)
+}
+
+/// Frames an encoded packet (id and data, without a length) for an uncompressed connection.
+pub fn frame_packet(packet: &[u8]) -> NetResult<Vec<u8>> {
+    let mut frame = Vec::with_capacity(packet.len() + 5);
+    VarInt::from(packet.len()).encode(&mut frame, &NetEncodeOpts::None)?;
+    frame.extend_from_slice(packet);
+
+    Ok(frame)
+}
+
+/// Re-frames a buffer of packets encoded with [NetEncodeOpts::WithLength] for a compressed
+/// connection.
+pub fn compress_frames(mut frames: &[u8], threshold: usize) -> NetResult<Vec<u8>> {
+    let mut compressed = Vec::with_capacity(frames.len());
+
+    while !frames.is_empty() {
+        let length = VarInt::read(&mut frames)?.val as usize;
+        let (packet, rest) = frames.split_at(length.min(frames.len()));
+        compressed.extend(compress_packet(packet, threshold)?);
+        frames = rest;
+    }
+
+    Ok(compressed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::packets::incoming::packet_skeleton::PacketSkeleton;
+    use std::io::Read;
+
+    fn packet(id: u8, len: usize) -> Vec<u8> {
+        let mut packet = vec![id];
+        packet.extend((0..len).map(|i| (i % 7) as u8));
+        packet
+    }
+
+    #[tokio::test]
+    async fn test_round_trip() {
+        for packet in [packet(0x27, 10), packet(0x27, 4096)] {
+            let frame = compress_packet(&packet, 256).unwrap();
+
+            let mut reader = frame.as_slice();
+            let mut skeleton = PacketSkeleton::new(&mut reader, true).await.unwrap();
+            assert!(reader.is_empty());
+            assert_eq!(skeleton.id, 0x27);
+
+            let mut data = Vec::new();
+            skeleton.data.read_to_end(&mut data).unwrap();
+            assert_eq!(data, packet[1..]);
+        }
+    }
+
+    #[test]
+    fn test_small_packets_are_not_compressed() {
+        let frame = compress_packet(&packet(0x01, 3), 256).unwrap();
+        // packet length, data length (0), then the packet as is.
+        assert_eq!(frame[0], 5);
+        assert_eq!(frame[1], 0);
+        assert_eq!(&frame[2..], packet(0x01, 3).as_slice());
+    }
+
+    #[tokio::test]
+    async fn test_compress_frames() {
+        let mut frames = frame_packet(&packet(0x07, 1000)).unwrap();
+        frames.extend(frame_packet(&packet(0x03, 0)).unwrap());
+
+        let compressed = compress_frames(&frames, 256).unwrap();
+        let mut reader = compressed.as_slice();
+        assert_eq!(
+            PacketSkeleton::new(&mut reader, true).await.unwrap().id,
+            0x07
+        );
+        assert_eq!(
+            PacketSkeleton::new(&mut reader, true).await.unwrap().id,
+            0x03
+        );
+        assert!(reader.is_empty());
+    }
+}
@@ -1,11 +1,16 @@
+use crate::compression::{compress_frames, compress_packet};
 use crate::packets::incoming::packet_skeleton::PacketSkeleton;
+use crate::packets::packet_events::ConnectionClosedEvent;
 use crate::utils::state::terminate_connection;
 use crate::{handle_packet, NetResult};
+use ferrumc_events::infrastructure::Event;
 use ferrumc_net_codec::encode::NetEncode;
 use ferrumc_net_codec::encode::NetEncodeOpts;
+use ferrumc_net_encryption::cipher::{EncryptedReader, EncryptedWriter};
 use ferrumc_state::ServerState;
 use std::sync::Arc;
 use std::time::Duration;
+use tokio::io::AsyncWriteExt;
 use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
 use tokio::net::TcpStream;
 use tokio::time::timeout;
@@ -50,32 +55,97 @@ impl ConnectionState {
 }
 
 pub struct StreamReader {
-    pub reader: OwnedReadHalf,
+    pub reader: EncryptedReader<OwnedReadHalf>,
 }
 
 impl StreamReader {
     pub fn new(reader: OwnedReadHalf) -> Self {
-        Self { reader }
+        Self {
+            reader: EncryptedReader::new(reader),
+        }
+    }
+
+    pub fn enable_encryption(&mut self, shared_secret: &[u8]) -> NetResult<()> {
+        self.reader.enable(shared_secret)?;
+        Ok(())
     }
 }
 
 pub struct StreamWriter {
-    pub writer: OwnedWriteHalf,
+    pub writer: EncryptedWriter<OwnedWriteHalf>,
+    compression_threshold: Option<usize>,
 }
 
 impl StreamWriter {
     pub fn new(writer: OwnedWriteHalf) -> Self {
-        Self { writer }
+        Self {
+            writer: EncryptedWriter::new(writer),
+            compression_threshold: None,
+        }
     }
 
+    /// Compresses everything sent from now on. Must only be called after Set Compression has
+    /// been sent. The read half is switched over by the connection loop, see [CompressionStatus].
+    pub fn enable_compression(&mut self, threshold: usize) {
+        self.compression_threshold = Some(threshold);
+    }
+
+    pub fn compression_threshold(&self) -> Option<usize> {
+        self.compression_threshold
+    }
+
+    /// Encrypts everything sent from now on. The read half is switched over by the connection
+    /// loop, see [EncryptionStatus].
+    pub fn enable_encryption(&mut self, shared_secret: &[u8]) -> NetResult<()> {
+        self.writer.enable(shared_secret)?;
+        Ok(())
+    }
+
+    /// Sends a packet. Once compression is enabled, packets sent with
+    /// [NetEncodeOpts::WithLength] or [NetEncodeOpts::Compressed] are framed for it, while
+    /// [NetEncodeOpts::None] writes the encoded bytes as they are.
     pub async fn send_packet(
         &mut self,
         packet: &impl NetEncode,
         net_encode_opts: &NetEncodeOpts,
     ) -> NetResult<()> {
-        packet
-            .encode_async(&mut self.writer, net_encode_opts)
-            .await?;
+        match (net_encode_opts, self.compression_threshold) {
+            (NetEncodeOpts::WithLength | NetEncodeOpts::Compressed, Some(threshold)) => {
+                let mut buffer = Vec::new();
+                packet.encode(&mut buffer, &NetEncodeOpts::None)?;
+                self.writer
+                    .write_all(&compress_packet(&buffer, threshold)?)
+                    .await?;
+            }
+            // The client can't read compressed frames before Set Compression.
+            (NetEncodeOpts::Compressed, None) => {
+                packet
+                    .encode_async(&mut self.writer, &NetEncodeOpts::WithLength)
+                    .await?;
+            }
+            _ => {
+                packet
+                    .encode_async(&mut self.writer, net_encode_opts)
+                    .await?;
+            }
+        }
+        // The encrypted writer may still hold some of the packet.
+        self.writer.flush().await?;
+        Ok(())
+    }
+
+    /// Sends packets that were already encoded with [NetEncodeOpts::WithLength], re-framing
+    /// them if compression is enabled.
+    pub async fn send_encoded_frames(&mut self, frames: &[u8]) -> NetResult<()> {
+        match self.compression_threshold {
+            Some(threshold) => {
+                self.writer
+                    .write_all(&compress_frames(frames, threshold)?)
+                    .await?
+            }
+            None => self.writer.write_all(frames).await?,
+        }
+        self.writer.flush().await?;
         Ok(())
     }
 }
@@ -96,8 +166,24 @@ impl Default for CompressionStatus {
     }
 }
 
+/// Tracks the login encryption handshake of a connection.
+#[derive(Default)]
+pub struct EncryptionStatus {
+    /// The verify token sent in the Encryption Request, while waiting for the response.
+    pub verify_token: Option<[u8; 4]>,
+    /// The shared secret, once the handshake has succeeded.
+    pub shared_secret: Option<[u8; 16]>,
+}
+
+impl EncryptionStatus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
 pub async fn handle_connection(state: Arc<ServerState>, tcp_stream: TcpStream) -> NetResult<()> {
-    let (mut reader, writer) = tcp_stream.into_split();
+    let (reader, writer) = tcp_stream.into_split();
+    let mut reader = StreamReader::new(reader);
 
     let entity = state
         .universe
@@ -105,6 +191,7 @@ pub async fn handle_connection(state: Arc<ServerState>, tcp_stream: TcpStream) -
         .with(StreamWriter::new(writer))?
         .with(ConnectionState::Handshaking)?
         .with(CompressionStatus::new())?
+        .with(EncryptionStatus::new())?
         .with(ConnectionControl::new())?
         .build();
 
@@ -123,8 +210,24 @@ pub async fn handle_connection(state: Arc<ServerState>, tcp_stream: TcpStream) -
             break 'recv;
         }
 
+        // The client encrypts everything after its Encryption Response, so switch over as soon
+        // as the handshake is done and before reading anything else.
+        if !reader.reader.is_enabled() {
+            if let Some(shared_secret) = state
+                .universe
+                .get::<EncryptionStatus>(entity)?
+                .shared_secret
+            {
+                reader.enable_encryption(&shared_secret)?;
+            }
+        }
+
         let read_timeout = Duration::from_secs(2);
-        let packet_task = timeout(read_timeout, PacketSkeleton::new(&mut reader, compressed)).await;
+        let packet_task = timeout(
+            read_timeout,
+            PacketSkeleton::new(&mut reader.reader, compressed),
+        )
+        .await;
 
         if let Err(err) = packet_task {
             trace!(
@@ -174,6 +277,12 @@ pub async fn handle_connection(state: Arc<ServerState>, tcp_stream: TcpStream) -
 
     debug!("Connection closed for entity: {:?}", entity);
 
+    if let Err(e) =
+        ConnectionClosedEvent::trigger(ConnectionClosedEvent::new(entity), state.clone()).await
+    {
+        warn!("Failed to handle closed connection: {:?}", e);
+    }
+
     // Remove all components from the entity
 
     // Wait until anything that might be using the entity is done
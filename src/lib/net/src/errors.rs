@@ -43,6 +43,12 @@ pub enum NetError {
 
     #[error("{0}")]
     Chunk(#[from] ChunkError),
+
+    #[error("World Error: {0}")]
+    WorldError(#[from] ferrumc_world::errors::WorldError),
+
+    #[error("Core Error: {0}")]
+    CoreError(#[from] ferrumc_core::errors::CoreError),
 }
 
 #[derive(Debug, Error)]
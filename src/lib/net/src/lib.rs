@@ -3,6 +3,7 @@ extern crate core;
 
 use ferrumc_macros::bake_packet_registry;
 
+pub mod compression;
 pub mod connection;
 pub mod errors;
 pub mod packets;
@@ -0,0 +1,73 @@
+use crate::packets::IncomingPacket;
+use crate::NetResult;
+use ferrumc_events::infrastructure::Event;
+use ferrumc_macros::{packet, Event};
+use ferrumc_net_codec::decode::{NetDecode, NetDecodeOpts, NetDecodeResult};
+use ferrumc_net_codec::net_types::var_int::VarInt;
+use ferrumc_state::ServerState;
+use std::io::Read;
+use std::sync::Arc;
+
+#[derive(Debug)]
+#[packet(packet_id = 0x04, state = "play")]
+pub struct ChatCommandPacket {
+    /// The command, without the leading slash.
+    pub command: String,
+    pub timestamp: i64,
+    pub salt: i64,
+    /// Signatures for arguments that are signed messages. Secure chat isn't enforced, so they're
+    /// never checked.
+    pub argument_signatures: Vec<(String, [u8; 256])>,
+    pub message_count: VarInt,
+    pub acknowledged: [u8; 3],
+}
+
+// Decoded by hand, since the signatures are fixed size arrays.
+impl NetDecode for ChatCommandPacket {
+    fn decode<R: Read>(reader: &mut R, opts: &NetDecodeOpts) -> NetDecodeResult<Self> {
+        let command = String::decode(reader, opts)?;
+        let timestamp = i64::decode(reader, opts)?;
+        let salt = i64::decode(reader, opts)?;
+        let signature_count = VarInt::decode(reader, opts)?;
+        let mut argument_signatures = Vec::new();
+        for _ in 0..signature_count.val {
+            let name = String::decode(reader, opts)?;
+            let mut signature = [0; 256];
+            reader.read_exact(&mut signature)?;
+            argument_signatures.push((name, signature));
+        }
+        let message_count = VarInt::decode(reader, opts)?;
+        let mut acknowledged = [0; 3];
+        reader.read_exact(&mut acknowledged)?;
+
+        Ok(Self {
+            command,
+            timestamp,
+            salt,
+            argument_signatures,
+            message_count,
+            acknowledged,
+        })
+    }
+}
+
+impl IncomingPacket for ChatCommandPacket {
+    async fn handle(self, conn_id: usize, state: Arc<ServerState>) -> NetResult<()> {
+        ChatCommandEvent::trigger(ChatCommandEvent::new(conn_id, self.command), state).await?;
+        Ok(())
+    }
+}
+
+/// A player ran a command.
+#[derive(Event, Debug)]
+pub struct ChatCommandEvent {
+    pub conn_id: usize,
+    /// The command, without the leading slash.
+    pub command: String,
+}
+
+impl ChatCommandEvent {
+    pub fn new(conn_id: usize, command: String) -> Self {
+        Self { conn_id, command }
+    }
+}
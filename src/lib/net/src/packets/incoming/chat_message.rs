@@ -0,0 +1,80 @@
+use crate::packets::IncomingPacket;
+use crate::NetResult;
+use ferrumc_events::infrastructure::Event;
+use ferrumc_macros::{packet, Event};
+use ferrumc_net_codec::decode::{NetDecode, NetDecodeOpts, NetDecodeResult};
+use ferrumc_net_codec::net_types::var_int::VarInt;
+use ferrumc_state::ServerState;
+use std::io::Read;
+use std::sync::Arc;
+
+#[derive(Debug)]
+#[packet(packet_id = 0x06, state = "play")]
+pub struct ChatMessagePacket {
+    pub message: String,
+    pub timestamp: i64,
+    pub salt: i64,
+    /// Only sent by clients with a profile key. Secure chat isn't enforced, so it's never checked.
+    pub signature: Option<[u8; 256]>,
+    pub message_count: VarInt,
+    pub acknowledged: [u8; 3],
+}
+
+// Decoded by hand, since the signature is only present if the preceding bool is true.
+impl NetDecode for ChatMessagePacket {
+    fn decode<R: Read>(reader: &mut R, opts: &NetDecodeOpts) -> NetDecodeResult<Self> {
+        let message = String::decode(reader, opts)?;
+        let timestamp = i64::decode(reader, opts)?;
+        let salt = i64::decode(reader, opts)?;
+        let signature = if bool::decode(reader, opts)? {
+            let mut signature = [0; 256];
+            reader.read_exact(&mut signature)?;
+            Some(signature)
+        } else {
+            None
+        };
+        let message_count = VarInt::decode(reader, opts)?;
+        let mut acknowledged = [0; 3];
+        reader.read_exact(&mut acknowledged)?;
+
+        Ok(Self {
+            message,
+            timestamp,
+            salt,
+            signature,
+            message_count,
+            acknowledged,
+        })
+    }
+}
+
+impl IncomingPacket for ChatMessagePacket {
+    async fn handle(self, conn_id: usize, state: Arc<ServerState>) -> NetResult<()> {
+        ChatEvent::trigger(ChatEvent::new(conn_id, self.message), state).await?;
+        Ok(())
+    }
+}
+
+/// A player sent a chat message.
+///
+/// Listeners can change the message, or cancel it to stop it from being broadcast.
+#[derive(Event, Debug)]
+pub struct ChatEvent {
+    pub conn_id: usize,
+    pub message: String,
+    pub cancelled: bool,
+}
+
+impl ChatEvent {
+    pub fn new(conn_id: usize, message: String) -> Self {
+        Self {
+            conn_id,
+            message,
+            cancelled: false,
+        }
+    }
+
+    pub fn cancel(&mut self) {
+        self.cancelled = true;
+    }
+}
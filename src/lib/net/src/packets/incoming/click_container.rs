@@ -0,0 +1,99 @@
+use crate::packets::IncomingPacket;
+use crate::NetResult;
+use ferrumc_events::infrastructure::Event;
+use ferrumc_macros::{packet, Event};
+use ferrumc_net_codec::decode::errors::NetDecodeError;
+use ferrumc_net_codec::decode::{NetDecode, NetDecodeOpts, NetDecodeResult};
+use ferrumc_net_codec::net_types::slot::Slot;
+use ferrumc_net_codec::net_types::var_int::VarInt;
+use ferrumc_state::ServerState;
+use std::io::Read;
+use std::sync::Arc;
+
+/// The player clicked a slot in an open window, or outside of it.
+///
+/// The client works out what the click does itself and sends the result along with the click.
+#[derive(Debug)]
+#[packet(packet_id = 0x0E, state = "play")]
+pub struct ClickContainerPacket {
+    pub window_id: u8,
+    /// The state ID of the contents the client saw when it clicked.
+    pub state_id: VarInt,
+    /// The clicked slot, or -999 for outside the window.
+    pub slot: i16,
+    pub button: i8,
+    pub mode: VarInt,
+    /// What the client predicts the click does, or `None` if one of the stacks has an item
+    /// component that isn't supported.
+    pub result: Option<ClickResult>,
+}
+
+#[derive(Debug)]
+pub struct ClickResult {
+    /// The slots that changed, with their new contents.
+    pub changed_slots: Vec<(i16, Slot)>,
+    /// The stack left on the cursor.
+    pub carried_item: Slot,
+}
+
+impl NetDecode for ClickResult {
+    fn decode<R: Read>(reader: &mut R, opts: &NetDecodeOpts) -> NetDecodeResult<Self> {
+        let count = VarInt::decode(reader, opts)?.val;
+        let mut changed_slots = Vec::new();
+        for _ in 0..count {
+            changed_slots.push((i16::decode(reader, opts)?, Slot::decode(reader, opts)?));
+        }
+        Ok(Self {
+            changed_slots,
+            carried_item: Slot::decode(reader, opts)?,
+        })
+    }
+}
+
+/// Decodes something at the end of a packet that may contain stacks with unsupported item
+/// components, skipping the rest of the packet if it does.
+pub(crate) fn decode_items<T: NetDecode, R: Read>(
+    reader: &mut R,
+    opts: &NetDecodeOpts,
+) -> NetDecodeResult<Option<T>> {
+    match T::decode(reader, opts) {
+        Ok(value) => Ok(Some(value)),
+        Err(NetDecodeError::UnsupportedItemComponent(_)) => {
+            std::io::copy(reader, &mut std::io::sink())?;
+            Ok(None)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+impl NetDecode for ClickContainerPacket {
+    fn decode<R: Read>(reader: &mut R, opts: &NetDecodeOpts) -> NetDecodeResult<Self> {
+        Ok(Self {
+            window_id: u8::decode(reader, opts)?,
+            state_id: VarInt::decode(reader, opts)?,
+            slot: i16::decode(reader, opts)?,
+            button: i8::decode(reader, opts)?,
+            mode: VarInt::decode(reader, opts)?,
+            result: decode_items(reader, opts)?,
+        })
+    }
+}
+
+impl IncomingPacket for ClickContainerPacket {
+    async fn handle(self, conn_id: usize, state: Arc<ServerState>) -> NetResult<()> {
+        ClickContainerEvent::trigger(ClickContainerEvent::new(conn_id, self), state).await?;
+        Ok(())
+    }
+}
+
+#[derive(Event, Debug)]
+pub struct ClickContainerEvent {
+    pub conn_id: usize,
+    pub packet: ClickContainerPacket,
+}
+
+impl ClickContainerEvent {
+    pub fn new(conn_id: usize, packet: ClickContainerPacket) -> Self {
+        Self { conn_id, packet }
+    }
+}
@@ -8,6 +8,7 @@ use tracing::debug;
 
 #[derive(Debug, NetDecode)]
 #[packet(packet_id = 0x00, state = "configuration")]
+#[packet(packet_id = 0x0A, state = "play")]
 pub struct ClientInformation {
     pub locale: String,
     pub view_distance: u8,
@@ -40,6 +41,7 @@ impl IncomingPacket for ClientInformation {
     async fn handle(self, conn_id: usize, state: Arc<ServerState>) -> NetResult<()> {
         debug!("Received client information: {:#?}", self);
 
+        // Sent again in play whenever the settings change, so this replaces the old one.
         state.universe.add_component(conn_id, self)?;
 
         Ok(())
@@ -0,0 +1,44 @@
+use crate::packets::IncomingPacket;
+use crate::NetResult;
+use ferrumc_events::infrastructure::Event;
+use ferrumc_macros::{packet, Event, NetDecode};
+use ferrumc_net_codec::net_types::var_int::VarInt;
+use ferrumc_state::ServerState;
+use std::sync::Arc;
+
+#[derive(Debug, NetDecode)]
+#[packet(packet_id = 0x0B, state = "play")]
+pub struct CommandSuggestionRequestPacket {
+    pub transaction_id: VarInt,
+    /// Everything the player has typed so far, including the leading slash.
+    pub text: String,
+}
+
+impl IncomingPacket for CommandSuggestionRequestPacket {
+    async fn handle(self, conn_id: usize, state: Arc<ServerState>) -> NetResult<()> {
+        CommandSuggestionRequestEvent::trigger(
+            CommandSuggestionRequestEvent::new(self, conn_id),
+            state,
+        )
+        .await?;
+        Ok(())
+    }
+}
+
+#[derive(Event)]
+pub struct CommandSuggestionRequestEvent {
+    pub command_suggestion_request_packet: CommandSuggestionRequestPacket,
+    pub conn_id: usize,
+}
+
+impl CommandSuggestionRequestEvent {
+    pub fn new(
+        command_suggestion_request_packet: CommandSuggestionRequestPacket,
+        conn_id: usize,
+    ) -> Self {
+        Self {
+            command_suggestion_request_packet,
+            conn_id,
+        }
+    }
+}
@@ -0,0 +1,37 @@
+use crate::packets::IncomingPacket;
+use crate::NetResult;
+use ferrumc_events::infrastructure::Event;
+use ferrumc_macros::{packet, Event, NetDecode};
+use ferrumc_net_codec::net_types::var_int::VarInt;
+use ferrumc_state::ServerState;
+use std::sync::Arc;
+
+/// Sent by the client once it has moved to a position from a Synchronize Player Position packet.
+#[derive(Debug, NetDecode)]
+#[packet(packet_id = 0x00, state = "play")]
+pub struct ConfirmTeleportationPacket {
+    pub teleport_id: VarInt,
+}
+
+impl IncomingPacket for ConfirmTeleportationPacket {
+    async fn handle(self, conn_id: usize, state: Arc<ServerState>) -> NetResult<()> {
+        let event = ConfirmTeleportationEvent::new(conn_id, self.teleport_id.val);
+        ConfirmTeleportationEvent::trigger(event, state).await?;
+        Ok(())
+    }
+}
+
+#[derive(Event, Debug)]
+pub struct ConfirmTeleportationEvent {
+    pub conn_id: usize,
+    pub teleport_id: i32,
+}
+
+impl ConfirmTeleportationEvent {
+    pub fn new(conn_id: usize, teleport_id: i32) -> Self {
+        Self {
+            conn_id,
+            teleport_id,
+        }
+    }
+}
@@ -0,0 +1,40 @@
+use crate::packets::IncomingPacket;
+use crate::NetResult;
+use ferrumc_events::infrastructure::Event;
+use ferrumc_macros::{packet, Event, NetDecode};
+use ferrumc_net_codec::net_types::length_prefixed_vec::LengthPrefixedVec;
+use ferrumc_state::ServerState;
+use std::sync::Arc;
+
+#[derive(Debug, NetDecode)]
+#[packet(packet_id = 0x01, state = "login")]
+pub struct EncryptionResponsePacket {
+    /// The shared secret, encrypted with the server's public key.
+    pub shared_secret: LengthPrefixedVec<u8>,
+    /// The verify token, encrypted with the server's public key.
+    pub verify_token: LengthPrefixedVec<u8>,
+}
+
+impl IncomingPacket for EncryptionResponsePacket {
+    async fn handle(self, conn_id: usize, state: Arc<ServerState>) -> NetResult<()> {
+        // Not spawned: encryption has to be enabled before the next packet is read.
+        EncryptionResponseEvent::trigger(EncryptionResponseEvent::new(self, conn_id), state)
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(Event)]
+pub struct EncryptionResponseEvent {
+    pub encryption_response_packet: EncryptionResponsePacket,
+    pub conn_id: usize,
+}
+
+impl EncryptionResponseEvent {
+    pub fn new(encryption_response_packet: EncryptionResponsePacket, conn_id: usize) -> Self {
+        Self {
+            encryption_response_packet,
+            conn_id,
+        }
+    }
+}
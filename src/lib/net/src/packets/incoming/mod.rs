@@ -1,5 +1,9 @@
 pub mod ack_finish_configuration;
+pub mod chat_command;
+pub mod chat_message;
 pub mod client_information;
+pub mod command_suggestion_request;
+pub mod encryption_response;
 pub mod handshake;
 pub mod login_acknowledged;
 pub mod login_start;
@@ -11,6 +15,15 @@ pub mod status_request;
 pub mod keep_alive;
 pub mod packet_skeleton;
 
+pub mod confirm_teleportation;
 pub mod set_player_position;
 pub mod set_player_position_and_rotation;
 pub mod set_player_rotation;
+
+pub mod player_action;
+pub mod swing_arm;
+pub mod use_item_on;
+
+pub mod click_container;
+pub mod set_creative_mode_slot;
+pub mod set_held_item;
@@ -50,17 +50,19 @@ impl PacketSkeleton {
     #[inline(always)]
     async fn read_compressed<R: AsyncRead + Unpin>(reader: &mut R) -> NetResult<Self> {
         let packet_length = VarInt::read_async(reader).await?.val as usize;
-        let data_length = VarInt::read_async(reader).await?.val as usize;
 
-        // Uncompressed packet when data length is 0
-        if data_length == 0 {
-            let mut buf = {
-                let mut buf = vec![0; packet_length];
-                reader.read_exact(&mut buf).await?;
+        // Read the whole frame first, so nothing of the next packet is consumed.
+        let mut buf = {
+            let mut buf = vec![0; packet_length];
+            reader.read_exact(&mut buf).await?;
+
+            Cursor::new(buf)
+        };
 
-                Cursor::new(buf)
-            };
+        let data_length = VarInt::read(&mut buf)?.val as usize;
 
+        // Uncompressed packet when data length is 0
+        if data_length == 0 {
             let id = VarInt::read(&mut buf)?;
 
             return Ok(Self {
@@ -83,16 +85,8 @@ impl PacketSkeleton {
             ));
         }
 
-        // Here, guaranteed that data_length >= compression_threshold
-        let mut buf = {
-            let mut buf = vec![];
-            reader.read_to_end(&mut buf).await?;
-
-            Cursor::new(buf)
-        };
-
         // Decompress data
-        let mut decompressed = Vec::new();
+        let mut decompressed = Vec::with_capacity(data_length);
         {
             // Scope for decoder
             let mut decoder = flate2::read::ZlibDecoder::new(&mut buf);
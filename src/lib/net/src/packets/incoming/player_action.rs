@@ -0,0 +1,55 @@
+use crate::packets::IncomingPacket;
+use crate::NetResult;
+use ferrumc_events::infrastructure::Event;
+use ferrumc_macros::{packet, Event, NetDecode};
+use ferrumc_net_codec::net_types::network_position::NetworkPosition;
+use ferrumc_net_codec::net_types::var_int::VarInt;
+use ferrumc_state::ServerState;
+use std::sync::Arc;
+
+#[derive(Debug, NetDecode)]
+#[packet(packet_id = 0x24, state = "play")]
+pub struct PlayerActionPacket {
+    pub status: PlayerActionStatus,
+    pub location: NetworkPosition,
+    /// The face of the block being hit, in the same order as [super::use_item_on::BlockFace].
+    pub face: u8,
+    /// Sequence number to acknowledge the resulting block changes with.
+    pub sequence: VarInt,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, NetDecode)]
+#[net(type_cast = "VarInt", type_cast_handler = "value.val as u8")]
+#[repr(u8)]
+pub enum PlayerActionStatus {
+    StartedDigging,
+    CancelledDigging,
+    FinishedDigging,
+    DropItemStack,
+    DropItem,
+    /// Also used for finishing eating.
+    ShootArrow,
+    SwapItemInHand,
+}
+
+impl IncomingPacket for PlayerActionPacket {
+    async fn handle(self, conn_id: usize, state: Arc<ServerState>) -> NetResult<()> {
+        PlayerActionEvent::trigger(PlayerActionEvent::new(self, conn_id), state).await?;
+        Ok(())
+    }
+}
+
+#[derive(Event)]
+pub struct PlayerActionEvent {
+    pub player_action_packet: PlayerActionPacket,
+    pub conn_id: usize,
+}
+
+impl PlayerActionEvent {
+    pub fn new(player_action_packet: PlayerActionPacket, conn_id: usize) -> Self {
+        Self {
+            player_action_packet,
+            conn_id,
+        }
+    }
+}
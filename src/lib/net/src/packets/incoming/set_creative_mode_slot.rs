@@ -0,0 +1,49 @@
+use crate::packets::incoming::click_container::decode_items;
+use crate::packets::IncomingPacket;
+use crate::NetResult;
+use ferrumc_events::infrastructure::Event;
+use ferrumc_macros::{packet, Event};
+use ferrumc_net_codec::decode::{NetDecode, NetDecodeOpts, NetDecodeResult};
+use ferrumc_net_codec::net_types::slot::Slot;
+use ferrumc_state::ServerState;
+use std::io::Read;
+use std::sync::Arc;
+
+/// A player in creative mode put a stack in a slot of their inventory, or dropped it.
+#[derive(Debug)]
+#[packet(packet_id = 0x32, state = "play")]
+pub struct SetCreativeModeSlotPacket {
+    /// The slot in the player inventory window, or -1 to drop the stack.
+    pub slot: i16,
+    /// The new stack, or `None` if it has an item component that isn't supported.
+    pub clicked_item: Option<Slot>,
+}
+
+impl NetDecode for SetCreativeModeSlotPacket {
+    fn decode<R: Read>(reader: &mut R, opts: &NetDecodeOpts) -> NetDecodeResult<Self> {
+        Ok(Self {
+            slot: i16::decode(reader, opts)?,
+            clicked_item: decode_items(reader, opts)?,
+        })
+    }
+}
+
+impl IncomingPacket for SetCreativeModeSlotPacket {
+    async fn handle(self, conn_id: usize, state: Arc<ServerState>) -> NetResult<()> {
+        SetCreativeModeSlotEvent::trigger(SetCreativeModeSlotEvent::new(conn_id, self), state)
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(Event, Debug)]
+pub struct SetCreativeModeSlotEvent {
+    pub conn_id: usize,
+    pub packet: SetCreativeModeSlotPacket,
+}
+
+impl SetCreativeModeSlotEvent {
+    pub fn new(conn_id: usize, packet: SetCreativeModeSlotPacket) -> Self {
+        Self { conn_id, packet }
+    }
+}
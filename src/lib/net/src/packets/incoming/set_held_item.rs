@@ -0,0 +1,33 @@
+use crate::packets::IncomingPacket;
+use crate::NetResult;
+use ferrumc_events::infrastructure::Event;
+use ferrumc_macros::{packet, Event, NetDecode};
+use ferrumc_state::ServerState;
+use std::sync::Arc;
+
+/// The player selected a hotbar slot.
+#[derive(Debug, NetDecode)]
+#[packet(packet_id = 0x2F, state = "play")]
+pub struct SetHeldItemPacket {
+    /// From 0 to 8.
+    pub slot: i16,
+}
+
+impl IncomingPacket for SetHeldItemPacket {
+    async fn handle(self, conn_id: usize, state: Arc<ServerState>) -> NetResult<()> {
+        SetHeldItemEvent::trigger(SetHeldItemEvent::new(conn_id, self.slot), state).await?;
+        Ok(())
+    }
+}
+
+#[derive(Event, Debug)]
+pub struct SetHeldItemEvent {
+    pub conn_id: usize,
+    pub slot: i16,
+}
+
+impl SetHeldItemEvent {
+    pub fn new(conn_id: usize, slot: i16) -> Self {
+        Self { conn_id, slot }
+    }
+}
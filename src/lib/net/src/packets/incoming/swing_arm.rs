@@ -0,0 +1,35 @@
+use crate::packets::incoming::use_item_on::Hand;
+use crate::packets::IncomingPacket;
+use crate::NetResult;
+use ferrumc_events::infrastructure::Event;
+use ferrumc_macros::{packet, Event, NetDecode};
+use ferrumc_state::ServerState;
+use std::sync::Arc;
+
+#[derive(Debug, NetDecode)]
+#[packet(packet_id = 0x36, state = "play")]
+pub struct SwingArmPacket {
+    pub hand: Hand,
+}
+
+impl IncomingPacket for SwingArmPacket {
+    async fn handle(self, conn_id: usize, state: Arc<ServerState>) -> NetResult<()> {
+        SwingArmEvent::trigger(SwingArmEvent::new(self, conn_id), state).await?;
+        Ok(())
+    }
+}
+
+#[derive(Event)]
+pub struct SwingArmEvent {
+    pub swing_arm_packet: SwingArmPacket,
+    pub conn_id: usize,
+}
+
+impl SwingArmEvent {
+    pub fn new(swing_arm_packet: SwingArmPacket, conn_id: usize) -> Self {
+        Self {
+            swing_arm_packet,
+            conn_id,
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use crate::packets::IncomingPacket;
+use crate::NetResult;
+use ferrumc_events::infrastructure::Event;
+use ferrumc_macros::{packet, Event, NetDecode};
+use ferrumc_net_codec::net_types::network_position::NetworkPosition;
+use ferrumc_net_codec::net_types::var_int::VarInt;
+use ferrumc_state::ServerState;
+use ferrumc_world::block_state::Facing;
+use std::sync::Arc;
+
+#[derive(Debug, NetDecode)]
+#[packet(packet_id = 0x38, state = "play")]
+pub struct UseItemOnPacket {
+    pub hand: Hand,
+    pub location: NetworkPosition,
+    pub face: BlockFace,
+    pub cursor_x: f32,
+    pub cursor_y: f32,
+    pub cursor_z: f32,
+    /// Whether the player's head is inside a block.
+    pub inside_block: bool,
+    /// Sequence number to acknowledge the resulting block changes with.
+    pub sequence: VarInt,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, NetDecode)]
+#[net(type_cast = "VarInt", type_cast_handler = "value.val as u8")]
+#[repr(u8)]
+pub enum Hand {
+    Main,
+    Off,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, NetDecode)]
+#[net(type_cast = "VarInt", type_cast_handler = "value.val as u8")]
+#[repr(u8)]
+pub enum BlockFace {
+    Bottom,
+    Top,
+    North,
+    South,
+    West,
+    East,
+}
+
+impl BlockFace {
+    /// The offset from a block to its neighbour on this face.
+    pub fn offset(self) -> (i32, i32, i32) {
+        match self {
+            BlockFace::Bottom => (0, -1, 0),
+            BlockFace::Top => (0, 1, 0),
+            BlockFace::North => (0, 0, -1),
+            BlockFace::South => (0, 0, 1),
+            BlockFace::West => (-1, 0, 0),
+            BlockFace::East => (1, 0, 0),
+        }
+    }
+
+    /// The direction this face points in.
+    pub fn facing(self) -> Facing {
+        match self {
+            BlockFace::Bottom => Facing::Down,
+            BlockFace::Top => Facing::Up,
+            BlockFace::North => Facing::North,
+            BlockFace::South => Facing::South,
+            BlockFace::West => Facing::West,
+            BlockFace::East => Facing::East,
+        }
+    }
+}
+
+impl IncomingPacket for UseItemOnPacket {
+    async fn handle(self, conn_id: usize, state: Arc<ServerState>) -> NetResult<()> {
+        UseItemOnEvent::trigger(UseItemOnEvent::new(self, conn_id), state).await?;
+        Ok(())
+    }
+}
+
+#[derive(Event)]
+pub struct UseItemOnEvent {
+    pub use_item_on_packet: UseItemOnPacket,
+    pub conn_id: usize,
+}
+
+impl UseItemOnEvent {
+    pub fn new(use_item_on_packet: UseItemOnPacket, conn_id: usize) -> Self {
+        Self {
+            use_item_on_packet,
+            conn_id,
+        }
+    }
+}
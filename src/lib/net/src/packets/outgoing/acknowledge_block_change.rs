@@ -0,0 +1,17 @@
+use ferrumc_macros::{packet, NetEncode};
+use ferrumc_net_codec::net_types::var_int::VarInt;
+use std::io::Write;
+
+/// Tells the client that the block changes it predicted up to `sequence` have been handled, so it
+/// should use the server's block states from now on.
+#[derive(NetEncode)]
+#[packet(packet_id = 0x05)]
+pub struct AcknowledgeBlockChangePacket {
+    pub sequence: VarInt,
+}
+
+impl AcknowledgeBlockChangePacket {
+    pub fn new(sequence: VarInt) -> Self {
+        Self { sequence }
+    }
+}
@@ -0,0 +1,20 @@
+use ferrumc_macros::{packet, NetEncode};
+use ferrumc_net_codec::net_types::network_position::NetworkPosition;
+use ferrumc_net_codec::net_types::var_int::VarInt;
+use std::io::Write;
+
+#[derive(NetEncode)]
+#[packet(packet_id = 0x09)]
+pub struct BlockUpdatePacket {
+    pub location: NetworkPosition,
+    pub block_id: VarInt,
+}
+
+impl BlockUpdatePacket {
+    pub fn new(location: NetworkPosition, block_id: i32) -> Self {
+        Self {
+            location,
+            block_id: VarInt::new(block_id),
+        }
+    }
+}
@@ -0,0 +1,21 @@
+use ferrumc_macros::{packet, NetEncode};
+use ferrumc_world::level::LevelInfo;
+use std::io::Write;
+
+/// Tells the client the world's difficulty, shown in its options menu.
+#[derive(NetEncode)]
+#[packet(packet_id = 0x0B)]
+pub struct ChangeDifficultyPacket {
+    /// 0 to 3, for peaceful to hard.
+    pub difficulty: u8,
+    pub locked: bool,
+}
+
+impl ChangeDifficultyPacket {
+    pub fn from_level(level: &LevelInfo) -> Self {
+        Self {
+            difficulty: level.difficulty,
+            locked: level.difficulty_locked,
+        }
+    }
+}
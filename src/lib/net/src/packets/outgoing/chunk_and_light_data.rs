@@ -1,16 +1,15 @@
 use crate::errors::NetError;
 use byteorder::{BigEndian, WriteBytesExt};
+use ferrumc_core::dimensions::dimension::Dimension;
 use ferrumc_macros::{packet, NetEncode};
 use ferrumc_net_codec::net_types::bitset::BitSet;
 use ferrumc_net_codec::net_types::length_prefixed_vec::LengthPrefixedVec;
 use ferrumc_net_codec::net_types::var_int::VarInt;
 use ferrumc_world::chunk_format::{Chunk, Heightmaps};
+use ferrumc_world::lighting::{LightKind, LIGHT_DATA_LEN};
 use std::io::{Cursor, Write};
-use std::ops::Not;
 use tracing::warn;
 
-const SECTIONS: usize = 24; // Number of sections, adjust for your Y range (-64 to 319)
-
 #[derive(NetEncode)]
 pub struct BlockEntity {
     pub xz: u8,
@@ -28,6 +27,16 @@ pub struct ChunkAndLightData {
     pub heightmaps: Vec<u8>,
     pub data: LengthPrefixedVec<u8>,
     pub block_entities: LengthPrefixedVec<BlockEntity>,
+    pub light: LightData,
+}
+
+/// A chunk's light, as sent with the chunk and in [super::update_light::UpdateLightPacket].
+///
+/// Bit `i + 1` of each mask is for the `i`th section from the bottom of the dimension, and the
+/// bits on either side are for the sections just below and above it. Sections in neither mask
+/// keep whatever light the client already has.
+#[derive(NetEncode)]
+pub struct LightData {
     pub sky_light_mask: BitSet,
     pub block_light_mask: BitSet,
     pub empty_sky_light_mask: BitSet,
@@ -36,66 +45,144 @@ pub struct ChunkAndLightData {
     pub block_light_arrays: LengthPrefixedVec<LengthPrefixedVec<u8>>,
 }
 
+/// Light data where every block is as bright as it gets.
+const FULL_LIGHT: [u8; LIGHT_DATA_LEN] = [0xFF; LIGHT_DATA_LEN];
+
+impl LightData {
+    /// The light of an empty chunk: full sky light in dimensions that have it, and no block
+    /// light.
+    pub fn empty(dimension: Dimension) -> Self {
+        let masks = (dimension.height() / 16) as usize + 2;
+        let mut sky_light_mask = BitSet::new(masks);
+        let mut empty_sky_light_mask = BitSet::new(masks);
+        let mut empty_block_light_mask = BitSet::new(masks);
+        empty_block_light_mask.set_all(true);
+        let mut sky_light_arrays = Vec::new();
+        for i in 0..masks {
+            if dimension.has_skylight() && i > 0 {
+                sky_light_mask.set(i, true);
+                sky_light_arrays.push(LengthPrefixedVec::new(FULL_LIGHT.to_vec()));
+            } else {
+                empty_sky_light_mask.set(i, true);
+            }
+        }
+        LightData {
+            sky_light_mask,
+            block_light_mask: BitSet::new(masks),
+            empty_sky_light_mask,
+            empty_block_light_mask,
+            sky_light_arrays: LengthPrefixedVec::new(sky_light_arrays),
+            block_light_arrays: LengthPrefixedVec::new(Vec::new()),
+        }
+    }
+
+    /// The light of a chunk. Sections that haven't been lit are left out.
+    pub fn from_chunk(chunk: &Chunk) -> Self {
+        let (sky_light_mask, empty_sky_light_mask, sky_light_arrays) =
+            Self::encode_light(chunk, LightKind::Sky);
+        let (block_light_mask, empty_block_light_mask, block_light_arrays) =
+            Self::encode_light(chunk, LightKind::Block);
+        LightData {
+            sky_light_mask,
+            block_light_mask,
+            empty_sky_light_mask,
+            empty_block_light_mask,
+            sky_light_arrays,
+            block_light_arrays,
+        }
+    }
+
+    fn encode_light(
+        chunk: &Chunk,
+        kind: LightKind,
+    ) -> (BitSet, BitSet, LengthPrefixedVec<LengthPrefixedVec<u8>>) {
+        let dimension = chunk.dimension_kind();
+        let sections = (chunk.height() / 16) as usize;
+        let mut mask = BitSet::new(sections + 2);
+        let mut empty_mask = BitSet::new(sections + 2);
+        let mut arrays = Vec::new();
+        if kind == LightKind::Sky && !dimension.has_skylight() {
+            empty_mask.set_all(true);
+            return (mask, empty_mask, LengthPrefixedVec::new(arrays));
+        }
+
+        // Below the bottom of the world it's always dark
+        empty_mask.set(0, true);
+        let min_section = chunk.min_y().div_euclid(16);
+        for i in 0..sections {
+            let section_y = min_section + i as i32;
+            let Some(light) = chunk
+                .sections
+                .iter()
+                .find(|section| i32::from(section.y) == section_y)
+                .map(|section| section.light(kind))
+                .filter(|light| light.len() == LIGHT_DATA_LEN)
+            else {
+                continue;
+            };
+            if light.iter().all(|&byte| byte == 0) {
+                empty_mask.set(i + 1, true);
+            } else {
+                mask.set(i + 1, true);
+                arrays.push(LengthPrefixedVec::new(light.to_vec()));
+            }
+        }
+        // Above the top of the world the sky is as bright as it gets
+        match kind {
+            LightKind::Sky => {
+                mask.set(sections + 1, true);
+                arrays.push(LengthPrefixedVec::new(FULL_LIGHT.to_vec()));
+            }
+            LightKind::Block => empty_mask.set(sections + 1, true),
+        }
+        (mask, empty_mask, LengthPrefixedVec::new(arrays))
+    }
+}
+
 impl ChunkAndLightData {
-    pub fn empty(chunk_x: i32, chunk_z: i32) -> Self {
-        let sky_light_arrays = (0..SECTIONS)
-            .map(|_| LengthPrefixedVec::new(vec![0; 2048]))
-            .collect();
-        let block_light_arrays = (0..SECTIONS)
-            .map(|_| LengthPrefixedVec::new(vec![0; 2048]))
-            .collect();
-        let mut empty_sky_light_mask = BitSet::new(SECTIONS + 2);
-        empty_sky_light_mask.set_all(false);
-        let mut empty_block_light_mask = BitSet::new(SECTIONS + 2);
-        empty_block_light_mask.set_all(false);
+    /// A chunk of air as tall as the dimension.
+    pub fn empty(chunk_x: i32, chunk_z: i32, dimension: Dimension) -> Self {
+        let sections = (dimension.height() / 16) as usize;
         ChunkAndLightData {
             chunk_x,
             chunk_z,
             heightmaps: Heightmaps::new().serialize_as_network(),
-            data: LengthPrefixedVec::new(vec![0; SECTIONS * 10]),
+            data: LengthPrefixedVec::new(vec![0; sections * 10]),
             block_entities: LengthPrefixedVec::new(Vec::new()),
-            sky_light_mask: BitSet::new(SECTIONS),
-            block_light_mask: BitSet::new(SECTIONS),
-            empty_sky_light_mask,
-            empty_block_light_mask,
-            sky_light_arrays: LengthPrefixedVec::new(sky_light_arrays),
-            block_light_arrays: LengthPrefixedVec::new(block_light_arrays),
+            light: LightData::empty(dimension),
         }
     }
 
     pub fn from_chunk(chunk: &Chunk) -> Result<Self, NetError> {
         let mut data = Cursor::new(Vec::new());
-        let mut sky_light_data = Vec::new();
-        let mut block_light_data = Vec::new();
-        for section in &chunk.sections {
-            let section_sky_light_data = if section.sky_light.len() != 2048 {
-                warn!(
-                    "Sky light data for section at {}, {} is not 2048 bytes long",
-                    chunk.x, chunk.z
-                );
-                vec![255; 2048]
-            } else {
-                section.sky_light.clone()
-            };
-            sky_light_data.push(section_sky_light_data);
-            let section_block_light_data = if section.block_light.len() != 2048 {
-                warn!(
-                    "Block light data for section at {}, {} is not 2048 bytes long",
-                    chunk.x, chunk.z
-                );
-                vec![255; 2048]
-            } else {
-                section.block_light.clone()
+        let min_section = chunk.min_y().div_euclid(16);
+        // Sections outside of the dimension only hold light, so they aren't sent here
+        for section_y in min_section..min_section + chunk.height() / 16 {
+            let Some(section) = chunk
+                .sections
+                .iter()
+                .find(|section| i32::from(section.y) == section_y)
+            else {
+                // A section of air, with the single-value palette for both blocks and biomes
+                data.write_all(&[0; 8])?;
+                continue;
             };
-            block_light_data.push(section_block_light_data);
 
             data.write_u16::<BigEndian>(section.block_states.non_air_blocks)?;
 
             let bits_per_block = section.block_states.bits_per_block;
             data.write_u8(bits_per_block)?;
             // If bits_per_block is 0, the section is using the single-value palette format
-            // If bits_per_block is greater than 0, the section is using the indirect palette format
-            if bits_per_block > 0 {
+            // If bits_per_block is greater than 0 without a palette, the section is using the
+            // direct palette format
+            // Otherwise, the section is using the indirect palette format
+            if bits_per_block > 0 && section.block_states.palette.is_empty() {
+                // Write the data, there is no palette
+                VarInt::new(section.block_states.data.len() as i32).write(&mut data)?;
+                for data_entry in &section.block_states.data {
+                    data.write_i64::<BigEndian>(*data_entry)?;
+                }
+            } else if bits_per_block > 0 {
                 // Write the palette
                 VarInt::new(section.block_states.palette.len() as i32).write(&mut data)?;
                 for palette_entry in &section.block_states.palette {
@@ -131,49 +218,13 @@ impl ChunkAndLightData {
             data.write_u8(0)?;
             data.write_u8(0)?;
         }
-        let mut sky_light_mask = BitSet::new(SECTIONS + 2);
-        let mut block_light_mask = BitSet::new(SECTIONS + 2);
-
-        // Populate masks based on light data
-        for (i, section) in chunk.sections.iter().enumerate() {
-            if !section.sky_light.is_empty() && section.sky_light.len() == 2048 {
-                sky_light_mask.set(i, true);
-            }
-            if !section.block_light.is_empty() && section.block_light.len() == 2048 {
-                block_light_mask.set(i, true);
-            }
-        }
-
-        // Invert masks to create empty masks
-        let empty_sky_light_mask = sky_light_mask.clone().not();
-        let empty_block_light_mask = block_light_mask.clone().not();
-
-        // Align light arrays with masks
-        let sky_light_arrays = chunk
-            .sections
-            .iter()
-            .filter(|section| !section.sky_light.is_empty())
-            .map(|section| LengthPrefixedVec::new(section.sky_light.clone()))
-            .collect();
-
-        let block_light_arrays = chunk
-            .sections
-            .iter()
-            .filter(|section| !section.block_light.is_empty())
-            .map(|section| LengthPrefixedVec::new(section.block_light.clone()))
-            .collect();
         Ok(ChunkAndLightData {
             chunk_x: chunk.x,
             chunk_z: chunk.z,
             heightmaps: chunk.heightmaps.serialize_as_network(),
             data: LengthPrefixedVec::new(data.into_inner()),
             block_entities: LengthPrefixedVec::new(Vec::new()),
-            sky_light_mask,
-            block_light_mask,
-            empty_sky_light_mask,
-            empty_block_light_mask,
-            sky_light_arrays: LengthPrefixedVec::new(sky_light_arrays),
-            block_light_arrays: LengthPrefixedVec::new(block_light_arrays),
+            light: LightData::from_chunk(chunk),
         })
     }
 }
@@ -0,0 +1,49 @@
+use ferrumc_macros::{packet, NetEncode};
+use ferrumc_net_codec::net_types::length_prefixed_vec::LengthPrefixedVec;
+use ferrumc_net_codec::net_types::var_int::VarInt;
+use ferrumc_text::TextComponent;
+use std::io::Write;
+
+#[derive(NetEncode)]
+#[packet(packet_id = 0x10)]
+pub struct CommandSuggestionsResponsePacket {
+    pub transaction_id: VarInt,
+    /// Where in the player's input the suggestions start.
+    pub start: VarInt,
+    /// How many characters of the player's input the suggestions replace.
+    pub length: VarInt,
+    pub matches: LengthPrefixedVec<SuggestionMatch>,
+}
+
+impl CommandSuggestionsResponsePacket {
+    pub fn new(
+        transaction_id: VarInt,
+        start: usize,
+        length: usize,
+        matches: Vec<SuggestionMatch>,
+    ) -> Self {
+        Self {
+            transaction_id,
+            start: VarInt::new(start as i32),
+            length: VarInt::new(length as i32),
+            matches: LengthPrefixedVec::new(matches),
+        }
+    }
+}
+
+#[derive(NetEncode)]
+pub struct SuggestionMatch {
+    pub text: String,
+    pub has_tooltip: bool,
+    pub tooltip: Option<TextComponent>,
+}
+
+impl SuggestionMatch {
+    pub fn new(text: String, tooltip: Option<TextComponent>) -> Self {
+        Self {
+            text,
+            has_tooltip: tooltip.is_some(),
+            tooltip,
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use ferrumc_macros::{packet, NetEncode};
+use ferrumc_net_codec::net_types::length_prefixed_vec::LengthPrefixedVec;
+use ferrumc_net_codec::net_types::var_int::VarInt;
+use std::io::Write;
+
+#[derive(NetEncode)]
+#[packet(packet_id = 0x11)]
+pub struct DeclareCommandsPacket {
+    pub nodes: LengthPrefixedVec<CommandNodeData>,
+    pub root_index: VarInt,
+}
+
+impl DeclareCommandsPacket {
+    pub fn new(nodes: Vec<CommandNodeData>, root_index: usize) -> Self {
+        Self {
+            nodes: LengthPrefixedVec::new(nodes),
+            root_index: VarInt::new(root_index as i32),
+        }
+    }
+}
+
+/// A node of the command graph, as sent to the client. The optional fields are only sent when
+/// the flags say they're present.
+#[derive(NetEncode, Debug)]
+pub struct CommandNodeData {
+    pub flags: u8,
+    pub children: LengthPrefixedVec<VarInt>,
+    pub redirect_node: Option<VarInt>,
+    pub name: Option<String>,
+    pub parser_id: Option<VarInt>,
+    /// The parser's properties, already encoded.
+    pub properties: Option<Vec<u8>>,
+    pub suggestions_type: Option<String>,
+}
+
+impl CommandNodeData {
+    pub const ROOT: u8 = 0x00;
+    pub const LITERAL: u8 = 0x01;
+    pub const ARGUMENT: u8 = 0x02;
+    pub const EXECUTABLE: u8 = 0x04;
+    pub const HAS_REDIRECT: u8 = 0x08;
+    pub const HAS_SUGGESTIONS_TYPE: u8 = 0x10;
+}
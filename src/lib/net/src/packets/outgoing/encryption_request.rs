@@ -0,0 +1,25 @@
+use ferrumc_macros::{packet, NetEncode};
+use ferrumc_net_codec::net_types::length_prefixed_vec::LengthPrefixedVec;
+use std::io::Write;
+
+#[derive(NetEncode)]
+#[packet(packet_id = 0x01)]
+pub struct EncryptionRequestPacket<'a> {
+    /// Always empty since 1.7.
+    pub server_id: &'a str,
+    pub public_key: LengthPrefixedVec<u8>,
+    pub verify_token: LengthPrefixedVec<u8>,
+    /// Whether the client should authenticate through the session server.
+    pub should_authenticate: bool,
+}
+
+impl EncryptionRequestPacket<'_> {
+    pub fn new(public_key: &[u8], verify_token: &[u8], should_authenticate: bool) -> Self {
+        Self {
+            server_id: "",
+            public_key: LengthPrefixedVec::new(public_key.to_vec()),
+            verify_token: LengthPrefixedVec::new(verify_token.to_vec()),
+            should_authenticate,
+        }
+    }
+}
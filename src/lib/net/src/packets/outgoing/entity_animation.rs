@@ -0,0 +1,22 @@
+use ferrumc_macros::{packet, NetEncode};
+use ferrumc_net_codec::net_types::var_int::VarInt;
+use std::io::Write;
+
+#[derive(NetEncode)]
+#[packet(packet_id = 0x03)]
+pub struct EntityAnimationPacket {
+    pub entity_id: VarInt,
+    pub animation: u8,
+}
+
+impl EntityAnimationPacket {
+    pub const SWING_MAIN_ARM: u8 = 0;
+    pub const SWING_OFFHAND: u8 = 3;
+
+    pub fn new(entity_id: i32, animation: u8) -> Self {
+        Self {
+            entity_id: VarInt::new(entity_id),
+            animation,
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use ferrumc_core::gamemode::game_mode::GameMode;
 use ferrumc_macros::{packet, NetEncode};
 use std::io::Write;
 
@@ -13,6 +14,10 @@ impl GameEventPacket {
         Self { event_id, value }
     }
 
+    pub fn change_game_mode(game_mode: GameMode) -> Self {
+        Self::new(3, game_mode.id() as f32)
+    }
+
     pub fn start_waiting_for_level_chunks() -> Self {
         Self::new(13, 0f32)
     }
@@ -0,0 +1,40 @@
+use ferrumc_macros::{packet, NetEncode};
+use ferrumc_net_codec::net_types::var_int::VarInt;
+use ferrumc_world::border::WorldBorder;
+use std::io::Write;
+
+/// How far portals can take players from the center of the world.
+const PORTAL_TELEPORT_BOUNDARY: i32 = 29999984;
+
+/// Sends the whole world border.
+#[derive(NetEncode)]
+#[packet(packet_id = 0x25)]
+pub struct InitializeWorldBorderPacket {
+    pub x: f64,
+    pub z: f64,
+    pub old_diameter: f64,
+    pub new_diameter: f64,
+    /// How long the border takes to move from the old diameter to the new one, in
+    /// milliseconds. This is a VarLong, which encodes the same as a VarInt for values that fit
+    /// in one.
+    pub speed: VarInt,
+    pub portal_teleport_boundary: VarInt,
+    pub warning_blocks: VarInt,
+    pub warning_time: VarInt,
+}
+
+impl InitializeWorldBorderPacket {
+    /// A border that isn't moving.
+    pub fn new(border: &WorldBorder) -> Self {
+        Self {
+            x: border.center_x,
+            z: border.center_z,
+            old_diameter: border.diameter,
+            new_diameter: border.diameter,
+            speed: VarInt::new(0),
+            portal_teleport_boundary: VarInt::new(PORTAL_TELEPORT_BOUNDARY),
+            warning_blocks: VarInt::new(border.warning_blocks),
+            warning_time: VarInt::new(border.warning_time),
+        }
+    }
+}
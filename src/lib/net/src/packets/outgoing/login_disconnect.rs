@@ -0,0 +1,21 @@
+use ferrumc_macros::{packet, NetEncode};
+use ferrumc_text::TextComponent;
+use std::io::Write;
+
+/// Disconnect packet for the login state. Unlike the play variant, the reason is sent as JSON.
+#[derive(NetEncode)]
+#[packet(packet_id = 0x00)]
+pub struct LoginDisconnectPacket {
+    pub reason: String,
+}
+
+impl LoginDisconnectPacket {
+    pub fn new(reason: TextComponent) -> Self {
+        Self {
+            reason: reason.into(),
+        }
+    }
+    pub fn from_string(reason: String) -> Self {
+        Self::new(TextComponent::from(reason))
+    }
+}
@@ -1,7 +1,17 @@
+use ferrumc_config::statics::get_global_config;
+use ferrumc_core::dimensions::dimension::Dimension;
+use ferrumc_core::gamemode::game_mode::GameMode;
 use ferrumc_macros::{packet, NetEncode};
 use ferrumc_net_codec::net_types::var_int::VarInt;
 use std::io::Write;
 
+/// Every dimension the world has.
+const DIMENSION_NAMES: [&str; 3] = [
+    Dimension::Overworld.identifier(),
+    Dimension::Nether.identifier(),
+    Dimension::End.identifier(),
+];
+
 #[derive(NetEncode)]
 #[packet(packet_id = 0x2B)]
 pub struct LoginPlayPacket<'a> {
@@ -30,22 +40,22 @@ pub struct LoginPlayPacket<'a> {
 }
 
 impl LoginPlayPacket<'_> {
-    pub fn new(conn_id: usize) -> Self {
+    pub fn new(conn_id: usize, game_mode: GameMode, dimension: Dimension, hardcore: bool) -> Self {
         Self {
             entity_id: conn_id as i32,
-            is_hardcore: false,
-            dimension_length: VarInt::from(1),
-            dimension_names: &["minecraft:overworld"],
+            is_hardcore: hardcore,
+            dimension_length: VarInt::from(DIMENSION_NAMES.len()),
+            dimension_names: &DIMENSION_NAMES,
             max_players: VarInt::from(20),
-            view_distance: VarInt::from(10),
+            view_distance: VarInt::from(i32::from(get_global_config().max_view_distance)),
             simulation_distance: VarInt::from(10),
             reduced_debug_info: false,
             enable_respawn_screen: true,
             do_limited_crafting: false,
-            dimension_type: VarInt::new(0),
-            dimension_name: "minecraft:overworld",
+            dimension_type: VarInt::new(dimension.type_id()),
+            dimension_name: dimension.identifier(),
             seed_hash: 0,
-            gamemode: 1,
+            gamemode: game_mode.id(),
             previous_gamemode: -1,
             is_debug: false,
             is_flat: false,
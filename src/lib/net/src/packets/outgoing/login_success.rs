@@ -1,5 +1,6 @@
 use ferrumc_macros::{packet, NetEncode};
-use ferrumc_net_codec::net_types::var_int::VarInt;
+use ferrumc_net_codec::net_types::length_prefixed_vec::LengthPrefixedVec;
+use ferrumc_net_encryption::session::ProfileProperty;
 use std::io::Write;
 
 #[derive(NetEncode)]
@@ -7,16 +8,39 @@ use std::io::Write;
 pub struct LoginSuccessPacket<'a> {
     pub uuid: u128,
     pub username: &'a str,
-    pub number_of_properties: VarInt,
+    pub properties: LengthPrefixedVec<LoginSuccessProperty>,
     pub strict_error_handling: bool,
 }
 
+#[derive(NetEncode)]
+pub struct LoginSuccessProperty {
+    pub name: String,
+    pub value: String,
+    pub is_signed: bool,
+    pub signature: Option<String>,
+}
+
 impl<'a> LoginSuccessPacket<'a> {
     pub fn new(uuid: u128, username: &'a str) -> Self {
+        Self::with_properties(uuid, username, &[])
+    }
+
+    /// Includes the player's profile properties (skin, cape) from the session server.
+    pub fn with_properties(uuid: u128, username: &'a str, properties: &[ProfileProperty]) -> Self {
+        let properties = properties
+            .iter()
+            .map(|property| LoginSuccessProperty {
+                name: property.name.clone(),
+                value: property.value.clone(),
+                is_signed: property.signature.is_some(),
+                signature: property.signature.clone(),
+            })
+            .collect();
+
         Self {
             uuid,
             username,
-            number_of_properties: VarInt::from(0),
+            properties: LengthPrefixedVec::new(properties),
             strict_error_handling: false,
         }
     }
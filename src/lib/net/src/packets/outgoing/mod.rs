@@ -1,16 +1,44 @@
+pub mod acknowledge_block_change;
+pub mod block_update;
+pub mod change_difficulty;
 pub mod chunk_and_light_data;
 pub mod client_bound_known_packs;
+pub mod command_suggestions_response;
+pub mod declare_commands;
 pub mod disconnect;
+pub mod encryption_request;
+pub mod entity_animation;
 pub mod finish_configuration;
 pub mod game_event;
+pub mod initialize_world_border;
 pub mod keep_alive;
+pub mod login_disconnect;
 pub mod login_play;
 pub mod login_success;
 pub mod ping_response;
+pub mod player_abilities;
+pub mod player_chat_message;
+pub mod player_info_remove;
+pub mod player_info_update;
 pub mod registry_data;
+pub mod remove_entities;
+pub mod respawn;
 pub mod set_center_chunk;
+pub mod set_compression;
+pub mod set_container_content;
+pub mod set_container_slot;
 pub mod set_default_spawn_position;
+pub mod set_head_rotation;
+pub mod set_held_item;
 pub mod set_render_distance;
+pub mod spawn_entity;
 pub mod status_response;
 pub mod synchronize_player_position;
+pub mod system_chat_message;
+pub mod teleport_entity;
+pub mod unload_chunk;
+pub mod update_entity_position;
+pub mod update_entity_position_and_rotation;
+pub mod update_entity_rotation;
+pub mod update_light;
 pub mod update_time;
@@ -0,0 +1,45 @@
+use ferrumc_core::gamemode::game_mode::GameMode;
+use ferrumc_macros::{packet, NetEncode};
+use std::io::Write;
+
+/// Tells the client what its player is allowed to do.
+#[derive(NetEncode)]
+#[packet(packet_id = 0x38)]
+pub struct PlayerAbilitiesPacket {
+    pub flags: u8,
+    pub flying_speed: f32,
+    /// Modifies the field of view, like a speed effect would.
+    pub field_of_view_modifier: f32,
+}
+
+impl PlayerAbilitiesPacket {
+    pub const INVULNERABLE: u8 = 0x01;
+    pub const FLYING: u8 = 0x02;
+    pub const ALLOW_FLYING: u8 = 0x04;
+    pub const INSTANT_BREAK: u8 = 0x08;
+
+    pub const DEFAULT_FLYING_SPEED: f32 = 0.05;
+    pub const DEFAULT_FIELD_OF_VIEW_MODIFIER: f32 = 0.1;
+
+    /// The abilities that come with a game mode. Spectators are always flying.
+    pub fn for_game_mode(game_mode: GameMode) -> Self {
+        let mut flags = 0;
+        if game_mode.is_invulnerable() {
+            flags |= Self::INVULNERABLE;
+        }
+        if game_mode.can_fly() {
+            flags |= Self::ALLOW_FLYING;
+        }
+        if game_mode == GameMode::Spectator {
+            flags |= Self::FLYING;
+        }
+        if game_mode.breaks_instantly() {
+            flags |= Self::INSTANT_BREAK;
+        }
+        Self {
+            flags,
+            flying_speed: Self::DEFAULT_FLYING_SPEED,
+            field_of_view_modifier: Self::DEFAULT_FIELD_OF_VIEW_MODIFIER,
+        }
+    }
+}
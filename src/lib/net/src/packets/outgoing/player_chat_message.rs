@@ -0,0 +1,58 @@
+use ferrumc_macros::{packet, NetEncode};
+use ferrumc_net_codec::net_types::var_int::VarInt;
+use ferrumc_text::TextComponent;
+use std::io::Write;
+
+/// The ID of `minecraft:chat` in the `minecraft:chat_type` registry, in the order the registry
+/// data is sent.
+const CHAT_TYPE_CHAT: i32 = 0;
+
+/// A chat message from a player. Messages are sent unsigned, since secure chat isn't enforced.
+#[derive(NetEncode)]
+#[packet(packet_id = 0x39)]
+pub struct PlayerChatMessagePacket {
+    pub sender: u128,
+    /// The sender's message count, used to chain signed messages. Unsigned messages don't use it.
+    pub index: VarInt,
+    pub has_signature: bool,
+    pub message: String,
+    /// When the message was sent, in milliseconds since the Unix epoch.
+    pub timestamp: i64,
+    pub salt: i64,
+    /// The number of earlier signed messages this one acknowledges.
+    pub previous_messages: VarInt,
+    pub has_unsigned_content: bool,
+    pub unsigned_content: Option<TextComponent>,
+    /// 0 means the message isn't filtered.
+    pub filter_type: VarInt,
+    /// The chat type's registry ID plus one. 0 would mean an inline chat type follows.
+    pub chat_type: VarInt,
+    pub sender_name: TextComponent,
+    pub has_target_name: bool,
+    pub target_name: Option<TextComponent>,
+}
+
+impl PlayerChatMessagePacket {
+    /// A plain chat message, shown with the `chat.type.text` format.
+    pub fn new(sender: u128, sender_name: TextComponent, message: String) -> Self {
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |time| time.as_millis() as i64);
+        Self {
+            sender,
+            index: VarInt::new(0),
+            has_signature: false,
+            message,
+            timestamp,
+            salt: 0,
+            previous_messages: VarInt::new(0),
+            has_unsigned_content: false,
+            unsigned_content: None,
+            filter_type: VarInt::new(0),
+            chat_type: VarInt::new(CHAT_TYPE_CHAT + 1),
+            sender_name,
+            has_target_name: false,
+            target_name: None,
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use ferrumc_core::gamemode::game_mode::GameMode;
+use ferrumc_core::identity::player_identity::PlayerIdentity;
+use ferrumc_macros::{packet, NetEncode};
+use ferrumc_net_codec::net_types::length_prefixed_vec::LengthPrefixedVec;
+use ferrumc_net_codec::net_types::var_int::VarInt;
+use std::io::Write;
+
+/// Adds players to the client's player list, which it needs before it can spawn them, or
+/// updates their entries.
+#[derive(NetEncode)]
+#[packet(packet_id = 0x3E)]
+pub struct PlayerInfoUpdatePacket {
+    pub actions: u8,
+    pub players: LengthPrefixedVec<PlayerInfo>,
+}
+
+/// A player's entry. Only the fields for the packet's actions are set, since the others
+/// aren't sent at all.
+#[derive(NetEncode)]
+pub struct PlayerInfo {
+    pub uuid: u128,
+    pub name: Option<String>,
+    pub properties: Option<LengthPrefixedVec<PlayerInfoProperty>>,
+    pub game_mode: Option<VarInt>,
+    pub listed: Option<bool>,
+}
+
+#[derive(NetEncode)]
+pub struct PlayerInfoProperty {
+    pub name: String,
+    pub value: String,
+    pub is_signed: bool,
+    pub signature: Option<String>,
+}
+
+impl PlayerInfoUpdatePacket {
+    pub const ADD_PLAYER: u8 = 0x01;
+    pub const UPDATE_GAME_MODE: u8 = 0x04;
+    pub const UPDATE_LISTED: u8 = 0x08;
+
+    /// Adds the players, and shows them in the tab list.
+    pub fn add_players<'a>(
+        players: impl IntoIterator<Item = (&'a PlayerIdentity, GameMode)>,
+    ) -> Self {
+        Self {
+            actions: Self::ADD_PLAYER | Self::UPDATE_GAME_MODE | Self::UPDATE_LISTED,
+            players: LengthPrefixedVec::new(
+                players
+                    .into_iter()
+                    .map(|(identity, game_mode)| PlayerInfo::add(identity, game_mode))
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Changes the game mode a player is shown with.
+    pub fn update_game_mode(uuid: u128, game_mode: GameMode) -> Self {
+        Self {
+            actions: Self::UPDATE_GAME_MODE,
+            players: LengthPrefixedVec::new(vec![PlayerInfo {
+                uuid,
+                name: None,
+                properties: None,
+                game_mode: Some(VarInt::from(game_mode.id())),
+                listed: None,
+            }]),
+        }
+    }
+}
+
+impl PlayerInfo {
+    fn add(identity: &PlayerIdentity, game_mode: GameMode) -> Self {
+        let properties = identity
+            .properties
+            .iter()
+            .map(|property| PlayerInfoProperty {
+                name: property.name.clone(),
+                value: property.value.clone(),
+                is_signed: property.signature.is_some(),
+                signature: property.signature.clone(),
+            })
+            .collect();
+
+        Self {
+            uuid: identity.uuid,
+            name: Some(identity.username.clone()),
+            properties: Some(LengthPrefixedVec::new(properties)),
+            game_mode: Some(VarInt::from(game_mode.id())),
+            listed: Some(true),
+        }
+    }
+}
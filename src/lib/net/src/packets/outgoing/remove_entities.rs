@@ -0,0 +1,18 @@
+use ferrumc_macros::{packet, NetEncode};
+use ferrumc_net_codec::net_types::length_prefixed_vec::LengthPrefixedVec;
+use ferrumc_net_codec::net_types::var_int::VarInt;
+use std::io::Write;
+
+#[derive(NetEncode)]
+#[packet(packet_id = 0x42)]
+pub struct RemoveEntitiesPacket {
+    pub entity_ids: LengthPrefixedVec<VarInt>,
+}
+
+impl RemoveEntitiesPacket {
+    pub fn new(entity_ids: impl IntoIterator<Item = i32>) -> Self {
+        Self {
+            entity_ids: LengthPrefixedVec::new(entity_ids.into_iter().map(VarInt::new).collect()),
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use ferrumc_core::dimensions::dimension::Dimension;
+use ferrumc_core::gamemode::game_mode::GameMode;
+use ferrumc_macros::{packet, NetEncode};
+use ferrumc_net_codec::net_types::var_int::VarInt;
+use std::io::Write;
+
+/// Moves the client to another dimension, or respawns it in the same one.
+#[derive(NetEncode)]
+#[packet(packet_id = 0x47)]
+pub struct RespawnPacket<'a> {
+    pub dimension_type: VarInt,
+    pub dimension_name: &'a str,
+    pub seed_hash: i64,
+    pub gamemode: u8,
+    pub previous_gamemode: i8,
+    pub is_debug: bool,
+    pub is_flat: bool,
+    /// Always `false`, since death locations aren't tracked.
+    pub has_death_location: bool,
+    pub portal_cooldown: VarInt,
+    /// Which of the player's data the client keeps. See the `KEEP_` constants.
+    pub data_kept: u8,
+}
+
+impl RespawnPacket<'_> {
+    pub const KEEP_ATTRIBUTES: u8 = 0x01;
+    pub const KEEP_METADATA: u8 = 0x02;
+
+    /// Moves the player to another dimension, keeping everything about them, like going through
+    /// a portal does.
+    pub fn change_dimension(dimension: Dimension, game_mode: GameMode) -> Self {
+        Self {
+            dimension_type: VarInt::new(dimension.type_id()),
+            dimension_name: dimension.identifier(),
+            seed_hash: 0,
+            gamemode: game_mode.id(),
+            previous_gamemode: -1,
+            is_debug: false,
+            is_flat: false,
+            has_death_location: false,
+            portal_cooldown: VarInt::new(0),
+            data_kept: Self::KEEP_ATTRIBUTES | Self::KEEP_METADATA,
+        }
+    }
+}
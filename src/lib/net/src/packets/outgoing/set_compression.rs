@@ -0,0 +1,18 @@
+use ferrumc_macros::{packet, NetEncode};
+use ferrumc_net_codec::net_types::var_int::VarInt;
+use std::io::Write;
+
+#[derive(NetEncode)]
+#[packet(packet_id = 0x03)]
+pub struct SetCompressionPacket {
+    /// Packets of this size or bigger are compressed.
+    pub threshold: VarInt,
+}
+
+impl SetCompressionPacket {
+    pub fn new(threshold: i32) -> Self {
+        Self {
+            threshold: VarInt::new(threshold),
+        }
+    }
+}
@@ -0,0 +1,34 @@
+use ferrumc_core::items::inventory::Inventory;
+use ferrumc_macros::{packet, NetEncode};
+use ferrumc_net_codec::net_types::length_prefixed_vec::LengthPrefixedVec;
+use ferrumc_net_codec::net_types::slot::Slot;
+use ferrumc_net_codec::net_types::var_int::VarInt;
+use std::io::Write;
+
+/// Replaces every slot of a window, and the stack held by the cursor.
+#[derive(NetEncode)]
+#[packet(packet_id = 0x13)]
+pub struct SetContainerContentPacket {
+    pub window_id: u8,
+    pub state_id: VarInt,
+    pub slots: LengthPrefixedVec<Slot>,
+    pub carried_item: Slot,
+}
+
+impl SetContainerContentPacket {
+    /// The contents of a player's own inventory, window 0.
+    pub fn player_inventory(inventory: &Inventory) -> Self {
+        Self {
+            window_id: 0,
+            state_id: VarInt::new(inventory.state_id()),
+            slots: LengthPrefixedVec::new(
+                inventory
+                    .slots()
+                    .iter()
+                    .map(|stack| Slot(stack.clone()))
+                    .collect(),
+            ),
+            carried_item: Slot(inventory.carried.clone()),
+        }
+    }
+}
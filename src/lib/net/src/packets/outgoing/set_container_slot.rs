@@ -0,0 +1,25 @@
+use ferrumc_macros::{packet, NetEncode};
+use ferrumc_net_codec::net_types::slot::Slot;
+use ferrumc_net_codec::net_types::var_int::VarInt;
+use std::io::Write;
+
+/// Replaces a single slot of a window.
+#[derive(NetEncode)]
+#[packet(packet_id = 0x15)]
+pub struct SetContainerSlotPacket {
+    pub window_id: i8,
+    pub state_id: VarInt,
+    pub slot: i16,
+    pub slot_data: Slot,
+}
+
+impl SetContainerSlotPacket {
+    pub fn new(window_id: i8, state_id: i32, slot: i16, slot_data: Slot) -> Self {
+        Self {
+            window_id,
+            state_id: VarInt::new(state_id),
+            slot,
+            slot_data,
+        }
+    }
+}
@@ -1,7 +1,9 @@
 use ferrumc_macros::{packet, NetEncode};
 use ferrumc_net_codec::net_types::network_position::NetworkPosition;
+use ferrumc_world::spawn::WorldSpawn;
 use std::io::Write;
 
+/// Where the client's compass points, and where it spawns before it's sent its position.
 #[derive(NetEncode)]
 #[packet(packet_id = 0x56)]
 pub struct SetDefaultSpawnPositionPacket {
@@ -9,21 +11,18 @@ pub struct SetDefaultSpawnPositionPacket {
     pub angle: f32,
 }
 
-pub const DEFAULT_SPAWN_POSITION: NetworkPosition = NetworkPosition { x: 0, y: 256, z: 0 };
-
-const DEFAULT_ANGLE: f32 = 0.0;
-
-impl Default for SetDefaultSpawnPositionPacket {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
 impl SetDefaultSpawnPositionPacket {
-    pub fn new() -> Self {
+    pub fn new(spawn_position: NetworkPosition, angle: f32) -> Self {
         Self {
-            spawn_position: DEFAULT_SPAWN_POSITION,
-            angle: DEFAULT_ANGLE,
+            spawn_position,
+            angle,
         }
     }
+
+    pub fn from_spawn(spawn: &WorldSpawn) -> Self {
+        Self::new(
+            NetworkPosition::new(spawn.x, spawn.y as i16, spawn.z),
+            spawn.angle,
+        )
+    }
 }
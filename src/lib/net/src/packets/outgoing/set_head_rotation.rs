@@ -0,0 +1,21 @@
+use ferrumc_macros::{packet, NetEncode};
+use ferrumc_net_codec::net_types::angle::Angle;
+use ferrumc_net_codec::net_types::var_int::VarInt;
+use std::io::Write;
+
+/// Turns an entity's head, which is separate from the rotation of its body.
+#[derive(NetEncode)]
+#[packet(packet_id = 0x48)]
+pub struct SetHeadRotationPacket {
+    pub entity_id: VarInt,
+    pub head_yaw: Angle,
+}
+
+impl SetHeadRotationPacket {
+    pub fn new(entity_id: i32, head_yaw: Angle) -> Self {
+        Self {
+            entity_id: VarInt::new(entity_id),
+            head_yaw,
+        }
+    }
+}
@@ -0,0 +1,15 @@
+use ferrumc_macros::{packet, NetEncode};
+use std::io::Write;
+
+/// Selects a hotbar slot, from 0 to 8.
+#[derive(NetEncode)]
+#[packet(packet_id = 0x53)]
+pub struct SetHeldItemPacket {
+    pub slot: i8,
+}
+
+impl SetHeldItemPacket {
+    pub fn new(slot: i8) -> Self {
+        Self { slot }
+    }
+}
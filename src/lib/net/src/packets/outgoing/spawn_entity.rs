@@ -0,0 +1,47 @@
+use ferrumc_core::transform::position::Position;
+use ferrumc_core::transform::rotation::Rotation;
+use ferrumc_macros::{packet, NetEncode};
+use ferrumc_net_codec::net_types::angle::Angle;
+use ferrumc_net_codec::net_types::var_int::VarInt;
+use std::io::Write;
+
+#[derive(NetEncode)]
+#[packet(packet_id = 0x01)]
+pub struct SpawnEntityPacket {
+    pub entity_id: VarInt,
+    pub uuid: u128,
+    pub entity_type: VarInt,
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+    pub pitch: Angle,
+    pub yaw: Angle,
+    pub head_yaw: Angle,
+    pub data: VarInt,
+    pub velocity_x: i16,
+    pub velocity_y: i16,
+    pub velocity_z: i16,
+}
+
+impl SpawnEntityPacket {
+    /// `minecraft:player` in the entity type registry.
+    pub const PLAYER: i32 = 128;
+
+    pub fn player(entity_id: i32, uuid: u128, position: &Position, rotation: &Rotation) -> Self {
+        Self {
+            entity_id: VarInt::new(entity_id),
+            uuid,
+            entity_type: VarInt::new(Self::PLAYER),
+            x: position.x,
+            y: position.y,
+            z: position.z,
+            pitch: Angle::from_degrees(rotation.pitch),
+            yaw: Angle::from_degrees(rotation.yaw),
+            head_yaw: Angle::from_degrees(rotation.yaw),
+            data: VarInt::new(0),
+            velocity_x: 0,
+            velocity_y: 0,
+            velocity_z: 0,
+        }
+    }
+}
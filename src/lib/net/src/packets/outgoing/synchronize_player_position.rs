@@ -1,4 +1,3 @@
-use crate::packets::outgoing::set_default_spawn_position::DEFAULT_SPAWN_POSITION;
 use ferrumc_macros::{packet, NetEncode};
 use ferrumc_net_codec::net_types::var_int::VarInt;
 use std::io::Write;
@@ -15,21 +14,6 @@ pub struct SynchronizePlayerPositionPacket {
     pub teleport_id: VarInt,
 }
 
-impl Default for SynchronizePlayerPositionPacket {
-    fn default() -> Self {
-        let default_pos = DEFAULT_SPAWN_POSITION;
-        Self::new(
-            default_pos.x as f64,
-            default_pos.y as f64,
-            default_pos.z as f64,
-            0.0,
-            0.0,
-            0,
-            VarInt::new(0),
-        )
-    }
-}
-
 impl SynchronizePlayerPositionPacket {
     pub fn new(
         x: f64,
@@ -0,0 +1,27 @@
+use ferrumc_macros::{packet, NetEncode};
+use ferrumc_text::TextComponent;
+use std::io::Write;
+
+#[derive(NetEncode)]
+#[packet(packet_id = 0x6C)]
+pub struct SystemChatMessagePacket {
+    pub content: TextComponent,
+    /// Whether to show the message above the hotbar instead of in chat.
+    pub overlay: bool,
+}
+
+impl SystemChatMessagePacket {
+    pub fn new(content: TextComponent) -> Self {
+        Self {
+            content,
+            overlay: false,
+        }
+    }
+
+    pub fn action_bar(content: TextComponent) -> Self {
+        Self {
+            content,
+            overlay: true,
+        }
+    }
+}
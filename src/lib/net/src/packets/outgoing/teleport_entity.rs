@@ -0,0 +1,33 @@
+use ferrumc_core::transform::position::Position;
+use ferrumc_core::transform::rotation::Rotation;
+use ferrumc_macros::{packet, NetEncode};
+use ferrumc_net_codec::net_types::angle::Angle;
+use ferrumc_net_codec::net_types::var_int::VarInt;
+use std::io::Write;
+
+/// Moves an entity to an absolute position, for moves too far for Update Entity Position.
+#[derive(NetEncode)]
+#[packet(packet_id = 0x70)]
+pub struct TeleportEntityPacket {
+    pub entity_id: VarInt,
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+    pub yaw: Angle,
+    pub pitch: Angle,
+    pub on_ground: bool,
+}
+
+impl TeleportEntityPacket {
+    pub fn new(entity_id: i32, position: &Position, rotation: &Rotation, on_ground: bool) -> Self {
+        Self {
+            entity_id: VarInt::new(entity_id),
+            x: position.x,
+            y: position.y,
+            z: position.z,
+            yaw: Angle::from_degrees(rotation.yaw),
+            pitch: Angle::from_degrees(rotation.pitch),
+            on_ground,
+        }
+    }
+}
@@ -0,0 +1,16 @@
+use ferrumc_macros::{packet, NetEncode};
+use std::io::Write;
+
+#[derive(NetEncode)]
+#[packet(packet_id = 0x21)]
+pub struct UnloadChunkPacket {
+    // Yes, Z comes first.
+    pub chunk_z: i32,
+    pub chunk_x: i32,
+}
+
+impl UnloadChunkPacket {
+    pub fn new(chunk_x: i32, chunk_z: i32) -> Self {
+        Self { chunk_z, chunk_x }
+    }
+}
@@ -0,0 +1,30 @@
+use ferrumc_macros::{packet, NetEncode};
+use ferrumc_net_codec::net_types::var_int::VarInt;
+use std::io::Write;
+
+/// Moves an entity by less than 8 blocks on each axis. Deltas are in 1/4096ths of a block.
+#[derive(NetEncode)]
+#[packet(packet_id = 0x2E)]
+pub struct UpdateEntityPositionPacket {
+    pub entity_id: VarInt,
+    pub delta_x: i16,
+    pub delta_y: i16,
+    pub delta_z: i16,
+    pub on_ground: bool,
+}
+
+impl UpdateEntityPositionPacket {
+    pub fn new(
+        entity_id: i32,
+        (delta_x, delta_y, delta_z): (i16, i16, i16),
+        on_ground: bool,
+    ) -> Self {
+        Self {
+            entity_id: VarInt::new(entity_id),
+            delta_x,
+            delta_y,
+            delta_z,
+            on_ground,
+        }
+    }
+}
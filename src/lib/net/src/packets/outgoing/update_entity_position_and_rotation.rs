@@ -0,0 +1,37 @@
+use ferrumc_macros::{packet, NetEncode};
+use ferrumc_net_codec::net_types::angle::Angle;
+use ferrumc_net_codec::net_types::var_int::VarInt;
+use std::io::Write;
+
+/// Moves and rotates an entity. Deltas are in 1/4096ths of a block.
+#[derive(NetEncode)]
+#[packet(packet_id = 0x2F)]
+pub struct UpdateEntityPositionAndRotationPacket {
+    pub entity_id: VarInt,
+    pub delta_x: i16,
+    pub delta_y: i16,
+    pub delta_z: i16,
+    pub yaw: Angle,
+    pub pitch: Angle,
+    pub on_ground: bool,
+}
+
+impl UpdateEntityPositionAndRotationPacket {
+    pub fn new(
+        entity_id: i32,
+        (delta_x, delta_y, delta_z): (i16, i16, i16),
+        yaw: Angle,
+        pitch: Angle,
+        on_ground: bool,
+    ) -> Self {
+        Self {
+            entity_id: VarInt::new(entity_id),
+            delta_x,
+            delta_y,
+            delta_z,
+            yaw,
+            pitch,
+            on_ground,
+        }
+    }
+}
@@ -0,0 +1,24 @@
+use ferrumc_macros::{packet, NetEncode};
+use ferrumc_net_codec::net_types::angle::Angle;
+use ferrumc_net_codec::net_types::var_int::VarInt;
+use std::io::Write;
+
+#[derive(NetEncode)]
+#[packet(packet_id = 0x30)]
+pub struct UpdateEntityRotationPacket {
+    pub entity_id: VarInt,
+    pub yaw: Angle,
+    pub pitch: Angle,
+    pub on_ground: bool,
+}
+
+impl UpdateEntityRotationPacket {
+    pub fn new(entity_id: i32, yaw: Angle, pitch: Angle, on_ground: bool) -> Self {
+        Self {
+            entity_id: VarInt::new(entity_id),
+            yaw,
+            pitch,
+            on_ground,
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use ferrumc_macros::Event;
 use ferrumc_macros::{packet, NetEncode};
+use ferrumc_world::time::WorldTime;
 use std::io::Write;
 
 #[derive(NetEncode)]
@@ -16,10 +17,22 @@ impl UpdateTimePacket {
             time_of_day,
         }
     }
+
+    /// The world's time. A negative time of day tells the client the sun isn't moving.
+    pub fn from_world_time(time: WorldTime, daylight_cycle: bool) -> UpdateTimePacket {
+        let time_of_day = match time.time_of_day {
+            time_of_day if daylight_cycle => time_of_day,
+            0 => -1,
+            time_of_day => -time_of_day.abs(),
+        };
+        Self::new(time.world_age, time_of_day)
+    }
 }
 
 #[derive(Event, Clone, Copy)]
 pub struct TickEvent {
+    /// How many ticks have run since the server started. The world's own clock is
+    /// [ferrumc_world::World::time].
     pub tick: i64,
 }
 
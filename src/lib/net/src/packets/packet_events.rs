@@ -34,3 +34,15 @@ impl TransformEvent {
         self
     }
 }
+
+/// Fired when a connection closes, before its entity's components are removed.
+#[derive(Event, Debug)]
+pub struct ConnectionClosedEvent {
+    pub conn_id: usize,
+}
+
+impl ConnectionClosedEvent {
+    pub fn new(conn_id: usize) -> Self {
+        Self { conn_id }
+    }
+}
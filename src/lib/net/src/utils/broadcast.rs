@@ -1,6 +1,8 @@
+use crate::compression::{compress_packet, frame_packet};
 use crate::connection::StreamWriter;
 use crate::NetResult;
 use async_trait::async_trait;
+use ferrumc_config::statics::get_global_config;
 use ferrumc_ecs::entities::Entity;
 use ferrumc_net_codec::encode::{NetEncode, NetEncodeOpts};
 use ferrumc_state::GlobalState;
@@ -63,16 +65,25 @@ pub async fn broadcast(
         Some(entities) => entities,
     };
 
-    // Pre-encode the packet to save resources.
+    // Pre-encode (and compress) the packet to save resources.
     let packet = {
         let mut buffer = Vec::new();
-        packet.encode(&mut buffer, &NetEncodeOpts::WithLength)?;
+        packet.encode(&mut buffer, &NetEncodeOpts::None)?;
 
         buffer
     };
+    let compression_threshold = get_global_config().network_compression_threshold;
+    let frames = PreEncodedFrames {
+        uncompressed: frame_packet(&packet)?,
+        compressed: if compression_threshold >= 0 {
+            Some(compress_packet(&packet, compression_threshold as usize)?)
+        } else {
+            None
+        },
+    };
 
     let (state, packet, async_callback, sync_callback) =
-        (state, packet, opts.async_callback, opts.sync_callback);
+        (state, frames, opts.async_callback, opts.sync_callback);
 
     futures::stream::iter(entities.into_iter())
         .fold(
@@ -83,7 +94,8 @@ pub async fn broadcast(
                         return (state, packet, async_callback, sync_callback);
                     };
 
-                    if let Err(e) = writer.send_packet(&packet, &NetEncodeOpts::None).await {
+                    let frame = packet.for_writer(&writer);
+                    if let Err(e) = writer.send_packet(&frame, &NetEncodeOpts::None).await {
                         debug!("Error sending packet: {}", e);
                     }
 
@@ -106,6 +118,21 @@ pub async fn broadcast(
     Ok(())
 }
 
+/// A broadcast packet, framed for both compressed and uncompressed connections.
+struct PreEncodedFrames {
+    uncompressed: Vec<u8>,
+    compressed: Option<Vec<u8>>,
+}
+
+impl PreEncodedFrames {
+    fn for_writer(&self, writer: &StreamWriter) -> &[u8] {
+        match (writer.compression_threshold(), &self.compressed) {
+            (Some(_), Some(compressed)) => compressed,
+            _ => &self.uncompressed,
+        }
+    }
+}
+
 #[async_trait]
 pub trait BroadcastToAll {
     async fn broadcast(
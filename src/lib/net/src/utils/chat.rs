@@ -0,0 +1,55 @@
+use crate::connection::StreamWriter;
+use crate::packets::outgoing::player_chat_message::PlayerChatMessagePacket;
+use crate::packets::outgoing::system_chat_message::SystemChatMessagePacket;
+use crate::utils::broadcast::{BroadcastOptions, BroadcastToAll};
+use crate::utils::entity_tracking::play_state_players;
+use crate::NetResult;
+use ferrumc_core::identity::player_identity::PlayerIdentity;
+use ferrumc_ecs::entities::Entity;
+use ferrumc_net_codec::encode::NetEncodeOpts;
+use ferrumc_state::GlobalState;
+use ferrumc_text::TextComponent;
+
+/// Sends a chat message to a single player.
+pub async fn send_message(
+    state: &GlobalState,
+    entity: Entity,
+    message: TextComponent,
+) -> NetResult<()> {
+    let mut writer = state.universe.get_mut::<StreamWriter>(entity)?;
+    writer
+        .send_packet(
+            &SystemChatMessagePacket::new(message),
+            &NetEncodeOpts::WithLength,
+        )
+        .await
+}
+
+/// Sends a chat message to every player in the play state.
+pub async fn broadcast_message(state: &GlobalState, message: TextComponent) -> NetResult<()> {
+    state
+        .broadcast(
+            &SystemChatMessagePacket::new(message),
+            BroadcastOptions::default().only(play_state_players(state)),
+        )
+        .await
+}
+
+/// Sends a message a player typed in chat to every player in the play state, as that player's
+/// message rather than a system one.
+pub async fn broadcast_player_message(
+    state: &GlobalState,
+    sender: Entity,
+    message: String,
+) -> NetResult<()> {
+    let packet = {
+        let identity = state.universe.get::<PlayerIdentity>(sender)?;
+        PlayerChatMessagePacket::new(identity.uuid, identity.username.clone().into(), message)
+    };
+    state
+        .broadcast(
+            &packet,
+            BroadcastOptions::default().only(play_state_players(state)),
+        )
+        .await
+}
@@ -0,0 +1,95 @@
+//! Moving players between dimensions.
+
+use crate::connection::StreamWriter;
+use crate::packets::outgoing::change_difficulty::ChangeDifficultyPacket;
+use crate::packets::outgoing::game_event::GameEventPacket;
+use crate::packets::outgoing::initialize_world_border::InitializeWorldBorderPacket;
+use crate::packets::outgoing::respawn::RespawnPacket;
+use crate::packets::outgoing::set_default_spawn_position::SetDefaultSpawnPositionPacket;
+use crate::packets::outgoing::set_held_item::SetHeldItemPacket;
+use crate::utils::game_mode::send_abilities;
+use crate::utils::inventory::send_inventory;
+use crate::utils::movement::resync_position;
+use crate::utils::time::send_time;
+use crate::NetResult;
+use ferrumc_core::chunks::loaded_chunks::LoadedChunks;
+use ferrumc_core::dimensions::dimension::Dimension;
+use ferrumc_core::gamemode::game_mode::GameMode;
+use ferrumc_core::items::inventory::Inventory;
+use ferrumc_core::tracking::tracked_entities::TrackedEntities;
+use ferrumc_core::transform::position::Position;
+use ferrumc_ecs::entities::Entity;
+use ferrumc_net_codec::encode::NetEncodeOpts;
+use ferrumc_state::GlobalState;
+
+/// Moves a player to the safe spot closest to the spawn point in another dimension.
+///
+/// Their client is respawned in the new dimension, which makes it forget its chunks and
+/// entities, so the chunk sender sends it the new dimension's from scratch. Players in the old
+/// dimension stop tracking them the next time their views are updated.
+pub async fn change_dimension(
+    state: &GlobalState,
+    entity: Entity,
+    dimension: Dimension,
+) -> NetResult<()> {
+    let (x, y, z) = state.world.safe_spawn_position(dimension.name()).await;
+    *state.universe.get_mut::<Dimension>(entity)? = dimension;
+    *state.universe.get_mut::<Position>(entity)? = Position::new(x, y, z);
+    *state.universe.get_mut::<LoadedChunks>(entity)? = LoadedChunks::new();
+    state
+        .universe
+        .get_mut::<TrackedEntities>(entity)?
+        .entities
+        .clear();
+
+    let game_mode = *state.universe.get::<GameMode>(entity)?;
+    let selected_slot = state.universe.get::<Inventory>(entity)?.selected_slot();
+    {
+        let mut writer = state.universe.get_mut::<StreamWriter>(entity)?;
+        writer
+            .send_packet(
+                &RespawnPacket::change_dimension(dimension, game_mode),
+                &NetEncodeOpts::WithLength,
+            )
+            .await?;
+        writer
+            .send_packet(
+                &ChangeDifficultyPacket::from_level(&state.world.level_info()),
+                &NetEncodeOpts::WithLength,
+            )
+            .await?;
+    }
+    send_abilities(state, entity).await?;
+    resync_position(state, entity).await?;
+
+    // The client starts the new dimension with a fresh level and player
+    {
+        let mut writer = state.universe.get_mut::<StreamWriter>(entity)?;
+        writer
+            .send_packet(
+                &SetDefaultSpawnPositionPacket::from_spawn(&state.world.spawn()),
+                &NetEncodeOpts::WithLength,
+            )
+            .await?;
+        writer
+            .send_packet(
+                &InitializeWorldBorderPacket::new(&state.world.border()),
+                &NetEncodeOpts::WithLength,
+            )
+            .await?;
+        writer
+            .send_packet(
+                &GameEventPacket::start_waiting_for_level_chunks(),
+                &NetEncodeOpts::WithLength,
+            )
+            .await?;
+        writer
+            .send_packet(
+                &SetHeldItemPacket::new(selected_slot as i8),
+                &NetEncodeOpts::WithLength,
+            )
+            .await?;
+    }
+    send_inventory(state, entity).await?;
+    send_time(state, entity).await
+}
@@ -0,0 +1,367 @@
+//! Keeps players' clients in sync with the players around them.
+
+use crate::connection::{ConnectionState, StreamWriter};
+use crate::packets::outgoing::player_info_remove::PlayerInfoRemovePacket;
+use crate::packets::outgoing::player_info_update::PlayerInfoUpdatePacket;
+use crate::packets::outgoing::remove_entities::RemoveEntitiesPacket;
+use crate::packets::outgoing::set_head_rotation::SetHeadRotationPacket;
+use crate::packets::outgoing::spawn_entity::SpawnEntityPacket;
+use crate::packets::outgoing::teleport_entity::TeleportEntityPacket;
+use crate::packets::outgoing::update_entity_position::UpdateEntityPositionPacket;
+use crate::packets::outgoing::update_entity_position_and_rotation::UpdateEntityPositionAndRotationPacket;
+use crate::packets::outgoing::update_entity_rotation::UpdateEntityRotationPacket;
+use crate::utils::broadcast::{BroadcastOptions, BroadcastToAll};
+use crate::NetResult;
+use ferrumc_core::chunks::loaded_chunks::LoadedChunks;
+use ferrumc_core::dimensions::dimension::Dimension;
+use ferrumc_core::gamemode::game_mode::GameMode;
+use ferrumc_core::identity::player_identity::PlayerIdentity;
+use ferrumc_core::tracking::tracked_entities::TrackedEntities;
+use ferrumc_core::transform::grounded::OnGround;
+use ferrumc_core::transform::position::Position;
+use ferrumc_core::transform::rotation::Rotation;
+use ferrumc_ecs::entities::Entity;
+use ferrumc_net_codec::encode::{NetEncode, NetEncodeOpts};
+use ferrumc_net_codec::net_types::angle::Angle;
+use ferrumc_state::GlobalState;
+use std::collections::HashSet;
+use tracing::debug;
+
+/// The chunk a position is in.
+pub fn chunk_of(position: &Position) -> (i32, i32) {
+    (
+        (position.x.floor() as i32).div_euclid(16),
+        (position.z.floor() as i32).div_euclid(16),
+    )
+}
+
+/// Adds a player that just joined to everyone's player list, and everyone to theirs.
+pub async fn add_to_player_list(state: &GlobalState, entity: Entity) -> NetResult<()> {
+    let everyone = {
+        let players = play_state_players(state)
+            .into_iter()
+            .filter_map(|player| {
+                let identity = state.universe.get::<PlayerIdentity>(player).ok()?;
+                Some((identity, game_mode_of(state, player)))
+            })
+            .collect::<Vec<_>>();
+        PlayerInfoUpdatePacket::add_players(
+            players
+                .iter()
+                .map(|(identity, game_mode)| (&**identity, *game_mode)),
+        )
+    };
+    send(state, entity, &everyone).await?;
+
+    let joined = {
+        let identity = state.universe.get::<PlayerIdentity>(entity)?;
+        PlayerInfoUpdatePacket::add_players([(&*identity, game_mode_of(state, entity))])
+    };
+    let others = play_state_players(state)
+        .into_iter()
+        .filter(|player| *player != entity)
+        .collect();
+    state
+        .broadcast(&joined, BroadcastOptions::default().only(others))
+        .await
+}
+
+/// A player's game mode, or the default if it isn't known yet.
+fn game_mode_of(state: &GlobalState, entity: Entity) -> GameMode {
+    state
+        .universe
+        .get::<GameMode>(entity)
+        .map(|game_mode| *game_mode)
+        .unwrap_or_default()
+}
+
+/// Despawns a player that's leaving for everyone tracking them, and removes them from
+/// everyone's player list.
+pub async fn remove_player(state: &GlobalState, entity: Entity) -> NetResult<()> {
+    let uuid = state.universe.get::<PlayerIdentity>(entity)?.uuid;
+
+    let trackers = state
+        .universe
+        .query::<&TrackedEntities>()
+        .filter(|(_, tracked)| tracked.entities.contains(&entity))
+        .map(|(viewer, _)| viewer)
+        .collect::<Vec<_>>();
+    for viewer in trackers {
+        if let Err(e) = despawn_for(state, viewer, vec![entity]).await {
+            debug!("Could not despawn entity {} for {}: {}", entity, viewer, e);
+        }
+    }
+
+    let others = play_state_players(state)
+        .into_iter()
+        .filter(|player| *player != entity)
+        .collect();
+    state
+        .broadcast(
+            &PlayerInfoRemovePacket::new(vec![uuid]),
+            BroadcastOptions::default().only(others),
+        )
+        .await
+}
+
+/// Spawns the players in the chunks `viewer` has loaded that it isn't tracking yet, and
+/// despawns the ones it's tracking that are no longer in a loaded chunk of its dimension.
+pub async fn update_tracking(state: &GlobalState, viewer: Entity) -> NetResult<()> {
+    let visible = {
+        let loaded = state.universe.get::<LoadedChunks>(viewer)?;
+        let dimension = *state.universe.get::<Dimension>(viewer)?;
+        state
+            .universe
+            .query::<(&PlayerIdentity, &Position, &Dimension)>()
+            .filter(|(entity, (_, position, in_dimension))| {
+                *entity != viewer
+                    && **in_dimension == dimension
+                    && loaded.chunks.contains(&chunk_of(position))
+            })
+            .map(|(entity, _)| entity)
+            .collect::<HashSet<_>>()
+    };
+
+    let (spawn, despawn) = {
+        let tracked = state.universe.get::<TrackedEntities>(viewer)?;
+        (
+            visible
+                .difference(&tracked.entities)
+                .copied()
+                .collect::<Vec<_>>(),
+            tracked
+                .entities
+                .difference(&visible)
+                .copied()
+                .collect::<Vec<_>>(),
+        )
+    };
+
+    for entity in spawn {
+        spawn_for(state, viewer, entity).await?;
+    }
+    if !despawn.is_empty() {
+        despawn_for(state, viewer, despawn).await?;
+    }
+
+    Ok(())
+}
+
+/// Sends an entity's movement to the players tracking it, given where it was before it moved.
+///
+/// Players in its dimension who can see the chunk it moved into but aren't tracking it yet get
+/// it spawned, and players who are tracking it but can't see that chunk get it despawned.
+pub async fn broadcast_movement(
+    state: &GlobalState,
+    entity: Entity,
+    previous: Position,
+    rotated: bool,
+) -> NetResult<()> {
+    let position = *state.universe.get::<Position>(entity)?;
+    let rotation = *state.universe.get::<Rotation>(entity)?;
+    let on_ground = state.universe.get::<OnGround>(entity)?.0;
+
+    let movement = Movement::new(
+        entity as i32,
+        previous,
+        position,
+        rotation,
+        rotated,
+        on_ground,
+    );
+    let chunk = chunk_of(&position);
+    let dimension = *state.universe.get::<Dimension>(entity)?;
+
+    let viewers = state
+        .universe
+        .query::<(&LoadedChunks, &TrackedEntities, &Dimension)>()
+        .filter(|(viewer, _)| *viewer != entity)
+        .map(|(viewer, (loaded, tracked, in_dimension))| {
+            (
+                viewer,
+                *in_dimension == dimension && loaded.chunks.contains(&chunk),
+                tracked.entities.contains(&entity),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    for (viewer, can_see, tracking) in viewers {
+        let result = match (can_see, tracking) {
+            (true, true) => movement.send(state, viewer).await,
+            (true, false) => spawn_for(state, viewer, entity).await,
+            (false, true) => despawn_for(state, viewer, vec![entity]).await,
+            (false, false) => Ok(()),
+        };
+        if let Err(e) = result {
+            debug!("Could not update entity {} for {}: {}", entity, viewer, e);
+        }
+    }
+
+    Ok(())
+}
+
+/// The packets for an entity's movement.
+enum Movement {
+    None,
+    Position(UpdateEntityPositionPacket),
+    PositionAndRotation(UpdateEntityPositionAndRotationPacket, SetHeadRotationPacket),
+    Rotation(UpdateEntityRotationPacket, SetHeadRotationPacket),
+    Teleport(TeleportEntityPacket, SetHeadRotationPacket),
+}
+
+impl Movement {
+    fn new(
+        entity_id: i32,
+        previous: Position,
+        position: Position,
+        rotation: Rotation,
+        rotated: bool,
+        on_ground: bool,
+    ) -> Self {
+        let moved = previous != position;
+        let (yaw, pitch) = (
+            Angle::from_degrees(rotation.yaw),
+            Angle::from_degrees(rotation.pitch),
+        );
+        let head = SetHeadRotationPacket::new(entity_id, yaw);
+
+        if !moved {
+            return if rotated {
+                Self::Rotation(
+                    UpdateEntityRotationPacket::new(entity_id, yaw, pitch, on_ground),
+                    head,
+                )
+            } else {
+                Self::None
+            };
+        }
+
+        match position_delta(&previous, &position) {
+            Some(delta) if rotated => Self::PositionAndRotation(
+                UpdateEntityPositionAndRotationPacket::new(entity_id, delta, yaw, pitch, on_ground),
+                head,
+            ),
+            Some(delta) => {
+                Self::Position(UpdateEntityPositionPacket::new(entity_id, delta, on_ground))
+            }
+            // Too far for a relative move
+            None => Self::Teleport(
+                TeleportEntityPacket::new(entity_id, &position, &rotation, on_ground),
+                head,
+            ),
+        }
+    }
+
+    async fn send(&self, state: &GlobalState, viewer: Entity) -> NetResult<()> {
+        match self {
+            Self::None => Ok(()),
+            Self::Position(packet) => send(state, viewer, packet).await,
+            Self::PositionAndRotation(packet, head) => {
+                send(state, viewer, packet).await?;
+                send(state, viewer, head).await
+            }
+            Self::Rotation(packet, head) => {
+                send(state, viewer, packet).await?;
+                send(state, viewer, head).await
+            }
+            Self::Teleport(packet, head) => {
+                send(state, viewer, packet).await?;
+                send(state, viewer, head).await
+            }
+        }
+    }
+}
+
+/// The difference between two positions in 1/4096ths of a block, if it fits in the relative
+/// move packets.
+fn position_delta(from: &Position, to: &Position) -> Option<(i16, i16, i16)> {
+    let delta = |from: f64, to: f64| {
+        let delta = (to * 4096.0).round() as i64 - (from * 4096.0).round() as i64;
+        i16::try_from(delta).ok()
+    };
+    Some((
+        delta(from.x, to.x)?,
+        delta(from.y, to.y)?,
+        delta(from.z, to.z)?,
+    ))
+}
+
+async fn spawn_for(state: &GlobalState, viewer: Entity, entity: Entity) -> NetResult<()> {
+    let uuid = state.universe.get::<PlayerIdentity>(entity)?.uuid;
+    let position = *state.universe.get::<Position>(entity)?;
+    let rotation = *state.universe.get::<Rotation>(entity)?;
+
+    send(
+        state,
+        viewer,
+        &SpawnEntityPacket::player(entity as i32, uuid, &position, &rotation),
+    )
+    .await?;
+    send(
+        state,
+        viewer,
+        &SetHeadRotationPacket::new(entity as i32, Angle::from_degrees(rotation.yaw)),
+    )
+    .await?;
+
+    state
+        .universe
+        .get_mut::<TrackedEntities>(viewer)?
+        .entities
+        .insert(entity);
+    Ok(())
+}
+
+async fn despawn_for(state: &GlobalState, viewer: Entity, entities: Vec<Entity>) -> NetResult<()> {
+    send(
+        state,
+        viewer,
+        &RemoveEntitiesPacket::new(entities.iter().map(|&entity| entity as i32)),
+    )
+    .await?;
+
+    let mut tracked = state.universe.get_mut::<TrackedEntities>(viewer)?;
+    for entity in entities {
+        tracked.entities.remove(&entity);
+    }
+    Ok(())
+}
+
+async fn send(state: &GlobalState, viewer: Entity, packet: &impl NetEncode) -> NetResult<()> {
+    let mut writer = state.universe.get_mut::<StreamWriter>(viewer)?;
+    writer.send_packet(packet, &NetEncodeOpts::WithLength).await
+}
+
+pub(crate) fn play_state_players(state: &GlobalState) -> Vec<Entity> {
+    state
+        .universe
+        .query::<(&PlayerIdentity, &ConnectionState)>()
+        .filter(|(_, (_, conn_state))| matches!(**conn_state, ConnectionState::Play))
+        .map(|(entity, _)| entity)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_position_delta() {
+        let from = Position::new(0.0, 64.0, 0.0);
+        assert_eq!(
+            position_delta(&from, &Position::new(1.0, 63.5, -0.25)),
+            Some((4096, -2048, -1024))
+        );
+        assert_eq!(position_delta(&from, &Position::new(8.0, 64.0, 0.0)), None);
+        assert_eq!(
+            position_delta(&from, &Position::new(0.0, 64.0, -7.99)),
+            Some((0, 0, -32727))
+        );
+    }
+
+    #[test]
+    fn test_chunk_of() {
+        assert_eq!(chunk_of(&Position::new(0.0, 0.0, 15.9)), (0, 0));
+        assert_eq!(chunk_of(&Position::new(-0.1, 0.0, 16.0)), (-1, 1));
+    }
+}
@@ -0,0 +1,54 @@
+//! Changing players' game modes.
+
+use crate::connection::StreamWriter;
+use crate::packets::outgoing::game_event::GameEventPacket;
+use crate::packets::outgoing::player_abilities::PlayerAbilitiesPacket;
+use crate::packets::outgoing::player_info_update::PlayerInfoUpdatePacket;
+use crate::utils::broadcast::{BroadcastOptions, BroadcastToAll};
+use crate::utils::entity_tracking::play_state_players;
+use crate::NetResult;
+use ferrumc_core::gamemode::game_mode::GameMode;
+use ferrumc_core::identity::player_identity::PlayerIdentity;
+use ferrumc_ecs::entities::Entity;
+use ferrumc_net_codec::encode::NetEncodeOpts;
+use ferrumc_state::GlobalState;
+
+/// Sends a player the abilities that come with their game mode.
+pub async fn send_abilities(state: &GlobalState, entity: Entity) -> NetResult<()> {
+    let game_mode = *state.universe.get::<GameMode>(entity)?;
+    let mut writer = state.universe.get_mut::<StreamWriter>(entity)?;
+    writer
+        .send_packet(
+            &PlayerAbilitiesPacket::for_game_mode(game_mode),
+            &NetEncodeOpts::WithLength,
+        )
+        .await
+}
+
+/// Changes a player's game mode, and lets them and everyone's tab list know.
+pub async fn set_game_mode(
+    state: &GlobalState,
+    entity: Entity,
+    game_mode: GameMode,
+) -> NetResult<()> {
+    *state.universe.get_mut::<GameMode>(entity)? = game_mode;
+
+    {
+        let mut writer = state.universe.get_mut::<StreamWriter>(entity)?;
+        writer
+            .send_packet(
+                &GameEventPacket::change_game_mode(game_mode),
+                &NetEncodeOpts::WithLength,
+            )
+            .await?;
+    }
+    send_abilities(state, entity).await?;
+
+    let uuid = state.universe.get::<PlayerIdentity>(entity)?.uuid;
+    state
+        .broadcast(
+            &PlayerInfoUpdatePacket::update_game_mode(uuid, game_mode),
+            BroadcastOptions::default().only(play_state_players(state)),
+        )
+        .await
+}
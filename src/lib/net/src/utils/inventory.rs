@@ -0,0 +1,46 @@
+//! Keeps players' clients in sync with their inventories.
+
+use crate::connection::StreamWriter;
+use crate::packets::outgoing::set_container_content::SetContainerContentPacket;
+use crate::packets::outgoing::set_container_slot::SetContainerSlotPacket;
+use crate::NetResult;
+use ferrumc_core::items::inventory::Inventory;
+use ferrumc_ecs::entities::Entity;
+use ferrumc_net_codec::encode::NetEncodeOpts;
+use ferrumc_net_codec::net_types::slot::Slot;
+use ferrumc_state::GlobalState;
+
+/// Sends a player everything in their inventory, e.g. when they join or after a click that
+/// couldn't be applied.
+pub async fn send_inventory(state: &GlobalState, entity: Entity) -> NetResult<()> {
+    let packet = {
+        let mut inventory = state.universe.get_mut::<Inventory>(entity)?;
+        inventory.next_state_id();
+        SetContainerContentPacket::player_inventory(&inventory)
+    };
+    let mut writer = state.universe.get_mut::<StreamWriter>(entity)?;
+    writer
+        .send_packet(&packet, &NetEncodeOpts::WithLength)
+        .await
+}
+
+/// Sends a player what's in one slot of their inventory.
+pub async fn send_inventory_slot(
+    state: &GlobalState,
+    entity: Entity,
+    slot: usize,
+) -> NetResult<()> {
+    let packet = {
+        let inventory = state.universe.get::<Inventory>(entity)?;
+        SetContainerSlotPacket::new(
+            0,
+            inventory.state_id(),
+            slot as i16,
+            Slot(inventory.get(slot).cloned()),
+        )
+    };
+    let mut writer = state.universe.get_mut::<StreamWriter>(entity)?;
+    writer
+        .send_packet(&packet, &NetEncodeOpts::WithLength)
+        .await
+}
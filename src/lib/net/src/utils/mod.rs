@@ -1,3 +1,11 @@
 pub mod broadcast;
+pub mod chat;
+pub mod dimension;
 pub mod ecs_helpers;
+pub mod entity_tracking;
+pub mod game_mode;
+pub mod inventory;
+pub mod movement;
+pub mod player_data;
 pub mod state;
+pub mod time;
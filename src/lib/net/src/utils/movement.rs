@@ -0,0 +1,270 @@
+//! Checks that the moves players send are possible, and sends them back if they aren't.
+
+use crate::connection::StreamWriter;
+use crate::packets::outgoing::synchronize_player_position::SynchronizePlayerPositionPacket;
+use crate::NetResult;
+use ferrumc_config::server_config::MovementConfig;
+use ferrumc_core::dimensions::dimension::Dimension;
+use ferrumc_core::gamemode::game_mode::GameMode;
+use ferrumc_core::transform::position::Position;
+use ferrumc_core::transform::rotation::Rotation;
+use ferrumc_core::transform::teleport::TeleportTracker;
+use ferrumc_ecs::entities::Entity;
+use ferrumc_net_codec::encode::NetEncodeOpts;
+use ferrumc_net_codec::net_types::var_int::VarInt;
+use ferrumc_state::GlobalState;
+use ferrumc_world::border::WorldBorder;
+use ferrumc_world::World;
+use std::collections::HashSet;
+use thiserror::Error;
+
+/// The width of a player's bounding box.
+const PLAYER_WIDTH: f64 = 0.6;
+/// The height of a crawling player's bounding box. Poses aren't tracked, so only this much of a
+/// player is checked for collisions.
+const MIN_PLAYER_HEIGHT: f64 = 0.6;
+/// How much the bounding box is shrunk by on each side, so that standing on or next to a block
+/// doesn't count as being inside it.
+const COLLISION_MARGIN: f64 = 1.0e-3;
+
+/// Why a move was rejected.
+#[derive(Debug, Error, PartialEq)]
+pub enum InvalidMove {
+    #[error("Position isn't a finite number")]
+    NotFinite,
+    #[error("Moved too far at once: {0:.2} blocks")]
+    TooFast(f64),
+    #[error("Y is out of bounds: {0:.2}")]
+    OutOfBounds(f64),
+    #[error("Moved into a solid block at ({0}, {1}, {2})")]
+    IntoBlock(i32, i32, i32),
+    #[error("Moved past the world border")]
+    OutsideBorder,
+}
+
+/// Checks a move against the configured distance and height limits.
+pub fn check_move(
+    config: &MovementConfig,
+    from: &Position,
+    to: &Position,
+) -> Result<(), InvalidMove> {
+    if !(to.x.is_finite() && to.y.is_finite() && to.z.is_finite()) {
+        return Err(InvalidMove::NotFinite);
+    }
+    if to.y < config.min_y || to.y > config.max_y {
+        return Err(InvalidMove::OutOfBounds(to.y));
+    }
+    let distance =
+        ((to.x - from.x).powi(2) + (to.y - from.y).powi(2) + (to.z - from.z).powi(2)).sqrt();
+    if distance > config.max_move_distance {
+        return Err(InvalidMove::TooFast(distance));
+    }
+    Ok(())
+}
+
+/// The blocks a player standing at `position` would overlap.
+fn overlapped_blocks(position: &Position) -> impl Iterator<Item = (i32, i32, i32)> {
+    let half_width = PLAYER_WIDTH / 2.0 - COLLISION_MARGIN;
+    let min = (
+        (position.x - half_width).floor() as i32,
+        (position.y + COLLISION_MARGIN).floor() as i32,
+        (position.z - half_width).floor() as i32,
+    );
+    let max = (
+        (position.x + half_width).floor() as i32,
+        (position.y + MIN_PLAYER_HEIGHT - COLLISION_MARGIN).floor() as i32,
+        (position.z + half_width).floor() as i32,
+    );
+    (min.0..=max.0).flat_map(move |x| {
+        (min.1..=max.1).flat_map(move |y| (min.2..=max.2).map(move |z| (x, y, z)))
+    })
+}
+
+/// The solid blocks a player standing at `position` in a dimension would be inside of. Blocks
+/// that can't be read, e.g. in chunks that haven't been generated, count as passable.
+async fn solid_blocks_at(
+    world: &World,
+    dimension: Dimension,
+    position: &Position,
+) -> HashSet<(i32, i32, i32)> {
+    let mut solid = HashSet::new();
+    for (x, y, z) in overlapped_blocks(position) {
+        let Ok(block) = world.get_block_at(x, y, z, dimension.name()).await else {
+            continue;
+        };
+        if block.state().is_some_and(|state| state.is_solid()) {
+            solid.insert((x, y, z));
+        }
+    }
+    solid
+}
+
+/// Checks that a move doesn't go into a solid block. Blocks the player was already inside of
+/// are ignored, so a player stuck in a block can still get out.
+pub async fn check_collision(
+    world: &World,
+    dimension: Dimension,
+    from: &Position,
+    to: &Position,
+) -> Result<(), InvalidMove> {
+    let before = solid_blocks_at(world, dimension, from).await;
+    match solid_blocks_at(world, dimension, to)
+        .await
+        .into_iter()
+        .find(|block| !before.contains(block))
+    {
+        Some((x, y, z)) => Err(InvalidMove::IntoBlock(x, y, z)),
+        None => Ok(()),
+    }
+}
+
+/// Checks that a move doesn't go past the world border. Players already outside, e.g. because
+/// the border moved, can still make their way back.
+pub fn check_border(
+    border: &WorldBorder,
+    from: &Position,
+    to: &Position,
+) -> Result<(), InvalidMove> {
+    if border.distance_outside(to.x, to.z) > border.distance_outside(from.x, from.z) {
+        return Err(InvalidMove::OutsideBorder);
+    }
+    Ok(())
+}
+
+/// Runs every check enabled in the config on a move. Spectators can move through blocks. The
+/// world border is always enforced.
+pub async fn validate_move(
+    config: &MovementConfig,
+    world: &World,
+    game_mode: GameMode,
+    dimension: Dimension,
+    from: &Position,
+    to: &Position,
+) -> Result<(), InvalidMove> {
+    check_border(&world.border(), from, to)?;
+    if !config.validate {
+        return Ok(());
+    }
+    check_move(config, from, to)?;
+    if config.check_collisions && game_mode.has_collisions() {
+        check_collision(world, dimension, from, to).await?;
+    }
+    Ok(())
+}
+
+/// Teleports a player back to the position the server has for them, e.g. after an invalid move.
+/// Their moves are ignored until the client confirms the teleport.
+pub async fn resync_position(state: &GlobalState, entity: Entity) -> NetResult<()> {
+    let position = *state.universe.get::<Position>(entity)?;
+    let (yaw, pitch) = {
+        let rotation = state.universe.get::<Rotation>(entity)?;
+        (rotation.yaw, rotation.pitch)
+    };
+    let teleport_id = state.universe.get_mut::<TeleportTracker>(entity)?.start();
+
+    let mut writer = state.universe.get_mut::<StreamWriter>(entity)?;
+    writer
+        .send_packet(
+            &SynchronizePlayerPositionPacket::new(
+                position.x,
+                position.y,
+                position.z,
+                yaw,
+                pitch,
+                0,
+                VarInt::new(teleport_id),
+            ),
+            &NetEncodeOpts::WithLength,
+        )
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ferrumc_world::block_state::BlockRegistry;
+
+    fn config() -> MovementConfig {
+        MovementConfig {
+            validate: true,
+            max_move_distance: 10.0,
+            check_collisions: true,
+            min_y: -128.0,
+            max_y: 1024.0,
+        }
+    }
+
+    #[test]
+    fn test_check_move() {
+        let config = config();
+        let from = Position::new(0.5, 64.0, 0.5);
+        assert_eq!(
+            check_move(&config, &from, &Position::new(3.0, 65.0, -2.0)),
+            Ok(())
+        );
+        assert!(matches!(
+            check_move(&config, &from, &Position::new(20.5, 64.0, 0.5)),
+            Err(InvalidMove::TooFast(distance)) if distance == 20.0
+        ));
+        assert_eq!(
+            check_move(&config, &from, &Position::new(0.5, -130.0, 0.5)),
+            Err(InvalidMove::OutOfBounds(-130.0))
+        );
+        assert_eq!(
+            check_move(&config, &from, &Position::new(f64::NAN, 64.0, 0.5)),
+            Err(InvalidMove::NotFinite)
+        );
+    }
+
+    #[test]
+    fn test_check_border() {
+        let border = WorldBorder {
+            center_x: 0.0,
+            center_z: 0.0,
+            diameter: 100.0,
+            warning_blocks: 5,
+            warning_time: 15,
+        };
+        let inside = Position::new(49.0, 64.0, 0.0);
+        let outside = Position::new(51.0, 64.0, 0.0);
+        assert_eq!(check_border(&border, &inside, &inside), Ok(()));
+        assert_eq!(
+            check_border(&border, &inside, &outside),
+            Err(InvalidMove::OutsideBorder)
+        );
+        assert_eq!(check_border(&border, &outside, &inside), Ok(()));
+    }
+
+    #[test]
+    fn test_overlapped_blocks() {
+        // Standing in the middle of a block
+        let blocks = overlapped_blocks(&Position::new(0.5, 64.0, 0.5)).collect::<Vec<_>>();
+        assert_eq!(blocks, vec![(0, 64, 0)]);
+
+        // Straddling a corner
+        let blocks = overlapped_blocks(&Position::new(0.0, 64.5, 0.0)).collect::<HashSet<_>>();
+        assert_eq!(blocks.len(), 8);
+        assert!(blocks.contains(&(-1, 65, -1)));
+    }
+
+    #[test]
+    fn test_standing_on_partial_blocks() {
+        let registry = BlockRegistry::global();
+        // Where the feet of a player standing on each block are, above the block's bottom
+        for (name, height) in [
+            ("mud", 0.875),
+            ("soul_sand", 0.875),
+            ("farmland", 0.9375),
+            ("heavy_core", 0.5),
+            ("composter", 0.125),
+            ("oak_slab", 0.5),
+            ("powder_snow", 0.0),
+            ("mangrove_propagule", 0.0),
+        ] {
+            let position = Position::new(0.5, 63.0 + height, 0.5);
+            assert!(overlapped_blocks(&position).any(|block| block == (0, 63, 0)));
+            let state = registry.default_state(name).unwrap();
+            assert!(!state.is_solid(), "{name} shouldn't be solid");
+        }
+    }
+}
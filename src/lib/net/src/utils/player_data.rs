@@ -0,0 +1,127 @@
+//! Saving players' data, so they pick up where they left off when they join again.
+
+use crate::NetResult;
+use ferrumc_config::statics::get_global_config;
+use ferrumc_core::dimensions::dimension::Dimension;
+use ferrumc_core::gamemode::game_mode::GameMode;
+use ferrumc_core::identity::player_identity::PlayerIdentity;
+use ferrumc_core::items::inventory::Inventory;
+use ferrumc_core::transform::grounded::OnGround;
+use ferrumc_core::transform::position::Position;
+use ferrumc_core::transform::rotation::Rotation;
+use ferrumc_ecs::entities::Entity;
+use ferrumc_net_codec::net_types::slot::ItemStack;
+use ferrumc_state::GlobalState;
+use ferrumc_world::player_data::PlayerData;
+use tracing::{debug, warn};
+
+/// Adds a joining player's saved components, or the defaults for new players.
+pub async fn load_player_data(state: &GlobalState, entity: Entity) -> NetResult<()> {
+    let uuid = state.universe.get::<PlayerIdentity>(entity)?.uuid;
+    let (position, rotation, on_ground, inventory, game_mode, dimension) =
+        match state.world.load_player_data(uuid).await? {
+            Some(data) => (
+                Position::new(data.x, data.y, data.z),
+                Rotation::new(data.yaw, data.pitch),
+                OnGround(data.on_ground),
+                load_inventory(entity, data.inventory, data.selected_slot),
+                GameMode::from_id(data.game_mode).unwrap_or_else(default_game_mode),
+                Dimension::from_name(&data.dimension).unwrap_or_default(),
+            ),
+            None => {
+                debug!("No saved data for entity {}, using the spawn", entity);
+                let dimension = Dimension::default();
+                let (x, y, z) = state.world.safe_spawn_position(dimension.name()).await;
+                (
+                    Position::new(x, y, z),
+                    Rotation::new(state.world.spawn().angle, 0.0),
+                    OnGround::default(),
+                    Inventory::new(),
+                    default_game_mode(),
+                    dimension,
+                )
+            }
+        };
+
+    state
+        .universe
+        .add_component::<Position>(entity, position)?
+        .add_component::<Rotation>(entity, rotation)?
+        .add_component::<OnGround>(entity, on_ground)?
+        .add_component::<Inventory>(entity, inventory)?
+        .add_component::<GameMode>(entity, game_mode)?
+        .add_component::<Dimension>(entity, dimension)?;
+    Ok(())
+}
+
+/// The game mode new players start in. The configured one is checked when the server starts.
+fn default_game_mode() -> GameMode {
+    get_global_config()
+        .default_gamemode
+        .parse()
+        .unwrap_or_default()
+}
+
+/// Rebuilds a saved inventory. Stacks in slots that don't exist are dropped.
+fn load_inventory(entity: Entity, stacks: Vec<(u8, ItemStack)>, selected_slot: u8) -> Inventory {
+    let mut inventory = Inventory::new();
+    for (slot, stack) in stacks {
+        if let Err(e) = inventory.set(slot as usize, Some(stack)) {
+            warn!("Dropping a saved stack of entity {}: {}", entity, e);
+        }
+    }
+    if let Err(e) = inventory.select(selected_slot as usize) {
+        warn!("Invalid saved hotbar slot for entity {}: {}", entity, e);
+    }
+    inventory
+}
+
+/// Saves a player's components.
+pub async fn save_player_data(state: &GlobalState, entity: Entity) -> NetResult<()> {
+    let (uuid, data) = {
+        let uuid = state.universe.get::<PlayerIdentity>(entity)?.uuid;
+        let position = state.universe.get::<Position>(entity)?;
+        let rotation = state.universe.get::<Rotation>(entity)?;
+        let on_ground = state.universe.get::<OnGround>(entity)?;
+        let inventory = state.universe.get::<Inventory>(entity)?;
+        let game_mode = *state.universe.get::<GameMode>(entity)?;
+        let dimension = *state.universe.get::<Dimension>(entity)?;
+        let data = PlayerData {
+            x: position.x,
+            y: position.y,
+            z: position.z,
+            yaw: rotation.yaw,
+            pitch: rotation.pitch,
+            on_ground: on_ground.0,
+            inventory: inventory
+                .slots()
+                .iter()
+                .enumerate()
+                .filter_map(|(slot, stack)| Some((slot as u8, stack.clone()?)))
+                .collect(),
+            selected_slot: inventory.selected_slot() as u8,
+            game_mode: game_mode.id(),
+            dimension: dimension.name().to_string(),
+        };
+        (uuid, data)
+    };
+    state.world.save_player_data(uuid, &data).await?;
+    Ok(())
+}
+
+/// Saves every player in the game, returning how many were saved.
+pub async fn save_all_player_data(state: &GlobalState) -> usize {
+    let players = state
+        .universe
+        .query::<(&PlayerIdentity, &Position)>()
+        .into_entities();
+
+    let mut saved = 0;
+    for entity in players {
+        match save_player_data(state, entity).await {
+            Ok(()) => saved += 1,
+            Err(e) => warn!("Could not save data for entity {}: {}", entity, e),
+        }
+    }
+    saved
+}
@@ -1,7 +1,8 @@
 use crate::{
-    connection::{ConnectionControl, StreamWriter},
+    connection::{ConnectionControl, ConnectionState, StreamWriter},
     errors::NetError,
     packets::outgoing::disconnect::DisconnectPacket,
+    packets::outgoing::login_disconnect::LoginDisconnectPacket,
     NetResult,
 };
 use ferrumc_net_codec::encode::NetEncodeOpts;
@@ -35,13 +36,28 @@ pub async fn terminate_connection(
         }
     };
 
-    if let Err(e) = writer
-        .send_packet(
-            &DisconnectPacket::from_string(reason),
-            &NetEncodeOpts::WithLength,
-        )
-        .await
-    {
+    // The login state has its own disconnect packet.
+    let in_login = conn_id
+        .get::<ConnectionState>(&state)
+        .is_ok_and(|conn_state| matches!(*conn_state, ConnectionState::Login));
+
+    let sent = if in_login {
+        writer
+            .send_packet(
+                &LoginDisconnectPacket::from_string(reason),
+                &NetEncodeOpts::WithLength,
+            )
+            .await
+    } else {
+        writer
+            .send_packet(
+                &DisconnectPacket::from_string(reason),
+                &NetEncodeOpts::WithLength,
+            )
+            .await
+    };
+
+    if let Err(e) = sent {
         warn!(
             "Failed to send disconnect packet to entity {}: {}",
             conn_id, e
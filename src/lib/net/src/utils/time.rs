@@ -0,0 +1,33 @@
+//! Keeps players' clients in sync with the world's time.
+
+use crate::connection::StreamWriter;
+use crate::packets::outgoing::update_time::UpdateTimePacket;
+use crate::utils::broadcast::{BroadcastOptions, BroadcastToAll};
+use crate::utils::entity_tracking::play_state_players;
+use crate::NetResult;
+use ferrumc_ecs::entities::Entity;
+use ferrumc_net_codec::encode::NetEncodeOpts;
+use ferrumc_state::GlobalState;
+
+fn time_packet(state: &GlobalState) -> UpdateTimePacket {
+    UpdateTimePacket::from_world_time(state.world.time(), state.world.daylight_cycle())
+}
+
+/// Sends a player the time, e.g. when they join.
+pub async fn send_time(state: &GlobalState, entity: Entity) -> NetResult<()> {
+    let packet = time_packet(state);
+    let mut writer = state.universe.get_mut::<StreamWriter>(entity)?;
+    writer
+        .send_packet(&packet, &NetEncodeOpts::WithLength)
+        .await
+}
+
+/// Sends every player in the play state the time, e.g. after it's been changed.
+pub async fn broadcast_time(state: &GlobalState) -> NetResult<()> {
+    state
+        .broadcast(
+            &time_packet(state),
+            BroadcastOptions::default().only(play_state_players(state)),
+        )
+        .await
+}
@@ -0,0 +1,141 @@
+use crate::embedded::RedbBackend;
+use crate::errors::StorageError;
+use crate::lmdb::LmdbBackend;
+use crate::memory::MemoryBackend;
+use async_trait::async_trait;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// A database made of named tables, each mapping `u128` keys to bytes.
+///
+/// Every operation but [DatabaseBackend::insert] and [DatabaseBackend::batch_insert] fails with
+/// [StorageError::TableError] if the table doesn't exist; those two create it.
+#[async_trait]
+pub trait DatabaseBackend: Send + Sync {
+    /// Adds a value, failing with [StorageError::KeyExists] if the key already has one.
+    async fn insert(&self, table: String, key: u128, value: Vec<u8>) -> Result<(), StorageError>;
+
+    /// The value of a key, or `None` if it doesn't have one.
+    async fn get(&self, table: String, key: u128) -> Result<Option<Vec<u8>>, StorageError>;
+
+    /// Removes a value, failing with [StorageError::KeyNotFound] if the key doesn't have one.
+    async fn delete(&self, table: String, key: u128) -> Result<(), StorageError>;
+
+    /// Replaces a value, failing with [StorageError::KeyNotFound] if the key doesn't have one.
+    async fn update(&self, table: String, key: u128, value: Vec<u8>) -> Result<(), StorageError>;
+
+    /// Adds or replaces a value.
+    async fn upsert(&self, table: String, key: u128, value: Vec<u8>) -> Result<bool, StorageError>;
+
+    /// Whether a key has a value.
+    async fn exists(&self, table: String, key: u128) -> Result<bool, StorageError>;
+
+    /// A description of the backend, for logging.
+    async fn details(&self) -> String;
+
+    /// Adds many values at once. Nothing is added if any of the keys already has a value.
+    async fn batch_insert(
+        &self,
+        table: String,
+        data: Vec<(u128, Vec<u8>)>,
+    ) -> Result<(), StorageError>;
+
+    /// The values of many keys at once, in the same order as the keys.
+    async fn batch_get(
+        &self,
+        table: String,
+        keys: Vec<u128>,
+    ) -> Result<Vec<Option<Vec<u8>>>, StorageError>;
+
+    /// Every key in a table that has a value, in ascending order.
+    async fn keys(&self, table: String) -> Result<Vec<u128>, StorageError>;
+
+    /// Makes sure everything written so far is saved.
+    async fn flush(&self) -> Result<(), StorageError>;
+
+    /// Creates a table if it doesn't exist yet.
+    async fn create_table(&self, table: String) -> Result<(), StorageError>;
+
+    /// Saves everything and closes the database.
+    async fn close(&self) -> Result<(), StorageError>;
+}
+
+/// The kinds of [DatabaseBackend].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BackendType {
+    /// LMDB, in a directory.
+    Lmdb,
+    /// Kept in memory and lost when the server stops, for tests and throwaway worlds.
+    Memory,
+    /// redb, in a single file.
+    Redb,
+}
+
+/// Opens a backend of the given type at `path`, which is a directory for every type that's kept
+/// on disk. The in-memory backend doesn't touch `path`.
+pub async fn open_backend(
+    backend: BackendType,
+    path: PathBuf,
+) -> Result<Arc<dyn DatabaseBackend>, StorageError> {
+    Ok(match backend {
+        BackendType::Lmdb => Arc::new(LmdbBackend::initialize(Some(path)).await?),
+        BackendType::Memory => Arc::new(MemoryBackend::new()),
+        BackendType::Redb => Arc::new(RedbBackend::initialize(path).await?),
+    })
+}
+
+/// Checks that a backend behaves like the [DatabaseBackend] docs say.
+#[cfg(test)]
+pub(crate) async fn check_backend(backend: &dyn DatabaseBackend) {
+    let table = || "test".to_string();
+    assert!(matches!(
+        backend.get(table(), 1).await,
+        Err(StorageError::TableError(_))
+    ));
+
+    backend.create_table(table()).await.unwrap();
+    assert_eq!(backend.get(table(), 1).await.unwrap(), None);
+    backend.insert(table(), 1, vec![1, 2, 3]).await.unwrap();
+    assert!(matches!(
+        backend.insert(table(), 1, vec![4]).await,
+        Err(StorageError::KeyExists(1))
+    ));
+    assert_eq!(backend.get(table(), 1).await.unwrap(), Some(vec![1, 2, 3]));
+
+    backend.update(table(), 1, vec![4, 5]).await.unwrap();
+    assert!(matches!(
+        backend.update(table(), 2, vec![4]).await,
+        Err(StorageError::KeyNotFound(2))
+    ));
+    backend.upsert(table(), 2, vec![6]).await.unwrap();
+    assert!(backend.exists(table(), 2).await.unwrap());
+
+    backend
+        .batch_insert(table(), vec![(3, vec![7]), (u128::MAX, vec![8])])
+        .await
+        .unwrap();
+    assert!(backend
+        .batch_insert(table(), vec![(4, vec![9]), (3, vec![9])])
+        .await
+        .is_err());
+    assert!(!backend.exists(table(), 4).await.unwrap());
+    assert_eq!(
+        backend
+            .batch_get(table(), vec![u128::MAX, 4, 1])
+            .await
+            .unwrap(),
+        vec![Some(vec![8]), None, Some(vec![4, 5])]
+    );
+    assert_eq!(
+        backend.keys(table()).await.unwrap(),
+        vec![1, 2, 3, u128::MAX]
+    );
+
+    backend.delete(table(), 1).await.unwrap();
+    assert!(!backend.exists(table(), 1).await.unwrap());
+    assert!(matches!(
+        backend.delete(table(), 1).await,
+        Err(StorageError::KeyNotFound(1))
+    ));
+    backend.flush().await.unwrap();
+}
@@ -0,0 +1,246 @@
+use crate::backend::DatabaseBackend;
+use crate::errors::StorageError;
+use async_trait::async_trait;
+use redb::{Database, Durability, ReadableTable, TableDefinition};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// The name of the database file in the backend's directory.
+const DATABASE_FILE: &str = "world.redb";
+
+/// A backend that keeps everything in a single redb file.
+///
+/// Writes are committed without waiting for them to reach the disk, so
+/// [DatabaseBackend::flush] has to be called to be sure they're saved.
+#[derive(Clone)]
+pub struct RedbBackend {
+    db: Arc<Database>,
+}
+
+macro_rules! from_redb_error {
+    ($($error:ty),*) => {
+        $(
+            impl From<$error> for StorageError {
+                fn from(err: $error) -> Self {
+                    StorageError::DatabaseError(err.to_string())
+                }
+            }
+        )*
+    };
+}
+
+from_redb_error!(
+    redb::DatabaseError,
+    redb::TransactionError,
+    redb::StorageError,
+    redb::CommitError
+);
+
+impl From<redb::TableError> for StorageError {
+    fn from(err: redb::TableError) -> Self {
+        match err {
+            redb::TableError::TableDoesNotExist(_) => {
+                StorageError::TableError("Table not found".to_string())
+            }
+            err => StorageError::TableError(err.to_string()),
+        }
+    }
+}
+
+fn definition(table: &str) -> TableDefinition<'_, u128, &'static [u8]> {
+    TableDefinition::new(table)
+}
+
+impl RedbBackend {
+    /// Opens the database file in the given directory, creating both if they don't exist.
+    pub async fn initialize(store_path: PathBuf) -> Result<Self, StorageError> {
+        tokio::task::spawn_blocking(move || {
+            if !store_path.exists() {
+                std::fs::create_dir_all(&store_path)?;
+            }
+            let db = Database::create(store_path.join(DATABASE_FILE))
+                .map_err(|e| StorageError::DatabaseInitError(e.to_string()))?;
+            Ok(RedbBackend { db: Arc::new(db) })
+        })
+        .await
+        .expect("Failed to run tokio task")
+    }
+
+    /// Runs a write transaction on a table and commits it if `f` succeeds. The table is created
+    /// if `create` is set, and has to exist otherwise.
+    async fn write<T: Send + 'static>(
+        &self,
+        table: String,
+        create: bool,
+        f: impl FnOnce(&mut redb::Table<'_, u128, &'static [u8]>) -> Result<T, StorageError>
+            + Send
+            + 'static,
+    ) -> Result<T, StorageError> {
+        let db = self.db.clone();
+        tokio::task::spawn_blocking(move || {
+            if !create {
+                // Write transactions create tables when they're opened
+                db.begin_read()?.open_table(definition(&table))?;
+            }
+            let mut txn = db.begin_write()?;
+            txn.set_durability(Durability::Eventual);
+            let result = f(&mut txn.open_table(definition(&table))?)?;
+            txn.commit()?;
+            Ok(result)
+        })
+        .await
+        .expect("Failed to run tokio task")
+    }
+
+    async fn read<T: Send + 'static>(
+        &self,
+        table: String,
+        f: impl FnOnce(&redb::ReadOnlyTable<u128, &'static [u8]>) -> Result<T, StorageError>
+            + Send
+            + 'static,
+    ) -> Result<T, StorageError> {
+        let db = self.db.clone();
+        tokio::task::spawn_blocking(move || f(&db.begin_read()?.open_table(definition(&table))?))
+            .await
+            .expect("Failed to run tokio task")
+    }
+}
+
+#[async_trait]
+impl DatabaseBackend for RedbBackend {
+    async fn insert(&self, table: String, key: u128, value: Vec<u8>) -> Result<(), StorageError> {
+        self.write(table, true, move |db| {
+            if db.get(key)?.is_some() {
+                return Err(StorageError::KeyExists(key as u64));
+            }
+            db.insert(key, value.as_slice())?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn get(&self, table: String, key: u128) -> Result<Option<Vec<u8>>, StorageError> {
+        self.read(table, move |db| {
+            Ok(db.get(key)?.map(|value| value.value().to_vec()))
+        })
+        .await
+    }
+
+    async fn delete(&self, table: String, key: u128) -> Result<(), StorageError> {
+        self.write(table, false, move |db| match db.remove(key)? {
+            Some(_) => Ok(()),
+            None => Err(StorageError::KeyNotFound(key as u64)),
+        })
+        .await
+    }
+
+    async fn update(&self, table: String, key: u128, value: Vec<u8>) -> Result<(), StorageError> {
+        self.write(table, false, move |db| {
+            if db.get(key)?.is_none() {
+                return Err(StorageError::KeyNotFound(key as u64));
+            }
+            db.insert(key, value.as_slice())?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn upsert(&self, table: String, key: u128, value: Vec<u8>) -> Result<bool, StorageError> {
+        self.write(table, false, move |db| {
+            db.insert(key, value.as_slice())?;
+            Ok(true)
+        })
+        .await
+    }
+
+    async fn exists(&self, table: String, key: u128) -> Result<bool, StorageError> {
+        self.read(table, move |db| Ok(db.get(key)?.is_some())).await
+    }
+
+    async fn details(&self) -> String {
+        "redb 2.6".to_string()
+    }
+
+    async fn batch_insert(
+        &self,
+        table: String,
+        data: Vec<(u128, Vec<u8>)>,
+    ) -> Result<(), StorageError> {
+        self.write(table, true, move |db| {
+            for (key, value) in data {
+                if db.get(key)?.is_some() {
+                    return Err(StorageError::KeyExists(key as u64));
+                }
+                db.insert(key, value.as_slice())?;
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    async fn batch_get(
+        &self,
+        table: String,
+        keys: Vec<u128>,
+    ) -> Result<Vec<Option<Vec<u8>>>, StorageError> {
+        self.read(table, move |db| {
+            keys.into_iter()
+                .map(|key| Ok(db.get(key)?.map(|value| value.value().to_vec())))
+                .collect()
+        })
+        .await
+    }
+
+    async fn keys(&self, table: String) -> Result<Vec<u128>, StorageError> {
+        self.read(table, |db| {
+            db.iter()?.map(|entry| Ok(entry?.0.value())).collect()
+        })
+        .await
+    }
+
+    async fn flush(&self) -> Result<(), StorageError> {
+        let db = self.db.clone();
+        tokio::task::spawn_blocking(move || {
+            // Committing a durable transaction also saves the eventual ones before it
+            let mut txn = db.begin_write()?;
+            txn.set_durability(Durability::Immediate);
+            txn.commit()?;
+            Ok(())
+        })
+        .await
+        .expect("Failed to run tokio task")
+    }
+
+    async fn create_table(&self, table: String) -> Result<(), StorageError> {
+        self.write(table, true, |_| Ok(())).await
+    }
+
+    async fn close(&self) -> Result<(), StorageError> {
+        self.flush().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::check_backend;
+
+    #[tokio::test]
+    async fn test_redb_backend() {
+        let dir = tempfile::tempdir().unwrap();
+        let backend = RedbBackend::initialize(dir.path().to_path_buf())
+            .await
+            .unwrap();
+        check_backend(&backend).await;
+
+        // Everything flushed is still there after reopening
+        drop(backend);
+        let backend = RedbBackend::initialize(dir.path().to_path_buf())
+            .await
+            .unwrap();
+        assert_eq!(
+            backend.get("test".to_string(), 2).await.unwrap(),
+            Some(vec![6])
+        );
+    }
+}
@@ -1,4 +1,7 @@
 #![feature(async_closure)]
+pub mod backend;
 pub mod compressors;
+pub mod embedded;
 pub mod errors;
 pub mod lmdb;
+pub mod memory;
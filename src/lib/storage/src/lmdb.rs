@@ -1,4 +1,6 @@
+use crate::backend::DatabaseBackend;
 use crate::errors::StorageError;
+use async_trait::async_trait;
 use heed;
 use heed::byteorder::BigEndian;
 use heed::types::{Bytes, U128};
@@ -49,7 +51,7 @@ impl LmdbBackend {
                 env: Arc::new(
                     EnvOpenOptions::new()
                         // Change this as more tables are needed.
-                        .max_dbs(1)
+                        .max_dbs(3)
                         .map_size(rounded_map_size)
                         .open(checked_path)
                         .map_err(|e| StorageError::DatabaseInitError(e.to_string()))?,
@@ -57,13 +59,11 @@ impl LmdbBackend {
             })
         }
     }
+}
 
-    pub async fn insert(
-        &self,
-        table: String,
-        key: u128,
-        value: Vec<u8>,
-    ) -> Result<(), StorageError> {
+#[async_trait]
+impl DatabaseBackend for LmdbBackend {
+    async fn insert(&self, table: String, key: u128, value: Vec<u8>) -> Result<(), StorageError> {
         let env = self.env.clone();
         tokio::task::spawn_blocking(move || {
             let mut rw_txn = env.write_txn()?;
@@ -80,7 +80,7 @@ impl LmdbBackend {
         .expect("Failed to run tokio task")
     }
 
-    pub async fn get(&self, table: String, key: u128) -> Result<Option<Vec<u8>>, StorageError> {
+    async fn get(&self, table: String, key: u128) -> Result<Option<Vec<u8>>, StorageError> {
         let env = self.env.clone();
         tokio::task::spawn_blocking(move || {
             let ro_txn = env.read_txn()?;
@@ -98,7 +98,7 @@ impl LmdbBackend {
         .expect("Failed to run tokio task")
     }
 
-    pub async fn delete(&self, table: String, key: u128) -> Result<(), StorageError> {
+    async fn delete(&self, table: String, key: u128) -> Result<(), StorageError> {
         let env = self.env.clone();
         tokio::task::spawn_blocking(move || {
             let mut rw_txn = env.write_txn()?;
@@ -116,12 +116,7 @@ impl LmdbBackend {
         .expect("Failed to run tokio task")
     }
 
-    pub async fn update(
-        &self,
-        table: String,
-        key: u128,
-        value: Vec<u8>,
-    ) -> Result<(), StorageError> {
+    async fn update(&self, table: String, key: u128, value: Vec<u8>) -> Result<(), StorageError> {
         let env = self.env.clone();
         tokio::task::spawn_blocking(move || {
             let mut rw_txn = env.write_txn()?;
@@ -139,12 +134,7 @@ impl LmdbBackend {
         .expect("Failed to run tokio task")
     }
 
-    pub async fn upsert(
-        &self,
-        table: String,
-        key: u128,
-        value: Vec<u8>,
-    ) -> Result<bool, StorageError> {
+    async fn upsert(&self, table: String, key: u128, value: Vec<u8>) -> Result<bool, StorageError> {
         let env = self.env.clone();
         tokio::task::spawn_blocking(move || {
             let mut rw_txn = env.write_txn()?;
@@ -159,7 +149,7 @@ impl LmdbBackend {
         .expect("Failed to run tokio task")
     }
 
-    pub async fn exists(&self, table: String, key: u128) -> Result<bool, StorageError> {
+    async fn exists(&self, table: String, key: u128) -> Result<bool, StorageError> {
         let env = self.env.clone();
         tokio::task::spawn_blocking(move || {
             let ro_txn = env.read_txn()?;
@@ -172,11 +162,11 @@ impl LmdbBackend {
         .expect("Failed to run tokio task")
     }
 
-    pub async fn details(&self) -> String {
+    async fn details(&self) -> String {
         format!("LMDB (heed 0.20.5): {:?}", self.env.info())
     }
 
-    pub async fn batch_insert(
+    async fn batch_insert(
         &self,
         table: String,
         data: Vec<(u128, Vec<u8>)>,
@@ -203,7 +193,7 @@ impl LmdbBackend {
         .expect("Failed to run tokio task")
     }
 
-    pub async fn batch_get(
+    async fn batch_get(
         &self,
         table: String,
         keys: Vec<u128>,
@@ -229,7 +219,24 @@ impl LmdbBackend {
         .expect("Failed to run tokio task")
     }
 
-    pub async fn flush(&self) -> Result<(), StorageError> {
+    async fn keys(&self, table: String) -> Result<Vec<u128>, StorageError> {
+        let env = self.env.clone();
+        tokio::task::spawn_blocking(move || {
+            let ro_txn = env.read_txn()?;
+            let db: Database<U128<BigEndian>, Bytes> = env
+                .open_database(&ro_txn, Some(&table))?
+                .ok_or(StorageError::TableError("Table not found".to_string()))?;
+            let mut keys = Vec::new();
+            for entry in db.iter(&ro_txn)? {
+                keys.push(entry?.0);
+            }
+            Ok(keys)
+        })
+        .await
+        .expect("Failed to run tokio task")
+    }
+
+    async fn flush(&self) -> Result<(), StorageError> {
         let env = self.env.clone();
         tokio::task::spawn_blocking(move || {
             env.clear_stale_readers()?;
@@ -240,7 +247,7 @@ impl LmdbBackend {
         .expect("Failed to run tokio task")
     }
 
-    pub async fn create_table(&self, table: String) -> Result<(), StorageError> {
+    async fn create_table(&self, table: String) -> Result<(), StorageError> {
         let env = self.env.clone();
         tokio::task::spawn_blocking(move || {
             let mut rw_txn = env.write_txn()?;
@@ -252,8 +259,23 @@ impl LmdbBackend {
         .expect("Failed to run tokio task")
     }
 
-    pub async fn close(&self) -> Result<(), StorageError> {
+    async fn close(&self) -> Result<(), StorageError> {
         self.flush().await?;
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::check_backend;
+
+    #[tokio::test]
+    async fn test_lmdb_backend() {
+        let dir = tempfile::tempdir().unwrap();
+        let backend = LmdbBackend::initialize(Some(dir.path().to_path_buf()))
+            .await
+            .unwrap();
+        check_backend(&backend).await;
+    }
+}
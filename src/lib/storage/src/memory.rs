@@ -0,0 +1,154 @@
+use crate::backend::DatabaseBackend;
+use crate::errors::StorageError;
+use async_trait::async_trait;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, RwLock};
+
+type Tables = HashMap<String, BTreeMap<u128, Vec<u8>>>;
+
+/// A backend that keeps everything in memory. Nothing is saved, so it's only useful for tests
+/// and worlds that are thrown away when the server stops.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryBackend {
+    tables: Arc<RwLock<Tables>>,
+}
+
+impl MemoryBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn read<T>(
+        &self,
+        table: &str,
+        f: impl FnOnce(&BTreeMap<u128, Vec<u8>>) -> Result<T, StorageError>,
+    ) -> Result<T, StorageError> {
+        let tables = self
+            .tables
+            .read()
+            .map_err(|e| StorageError::ReadError(e.to_string()))?;
+        f(tables
+            .get(table)
+            .ok_or(StorageError::TableError("Table not found".to_string()))?)
+    }
+
+    fn write<T>(
+        &self,
+        table: &str,
+        create: bool,
+        f: impl FnOnce(&mut BTreeMap<u128, Vec<u8>>) -> Result<T, StorageError>,
+    ) -> Result<T, StorageError> {
+        let mut tables = self
+            .tables
+            .write()
+            .map_err(|e| StorageError::WriteError(e.to_string()))?;
+        if create {
+            tables.entry(table.to_string()).or_default();
+        }
+        f(tables
+            .get_mut(table)
+            .ok_or(StorageError::TableError("Table not found".to_string()))?)
+    }
+}
+
+#[async_trait]
+impl DatabaseBackend for MemoryBackend {
+    async fn insert(&self, table: String, key: u128, value: Vec<u8>) -> Result<(), StorageError> {
+        self.write(&table, true, |db| {
+            if db.contains_key(&key) {
+                return Err(StorageError::KeyExists(key as u64));
+            }
+            db.insert(key, value);
+            Ok(())
+        })
+    }
+
+    async fn get(&self, table: String, key: u128) -> Result<Option<Vec<u8>>, StorageError> {
+        self.read(&table, |db| Ok(db.get(&key).cloned()))
+    }
+
+    async fn delete(&self, table: String, key: u128) -> Result<(), StorageError> {
+        self.write(&table, false, |db| {
+            db.remove(&key)
+                .map(|_| ())
+                .ok_or(StorageError::KeyNotFound(key as u64))
+        })
+    }
+
+    async fn update(&self, table: String, key: u128, value: Vec<u8>) -> Result<(), StorageError> {
+        self.write(&table, false, |db| {
+            let existing = db
+                .get_mut(&key)
+                .ok_or(StorageError::KeyNotFound(key as u64))?;
+            *existing = value;
+            Ok(())
+        })
+    }
+
+    async fn upsert(&self, table: String, key: u128, value: Vec<u8>) -> Result<bool, StorageError> {
+        self.write(&table, false, |db| {
+            db.insert(key, value);
+            Ok(true)
+        })
+    }
+
+    async fn exists(&self, table: String, key: u128) -> Result<bool, StorageError> {
+        self.read(&table, |db| Ok(db.contains_key(&key)))
+    }
+
+    async fn details(&self) -> String {
+        let tables = self.tables.read().map(|tables| tables.len()).unwrap_or(0);
+        format!("In memory: {} tables", tables)
+    }
+
+    async fn batch_insert(
+        &self,
+        table: String,
+        data: Vec<(u128, Vec<u8>)>,
+    ) -> Result<(), StorageError> {
+        self.write(&table, true, |db| {
+            if let Some((key, _)) = data.iter().find(|(key, _)| db.contains_key(key)) {
+                return Err(StorageError::KeyExists(*key as u64));
+            }
+            db.extend(data);
+            Ok(())
+        })
+    }
+
+    async fn batch_get(
+        &self,
+        table: String,
+        keys: Vec<u128>,
+    ) -> Result<Vec<Option<Vec<u8>>>, StorageError> {
+        self.read(&table, |db| {
+            Ok(keys.iter().map(|key| db.get(key).cloned()).collect())
+        })
+    }
+
+    async fn keys(&self, table: String) -> Result<Vec<u128>, StorageError> {
+        self.read(&table, |db| Ok(db.keys().copied().collect()))
+    }
+
+    async fn flush(&self) -> Result<(), StorageError> {
+        Ok(())
+    }
+
+    async fn create_table(&self, table: String) -> Result<(), StorageError> {
+        self.write(&table, true, |_| Ok(()))
+    }
+
+    async fn close(&self) -> Result<(), StorageError> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::check_backend;
+
+    #[tokio::test]
+    async fn test_memory_backend() {
+        check_backend(&MemoryBackend::new()).await;
+    }
+}
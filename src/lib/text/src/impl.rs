@@ -94,6 +94,29 @@ impl TextComponent {
         NBTSerializable::serialize(self, &mut vec, &NBTSerializeOptions::Network);
         vec
     }
+
+    /// The component's text without any formatting, for places like the server console.
+    ///
+    /// The server has no translations, so translated components show their key, followed by
+    /// their arguments in brackets.
+    pub fn to_plain_text(&self) -> String {
+        let mut text = match &self.content {
+            TextContent::Text { text } => text.clone(),
+            TextContent::Translate { translate, with } if with.is_empty() => translate.clone(),
+            TextContent::Translate { translate, with } => {
+                let with = with
+                    .iter()
+                    .map(TextComponent::to_plain_text)
+                    .collect::<Vec<_>>();
+                format!("{} [{}]", translate, with.join(", "))
+            }
+            TextContent::Keybind { keybind } => keybind.clone(),
+        };
+        for extra in &self.extra {
+            text.push_str(&extra.to_plain_text());
+        }
+        text
+    }
 }
 
 impl NetEncode for TextComponent {
@@ -178,3 +178,17 @@ async fn test_serialize_to_nbt() {
         bytes_to_readable_string(&cursor.get_ref()[cursor.position() as usize..])
     );
 }
+
+#[test]
+fn test_to_plain_text() {
+    let component = ComponentBuilder::text("Hello")
+        .color(NamedColor::Red)
+        .space()
+        .extra(ComponentBuilder::text("world!").bold().build())
+        .build();
+    assert_eq!(component.to_plain_text(), "Hello world!");
+
+    let component =
+        ComponentBuilder::translate("chat.type.text", vec!["Steve".into(), "hi".into()]);
+    assert_eq!(component.to_plain_text(), "chat.type.text [Steve, hi]");
+}
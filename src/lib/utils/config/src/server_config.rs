@@ -12,9 +12,25 @@ use serde_derive::{Deserialize, Serialize};
 /// - `motd`: The message of the day that is displayed to clients. It will randomly select one from the list.
 /// - `max_players`: The maximum number of players that can be connected to the server.
 /// - `network_tick_rate`: How many network updates to process per second per user.
+/// - `tick_rate`: How many game ticks to run per second. Vanilla runs 20.
 /// - `database` - [DatabaseConfig]: The configuration for the database.
 /// - `world`: The name of the world that the server will load.
 /// - `network_compression_threshold`: The threshold at which the server will compress network packets.
+/// - `max_view_distance`: The maximum view distance (in chunks) sent to players. Players with a
+///   lower client view distance get their own.
+/// - `online_mode`: Whether players are authenticated with Mojang and the connection is encrypted.
+/// - `shutdown_message`: The reason shown to players who are disconnected when the server stops.
+/// - `default_gamemode`: The game mode new players start in. One of `survival`, `creative`,
+///   `adventure` or `spectator`.
+/// - `movement` - [MovementConfig]: How players' movement is validated.
+/// - `time` - [TimeConfig]: How the world's time moves.
+/// - `spawn` - [SpawnConfig]: Where players spawn, unless the world has its own spawn point.
+/// - `world_border` - [WorldBorderConfig]: The world border, unless the world has its own.
+/// - `generation` - [GenerationConfig]: How chunks that don't exist yet are generated.
+///
+/// `tick_rate`, `max_view_distance`, `online_mode`, `shutdown_message`, `default_gamemode` and
+/// the sections after `database` can be left out, so config files from before they existed
+/// still load. Missing settings get the values from `.etc/example-config.toml`.
 #[derive(Debug, Deserialize, Serialize)]
 pub struct ServerConfig {
     pub host: String,
@@ -22,21 +38,63 @@ pub struct ServerConfig {
     pub motd: Vec<String>,
     pub max_players: u32,
     pub network_tick_rate: u32,
+    #[serde(default = "default_tick_rate")]
+    pub tick_rate: u32,
     pub database: DatabaseConfig,
     pub world: String,
     pub network_compression_threshold: i32, // Can be negative
+    #[serde(default = "default_max_view_distance")]
+    pub max_view_distance: u8,
+    #[serde(default)]
+    pub online_mode: bool,
+    #[serde(default = "default_shutdown_message")]
+    pub shutdown_message: String,
+    #[serde(default = "default_gamemode")]
+    pub default_gamemode: String,
+    #[serde(default)]
+    pub movement: MovementConfig,
+    #[serde(default)]
+    pub time: TimeConfig,
+    #[serde(default)]
+    pub spawn: SpawnConfig,
+    #[serde(default)]
+    pub world_border: WorldBorderConfig,
+    #[serde(default)]
+    pub generation: GenerationConfig,
+}
+
+fn default_tick_rate() -> u32 {
+    20
+}
+
+fn default_max_view_distance() -> u8 {
+    12
+}
+
+fn default_shutdown_message() -> String {
+    "Server closed".to_string()
+}
+
+fn default_gamemode() -> String {
+    "survival".to_string()
+}
+
+fn default_backend() -> String {
+    "lmdb".to_string()
 }
 
 /// The database configuration section from [ServerConfig].
 ///
 /// Fields:
+/// - `backend`: Which database to keep the world in. Options are `lmdb`, `redb`, which keeps
+///   everything in a single file, and `memory`, which keeps nothing once the server stops.
 /// - `cache_size`: The cache size in KB.
 /// - `compression` - Which compression algorithm to use. Options are `brotli`, `deflate`, `gzip`, `zlib`
 ///     and `zstd`
 /// - `world_path`: The path to the world database.
 /// - `compression_level`: The compression level to use. This is a number from 0-22. Not all compressors
 ///     support levels, so this will be a no-op for some compressors.
-/// - `map_size`: The max size of the database's memory map. Basically you need this to be big enough
+/// - `map_size`: The max size of LMDB's memory map. Basically you need this to be big enough
 ///    to hold everything before it starts writing to disk. This isn't memory use though, it's just
 ///    how much we can map into memory if needed, so you can set this to an insane number if you want,
 ///    but it won't actually use that much memory, it'll just show up as virtual memory use.
@@ -44,6 +102,8 @@ pub struct ServerConfig {
 /// - `cache_capacity`: How big the cache can be in kb.
 #[derive(Debug, Deserialize, Serialize)]
 pub struct DatabaseConfig {
+    #[serde(default = "default_backend")]
+    pub backend: String,
     pub compression: String,
     pub db_path: String,
     pub compression_level: i32,
@@ -52,6 +112,168 @@ pub struct DatabaseConfig {
     pub cache_capacity: u64,
 }
 
+/// The movement validation section from [ServerConfig].
+///
+/// Players making a move that fails validation are sent back to where the server last had them.
+///
+/// Fields:
+/// - `validate`: Whether players' movement is validated at all.
+/// - `max_move_distance`: The furthest a player can move in a single movement packet, in blocks.
+/// - `check_collisions`: Whether players are stopped from moving into solid blocks.
+/// - `min_y`: The lowest a player can go.
+/// - `max_y`: The highest a player can go.
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub struct MovementConfig {
+    pub validate: bool,
+    pub max_move_distance: f64,
+    pub check_collisions: bool,
+    pub min_y: f64,
+    pub max_y: f64,
+}
+
+impl Default for MovementConfig {
+    fn default() -> Self {
+        Self {
+            validate: true,
+            max_move_distance: 10.0,
+            check_collisions: true,
+            min_y: -128.0,
+            max_y: 1024.0,
+        }
+    }
+}
+
+/// The time section from [ServerConfig].
+///
+/// Fields:
+/// - `daylight_cycle`: Whether the time of day moves on its own, like vanilla's
+///   `doDaylightCycle` game rule. An imported world's `doDaylightCycle` game rule takes precedence.
+/// - `broadcast_interval`: How often players are sent the time, in ticks. Clients keep the time
+///   moving by themselves in between.
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub struct TimeConfig {
+    pub daylight_cycle: bool,
+    pub broadcast_interval: u32,
+}
+
+impl Default for TimeConfig {
+    fn default() -> Self {
+        Self {
+            daylight_cycle: true,
+            broadcast_interval: 20,
+        }
+    }
+}
+
+/// The spawn point section from [ServerConfig].
+///
+/// Players spawn on the highest safe block near `x` and `z`. `y` is only used if there's no safe
+/// spot, e.g. because the chunk hasn't been generated.
+///
+/// Fields:
+/// - `x`, `y`, `z`: The spawn point.
+/// - `angle`: The yaw players face when they spawn.
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub struct SpawnConfig {
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+    pub angle: f32,
+}
+
+impl Default for SpawnConfig {
+    fn default() -> Self {
+        Self {
+            x: 0,
+            y: 256,
+            z: 0,
+            angle: 0.0,
+        }
+    }
+}
+
+/// The world border section from [ServerConfig].
+///
+/// Fields:
+/// - `center_x`, `center_z`: The center of the border.
+/// - `diameter`: How wide the border is, in blocks. Vanilla's default is 59999968.
+/// - `warning_blocks`: How close to the border players get a warning, in blocks.
+/// - `warning_time`: How long before a moving border reaches players they get a warning, in
+///   seconds.
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub struct WorldBorderConfig {
+    pub center_x: f64,
+    pub center_z: f64,
+    pub diameter: f64,
+    pub warning_blocks: i32,
+    pub warning_time: i32,
+}
+
+impl Default for WorldBorderConfig {
+    fn default() -> Self {
+        Self {
+            center_x: 0.0,
+            center_z: 0.0,
+            diameter: 59999968.0,
+            warning_blocks: 5,
+            warning_time: 15,
+        }
+    }
+}
+
+/// The world generation section from [ServerConfig].
+///
+/// Chunks that haven't been imported or generated before are generated the first time they're
+/// loaded.
+///
+/// Fields:
+/// - `overworld`, `the_nether`, `the_end` - [GeneratorConfig]: How each dimension is generated.
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub struct GenerationConfig {
+    pub overworld: GeneratorConfig,
+    pub the_nether: GeneratorConfig,
+    pub the_end: GeneratorConfig,
+}
+
+impl Default for GenerationConfig {
+    fn default() -> Self {
+        Self {
+            overworld: GeneratorConfig {
+                generator: "noise".to_string(),
+                superflat_layers: vec![
+                    "minecraft:bedrock".to_string(),
+                    "2*minecraft:dirt".to_string(),
+                    "minecraft:grass_block".to_string(),
+                ],
+            },
+            the_nether: GeneratorConfig::void(),
+            the_end: GeneratorConfig::void(),
+        }
+    }
+}
+
+/// How a dimension is generated, from [GenerationConfig].
+///
+/// Fields:
+/// - `generator`: One of `void`, `superflat` or `noise`. Noise terrain is based on the world's
+///   seed.
+/// - `superflat_layers`: The layers of a superflat dimension from the bottom up, like vanilla's
+///   superflat presets: `minecraft:dirt` is one layer of dirt, and `3*minecraft:dirt` is three.
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub struct GeneratorConfig {
+    pub generator: String,
+    pub superflat_layers: Vec<String>,
+}
+
+impl GeneratorConfig {
+    fn void() -> Self {
+        Self {
+            generator: "void".to_string(),
+            superflat_layers: Vec::new(),
+        }
+    }
+}
+
 /// The database compression enum for [DatabaseConfig].
 ///
 /// Variants:
@@ -67,3 +289,46 @@ pub enum DatabaseCompression {
     #[serde(rename = "best")]
     Best,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::statics::DEFAULT_CONFIG;
+
+    /// A config file from before the settings with defaults were added.
+    const OLD_CONFIG: &str = r#"
+        host = "0.0.0.0"
+        port = 25565
+        motd = ["Welcome to the best server ever!"]
+        max_players = 100
+        network_tick_rate = 30
+        world = "world"
+        network_compression_threshold = 64
+
+        [database]
+        compression = "gzip"
+        db_path = "world"
+        compression_level = 5
+        map_size = 1_000
+        cache_ttl = 60
+        cache_capacity = 20_000
+    "#;
+
+    #[test]
+    fn test_old_config_gets_example_defaults() {
+        let old: ServerConfig = toml::from_str(OLD_CONFIG).unwrap();
+        let example: ServerConfig = toml::from_str(DEFAULT_CONFIG).unwrap();
+
+        assert_eq!(old.tick_rate, example.tick_rate);
+        assert_eq!(old.max_view_distance, example.max_view_distance);
+        assert_eq!(old.online_mode, example.online_mode);
+        assert_eq!(old.shutdown_message, example.shutdown_message);
+        assert_eq!(old.default_gamemode, example.default_gamemode);
+        assert_eq!(old.database.backend, example.database.backend);
+        assert_eq!(old.movement, example.movement);
+        assert_eq!(old.time, example.time);
+        assert_eq!(old.spawn, example.spawn);
+        assert_eq!(old.world_border, example.world_border);
+        assert_eq!(old.generation, example.generation);
+    }
+}
@@ -1,5 +1,9 @@
 pub mod errors;
+mod printer;
 
+pub use printer::{clear_log_printer, set_log_printer};
+
+use crate::printer::LogWriter;
 use ferrumc_profiling::ProfilerTracingLayer;
 use tracing::Level;
 use tracing_subscriber::fmt::Layer;
@@ -25,6 +29,6 @@ pub fn init_logging(trace_level: tracing::Level) {
     tracing_subscriber::registry()
         .with(env_filter)
         .with(profiler_layer)
-        .with(fmt_layer)
+        .with(fmt_layer.with_writer(LogWriter::new))
         .init();
 }
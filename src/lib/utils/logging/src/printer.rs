@@ -0,0 +1,58 @@
+use std::io::Write;
+use std::sync::{Mutex, PoisonError};
+
+type Printer = Box<dyn FnMut(String) + Send>;
+
+static PRINTER: Mutex<Option<Printer>> = Mutex::new(None);
+
+/// Sends log lines to `printer` instead of stdout.
+///
+/// The console uses this to print logs above its prompt, rather than through the middle of the
+/// line being typed.
+pub fn set_log_printer(printer: impl FnMut(String) + Send + 'static) {
+    *PRINTER.lock().unwrap_or_else(PoisonError::into_inner) = Some(Box::new(printer));
+}
+
+/// Goes back to logging to stdout.
+pub fn clear_log_printer() {
+    *PRINTER.lock().unwrap_or_else(PoisonError::into_inner) = None;
+}
+
+/// Collects a single log line, and hands it to the printer once it's been written.
+#[derive(Default)]
+pub(crate) struct LogWriter {
+    buffer: Vec<u8>,
+}
+
+impl LogWriter {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Write for LogWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Drop for LogWriter {
+    fn drop(&mut self) {
+        if self.buffer.is_empty() {
+            return;
+        }
+
+        let mut printer = PRINTER.lock().unwrap_or_else(PoisonError::into_inner);
+        match printer.as_mut() {
+            Some(printer) => printer(String::from_utf8_lossy(&self.buffer).into_owned()),
+            None => {
+                let _ = std::io::stdout().write_all(&self.buffer);
+            }
+        }
+    }
+}
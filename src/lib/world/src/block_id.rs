@@ -0,0 +1,43 @@
+use ferrumc_net_codec::net_types::var_int::VarInt;
+use std::fmt::Display;
+
+/// A global block state ID, as used in the network protocol and in [crate::chunk_format::BlockStates]
+/// palettes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct BlockId(pub i32);
+
+impl BlockId {
+    pub const AIR: BlockId = BlockId(0);
+    pub const VOID_AIR: BlockId = BlockId(12958);
+    pub const CAVE_AIR: BlockId = BlockId(12959);
+
+    /// Whether this is one of the air blocks, which don't count towards a section's
+    /// `non_air_blocks` and are ignored by heightmaps.
+    pub fn is_air(self) -> bool {
+        self == Self::AIR || self == Self::VOID_AIR || self == Self::CAVE_AIR
+    }
+}
+
+impl From<VarInt> for BlockId {
+    fn from(value: VarInt) -> Self {
+        Self(value.val)
+    }
+}
+
+impl From<&VarInt> for BlockId {
+    fn from(value: &VarInt) -> Self {
+        Self(value.val)
+    }
+}
+
+impl From<BlockId> for VarInt {
+    fn from(value: BlockId) -> Self {
+        VarInt::new(value.0)
+    }
+}
+
+impl Display for BlockId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
@@ -0,0 +1,924 @@
+use crate::block_id::BlockId;
+use crate::errors::WorldError;
+use crate::vanilla_chunk_format::Palette;
+use lazy_static::lazy_static;
+use serde_derive::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::io::Read;
+use std::process::exit;
+use tracing::error;
+
+// If this file doesn't exist, you'll have to create it yourself. Download the 1.21.1 server from the
+// minecraft launcher, extract the blocks data (info here https://minecraft.wiki/w/Minecraft_Wiki:Projects/wiki.vg_merge/Data_Generators#Blocks_report)
+// , put the blocks.json file in the .etc folder, and run the blocks_parser.py script in the scripts
+// folder. This will generate the blockmappings.json file that is compressed with bzip2 and included
+// in the binary.
+const BLOCKSFILE: &[u8] = include_bytes!("../../../../.etc/blockmappings.bz2");
+
+/// Reads the bundled block mappings.
+fn block_mappings() -> Result<String, std::io::Error> {
+    let mut bzipreader = bzip2::read::BzDecoder::new(BLOCKSFILE);
+    let mut output = String::new();
+    bzipreader.read_to_string(&mut output)?;
+    Ok(output)
+}
+
+lazy_static! {
+    static ref REGISTRY: BlockRegistry = {
+        let output = match block_mappings() {
+            Ok(output) => output,
+            Err(e) => {
+                error!("Could not decompress block mappings: {}", e);
+                exit(1);
+            }
+        };
+        match BlockRegistry::from_json(&output) {
+            Ok(registry) => registry,
+            Err(e) => {
+                error!("Could not load block mappings: {}", e);
+                exit(1);
+            }
+        }
+    };
+}
+
+/// A single block state: a block and a value for each of its properties.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockState {
+    pub id: BlockId,
+    /// The namespaced block name, e.g. `minecraft:oak_stairs`.
+    pub name: String,
+    pub properties: BTreeMap<String, String>,
+    /// Whether this is the state the block is placed in by default.
+    pub default: bool,
+}
+
+/// An entry in the block mappings file.
+#[derive(Deserialize)]
+struct MappingEntry {
+    name: String,
+    #[serde(default)]
+    properties: BTreeMap<String, String>,
+    #[serde(default)]
+    default: bool,
+}
+
+/// Blocks that aren't full cubes, or that don't stop movement at all. See [BlockState::is_solid].
+const PASSABLE_BLOCKS: &[&str] = &[
+    "water",
+    "lava",
+    "bubble_column",
+    "fire",
+    "soul_fire",
+    "light",
+    "structure_void",
+    "nether_portal",
+    "end_portal",
+    "end_gateway",
+    "end_portal_frame",
+    "moving_piston",
+    "piston_head",
+    "cobweb",
+    "ladder",
+    "vine",
+    "snow",
+    "powder_snow",
+    "scaffolding",
+    "farmland",
+    "dirt_path",
+    "soul_sand",
+    "mud",
+    "honey_block",
+    "cake",
+    "sea_pickle",
+    "turtle_egg",
+    "sniffer_egg",
+    "dragon_egg",
+    "frogspawn",
+    "lily_pad",
+    "chest",
+    "trapped_chest",
+    "ender_chest",
+    "iron_bars",
+    "chain",
+    "lantern",
+    "soul_lantern",
+    "end_rod",
+    "lightning_rod",
+    "hopper",
+    "cauldron",
+    "water_cauldron",
+    "lava_cauldron",
+    "powder_snow_cauldron",
+    "composter",
+    "anvil",
+    "chipped_anvil",
+    "damaged_anvil",
+    "enchanting_table",
+    "brewing_stand",
+    "lectern",
+    "grindstone",
+    "stonecutter",
+    "bell",
+    "campfire",
+    "soul_campfire",
+    "conduit",
+    "heavy_core",
+    "daylight_detector",
+    "lever",
+    "repeater",
+    "comparator",
+    "redstone_wire",
+    "tripwire",
+    "tripwire_hook",
+    "pointed_dripstone",
+    "amethyst_cluster",
+    "decorated_pot",
+    "flower_pot",
+    "candle",
+    "short_grass",
+    "tall_grass",
+    "fern",
+    "large_fern",
+    "dead_bush",
+    "sweet_berry_bush",
+    "seagrass",
+    "tall_seagrass",
+    "kelp",
+    "kelp_plant",
+    "sugar_cane",
+    "bamboo",
+    "cactus",
+    "wheat",
+    "carrots",
+    "potatoes",
+    "beetroots",
+    "nether_wart",
+    "cocoa",
+    "chorus_plant",
+    "chorus_flower",
+    "melon_stem",
+    "pumpkin_stem",
+    "azalea",
+    "flowering_azalea",
+    "mangrove_propagule",
+    "spore_blossom",
+    "glow_lichen",
+    "sculk_vein",
+    "sculk_sensor",
+    "calibrated_sculk_sensor",
+    "sculk_shrieker",
+    "big_dripleaf",
+    "big_dripleaf_stem",
+    "small_dripleaf",
+    "hanging_roots",
+    "cave_vines",
+    "cave_vines_plant",
+    "twisting_vines",
+    "twisting_vines_plant",
+    "weeping_vines",
+    "weeping_vines_plant",
+    "dandelion",
+    "poppy",
+    "blue_orchid",
+    "allium",
+    "azure_bluet",
+    "oxeye_daisy",
+    "cornflower",
+    "lily_of_the_valley",
+    "wither_rose",
+    "sunflower",
+    "lilac",
+    "rose_bush",
+    "peony",
+    "torchflower",
+    "torchflower_crop",
+    "pitcher_plant",
+    "pitcher_crop",
+    "pink_petals",
+];
+
+/// Name endings of block families that aren't full cubes. See [BlockState::is_solid].
+const PASSABLE_SUFFIXES: &[&str] = &[
+    "_slab",
+    "_stairs",
+    "_fence",
+    "_fence_gate",
+    "_wall",
+    "_pane",
+    "_door",
+    "_trapdoor",
+    "_carpet",
+    "_sign",
+    "_banner",
+    "_button",
+    "_pressure_plate",
+    "_bed",
+    "_candle",
+    "_head",
+    "_skull",
+    "torch",
+    "rail",
+    "_sapling",
+    "_tulip",
+    "_mushroom",
+    "_fungus",
+    "_roots",
+    "_sprouts",
+    "_coral",
+    "_coral_fan",
+    "_amethyst_bud",
+    "_shulker_box",
+    "_cake",
+];
+
+/// Name beginnings of block families that aren't full cubes. See [BlockState::is_solid].
+const PASSABLE_PREFIXES: &[&str] = &["potted_", "attached_"];
+
+/// Blocks with no hardness, which break as soon as anyone starts digging them. See
+/// [BlockState::breaks_instantly].
+const INSTANT_BREAK_BLOCKS: &[&str] = &[
+    "redstone_wire",
+    "repeater",
+    "comparator",
+    "tripwire",
+    "tripwire_hook",
+    "tnt",
+    "slime_block",
+    "honey_block",
+    "scaffolding",
+    "flower_pot",
+    "decorated_pot",
+    "end_rod",
+    "structure_void",
+    "fire",
+    "soul_fire",
+    "lily_pad",
+    "sugar_cane",
+    "short_grass",
+    "tall_grass",
+    "fern",
+    "large_fern",
+    "dead_bush",
+    "seagrass",
+    "tall_seagrass",
+    "kelp",
+    "kelp_plant",
+    "sea_pickle",
+    "nether_wart",
+    "wheat",
+    "carrots",
+    "potatoes",
+    "beetroots",
+    "melon_stem",
+    "pumpkin_stem",
+    "sweet_berry_bush",
+    "cave_vines",
+    "cave_vines_plant",
+    "twisting_vines",
+    "twisting_vines_plant",
+    "weeping_vines",
+    "weeping_vines_plant",
+    "hanging_roots",
+    "crimson_roots",
+    "warped_roots",
+    "nether_sprouts",
+    "spore_blossom",
+    "azalea",
+    "flowering_azalea",
+    "small_dripleaf",
+    "frogspawn",
+    "pink_petals",
+    "mangrove_propagule",
+    "dandelion",
+    "poppy",
+    "blue_orchid",
+    "allium",
+    "azure_bluet",
+    "oxeye_daisy",
+    "cornflower",
+    "lily_of_the_valley",
+    "wither_rose",
+    "sunflower",
+    "lilac",
+    "rose_bush",
+    "peony",
+    "torchflower",
+    "torchflower_crop",
+    "pitcher_plant",
+    "pitcher_crop",
+];
+
+/// Name endings of block families with no hardness. See [BlockState::breaks_instantly].
+const INSTANT_BREAK_SUFFIXES: &[&str] = &[
+    "torch",
+    "_sapling",
+    "_tulip",
+    "_mushroom",
+    "_fungus",
+    "_coral",
+    "_coral_fan",
+];
+
+/// Blocks that a placed block takes the place of, instead of being placed against. See
+/// [BlockState::is_replaceable].
+const REPLACEABLE_BLOCKS: &[&str] = &[
+    "water",
+    "lava",
+    "bubble_column",
+    "short_grass",
+    "tall_grass",
+    "fern",
+    "large_fern",
+    "dead_bush",
+    "seagrass",
+    "tall_seagrass",
+    "vine",
+    "glow_lichen",
+    "hanging_roots",
+    "crimson_roots",
+    "warped_roots",
+    "nether_sprouts",
+    "fire",
+    "soul_fire",
+    "structure_void",
+    "light",
+];
+
+/// Blocks that give off light, and how much they give off when they're lit. See
+/// [BlockState::light_emission].
+const LIGHT_SOURCES: &[(&str, u8)] = &[
+    ("beacon", 15),
+    ("campfire", 15),
+    ("conduit", 15),
+    ("end_gateway", 15),
+    ("end_portal", 15),
+    ("fire", 15),
+    ("glowstone", 15),
+    ("jack_o_lantern", 15),
+    ("lantern", 15),
+    ("lava", 15),
+    ("lava_cauldron", 15),
+    ("ochre_froglight", 15),
+    ("pearlescent_froglight", 15),
+    ("redstone_lamp", 15),
+    ("sea_lantern", 15),
+    ("shroomlight", 15),
+    ("verdant_froglight", 15),
+    ("end_rod", 14),
+    ("torch", 14),
+    ("wall_torch", 14),
+    ("blast_furnace", 13),
+    ("furnace", 13),
+    ("smoker", 13),
+    ("nether_portal", 11),
+    ("crying_obsidian", 10),
+    ("soul_campfire", 10),
+    ("soul_fire", 10),
+    ("soul_lantern", 10),
+    ("soul_torch", 10),
+    ("soul_wall_torch", 10),
+    ("deepslate_redstone_ore", 9),
+    ("redstone_ore", 9),
+    ("enchanting_table", 7),
+    ("ender_chest", 7),
+    ("glow_lichen", 7),
+    ("redstone_torch", 7),
+    ("redstone_wall_torch", 7),
+    ("amethyst_cluster", 5),
+    ("large_amethyst_bud", 4),
+    ("magma_block", 3),
+    ("medium_amethyst_bud", 2),
+    ("brewing_stand", 1),
+    ("brown_mushroom", 1),
+    ("dragon_egg", 1),
+    ("end_portal_frame", 1),
+    ("sculk_sensor", 1),
+    ("small_amethyst_bud", 1),
+];
+
+/// Blocks that only dim light a little, like water does. See [BlockState::light_opacity].
+const TRANSLUCENT_BLOCKS: &[&str] = &[
+    "water",
+    "bubble_column",
+    "ice",
+    "frosted_ice",
+    "cobweb",
+    "slime_block",
+    "honey_block",
+];
+
+/// All block states, indexed by ID and by name and properties.
+pub struct BlockRegistry {
+    states: Vec<BlockState>,
+    by_name: HashMap<String, Vec<BlockId>>,
+    by_state: HashMap<(String, BTreeMap<String, String>), BlockId>,
+}
+
+/// Adds the `minecraft:` namespace to names that don't have one.
+fn namespaced(name: &str) -> String {
+    if name.contains(':') {
+        name.to_string()
+    } else {
+        format!("minecraft:{}", name)
+    }
+}
+
+impl BlockRegistry {
+    /// The registry for the server's Minecraft version, loaded from the bundled block mappings.
+    pub fn global() -> &'static BlockRegistry {
+        &REGISTRY
+    }
+
+    /// Builds a registry from a block mappings JSON object of `"id": {name, properties, default}`.
+    pub fn from_json(json: &str) -> Result<Self, WorldError> {
+        let entries: HashMap<String, MappingEntry> = serde_json::from_str(json)
+            .map_err(|e| WorldError::InvalidBlockMappings(e.to_string()))?;
+        let mut entries = entries
+            .into_iter()
+            .map(|(id, entry)| {
+                id.parse::<i32>()
+                    .map(|id| (id, entry))
+                    .map_err(|_| WorldError::InvalidBlockMappings(format!("Invalid block ID {id}")))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        entries.sort_by_key(|(id, _)| *id);
+
+        let mut registry = BlockRegistry {
+            states: Vec::with_capacity(entries.len()),
+            by_name: HashMap::new(),
+            by_state: HashMap::with_capacity(entries.len()),
+        };
+        for (index, (id, entry)) in entries.into_iter().enumerate() {
+            if id as usize != index {
+                return Err(WorldError::InvalidBlockMappings(format!(
+                    "Missing block ID {index}"
+                )));
+            }
+            let id = BlockId(id);
+            registry
+                .by_name
+                .entry(entry.name.clone())
+                .or_default()
+                .push(id);
+            registry
+                .by_state
+                .insert((entry.name.clone(), entry.properties.clone()), id);
+            registry.states.push(BlockState {
+                id,
+                name: entry.name,
+                properties: entry.properties,
+                default: entry.default,
+            });
+        }
+        for (name, ids) in &registry.by_name {
+            let defaults = ids
+                .iter()
+                .filter(|id| registry.states[id.0 as usize].default)
+                .count();
+            if defaults != 1 {
+                return Err(WorldError::InvalidBlockMappings(format!(
+                    "{name} has {defaults} default states"
+                )));
+            }
+        }
+        Ok(registry)
+    }
+
+    /// Gets a block state by its ID.
+    pub fn get(&self, id: BlockId) -> Option<&BlockState> {
+        usize::try_from(id.0)
+            .ok()
+            .and_then(|index| self.states.get(index))
+    }
+
+    /// Finds the state of a block with exactly these properties. The `minecraft:` namespace can
+    /// be left out of the name.
+    pub fn lookup(&self, name: &str, properties: &BTreeMap<String, String>) -> Option<&BlockState> {
+        self.by_state
+            .get(&(namespaced(name), properties.clone()))
+            .and_then(|id| self.get(*id))
+    }
+
+    /// Finds the state matching a palette entry from a chunk.
+    pub fn lookup_palette(&self, palette: &Palette) -> Option<&BlockState> {
+        self.lookup(
+            &palette.name,
+            palette.properties.as_ref().unwrap_or(&BTreeMap::new()),
+        )
+    }
+
+    /// The default state of a block, which is the one it's placed in unless something about the
+    /// placement changes it.
+    pub fn default_state(&self, name: &str) -> Option<&BlockState> {
+        self.states_of(name).find(|state| state.default)
+    }
+
+    /// All states of a block, in ID order. Empty if the block doesn't exist.
+    pub fn states_of<'a>(&'a self, name: &str) -> impl Iterator<Item = &'a BlockState> + 'a {
+        self.by_name
+            .get(&namespaced(name))
+            .into_iter()
+            .flatten()
+            .filter_map(|id| self.get(*id))
+    }
+
+    /// All block states, in ID order.
+    pub fn iter(&self) -> impl Iterator<Item = &BlockState> {
+        self.states.iter()
+    }
+
+    /// The number of block states.
+    pub fn len(&self) -> usize {
+        self.states.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.states.is_empty()
+    }
+
+    /// The state of the same block with one property changed, if that combination exists.
+    pub fn with_property(
+        &self,
+        state: &BlockState,
+        property: &str,
+        value: &str,
+    ) -> Option<&BlockState> {
+        if !state.properties.contains_key(property) {
+            return None;
+        }
+        let mut properties = state.properties.clone();
+        properties.insert(property.to_string(), value.to_string());
+        self.lookup(&state.name, &properties)
+    }
+}
+
+impl BlockState {
+    /// The raw value of a property.
+    pub fn property(&self, name: &str) -> Option<&str> {
+        self.properties.get(name).map(String::as_str)
+    }
+
+    pub fn facing(&self) -> Option<Facing> {
+        self.property("facing").and_then(Facing::parse)
+    }
+
+    pub fn axis(&self) -> Option<Axis> {
+        self.property("axis").and_then(Axis::parse)
+    }
+
+    pub fn half(&self) -> Option<Half> {
+        self.property("half").and_then(Half::parse)
+    }
+
+    pub fn waterlogged(&self) -> Option<bool> {
+        self.bool_property("waterlogged")
+    }
+
+    pub fn powered(&self) -> Option<bool> {
+        self.bool_property("powered")
+    }
+
+    pub fn open(&self) -> Option<bool> {
+        self.bool_property("open")
+    }
+
+    fn bool_property(&self, name: &str) -> Option<bool> {
+        self.property(name).and_then(|value| value.parse().ok())
+    }
+
+    /// Whether this state is of the given block. The `minecraft:` namespace can be left out.
+    pub fn is(&self, name: &str) -> bool {
+        self.name == namespaced(name)
+    }
+
+    /// Whether this block breaks as soon as digging starts, whatever the player is holding.
+    ///
+    /// The block mappings don't include hardness, so this is looked up by name. Blocks that
+    /// only break instantly with the right tool and enchantments aren't counted.
+    pub fn breaks_instantly(&self) -> bool {
+        let name = self.name.strip_prefix("minecraft:").unwrap_or(&self.name);
+        INSTANT_BREAK_BLOCKS.contains(&name)
+            || INSTANT_BREAK_SUFFIXES
+                .iter()
+                .any(|suffix| name.ends_with(suffix))
+            || name.starts_with("potted_")
+            || name.starts_with("attached_")
+    }
+
+    /// Whether placing a block here replaces this one, e.g. air, liquids, grass and single snow
+    /// layers.
+    pub fn is_replaceable(&self) -> bool {
+        let name = self.name.strip_prefix("minecraft:").unwrap_or(&self.name);
+        name.ends_with("air")
+            || REPLACEABLE_BLOCKS.contains(&name)
+            || (name == "snow" && self.property("layers") == Some("1"))
+    }
+
+    /// Whether this is a full, solid cube that entities can't be inside of.
+    ///
+    /// The block mappings don't include collision shapes, so this is guessed from the block's
+    /// name. Anything that might not be a full cube counts as passable, so this errs towards
+    /// letting players through.
+    pub fn is_solid(&self) -> bool {
+        let name = self.name.strip_prefix("minecraft:").unwrap_or(&self.name);
+        !(name.ends_with("air")
+            || PASSABLE_BLOCKS.contains(&name)
+            || PASSABLE_SUFFIXES
+                .iter()
+                .any(|suffix| name.ends_with(suffix))
+            || PASSABLE_PREFIXES
+                .iter()
+                .any(|prefix| name.starts_with(prefix)))
+    }
+
+    /// Whether this is water or lava.
+    pub fn is_liquid(&self) -> bool {
+        self.is("water") || self.is("lava")
+    }
+
+    /// How much light the block gives off, from 0 to 15. Blocks with a `lit` property only give
+    /// off light when they're lit.
+    pub fn light_emission(&self) -> u8 {
+        if self.bool_property("lit") == Some(false) {
+            return 0;
+        }
+        let name = self.name.strip_prefix("minecraft:").unwrap_or(&self.name);
+        if name == "light" {
+            return self
+                .property("level")
+                .and_then(|level| level.parse().ok())
+                .unwrap_or(15);
+        }
+        LIGHT_SOURCES
+            .iter()
+            .find(|(source, _)| *source == name)
+            .map_or(0, |(_, emission)| *emission)
+    }
+
+    /// How much light is lost going into the block, from 0 to 15.
+    ///
+    /// Like [BlockState::is_solid], this is guessed from the block's name: solid blocks stop
+    /// light, apart from glass, and leaves and blocks like water dim it.
+    pub fn light_opacity(&self) -> u8 {
+        let name = self.name.strip_prefix("minecraft:").unwrap_or(&self.name);
+        if TRANSLUCENT_BLOCKS.contains(&name)
+            || name.ends_with("_leaves")
+            || self.waterlogged() == Some(true)
+        {
+            1
+        } else if !self.is_solid() || (name.ends_with("glass") && name != "tinted_glass") {
+            0
+        } else {
+            15
+        }
+    }
+
+    /// Converts this state into a chunk palette entry.
+    pub fn to_palette(&self) -> Palette {
+        Palette {
+            name: self.name.clone(),
+            properties: if self.properties.is_empty() {
+                None
+            } else {
+                Some(self.properties.clone())
+            },
+        }
+    }
+}
+
+impl BlockId {
+    /// The state for this ID in the global registry.
+    pub fn state(self) -> Option<&'static BlockState> {
+        BlockRegistry::global().get(self)
+    }
+}
+
+/// The direction a block faces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Facing {
+    North,
+    South,
+    East,
+    West,
+    Up,
+    Down,
+}
+
+impl Facing {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "north" => Some(Facing::North),
+            "south" => Some(Facing::South),
+            "east" => Some(Facing::East),
+            "west" => Some(Facing::West),
+            "up" => Some(Facing::Up),
+            "down" => Some(Facing::Down),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Facing::North => "north",
+            Facing::South => "south",
+            Facing::East => "east",
+            Facing::West => "west",
+            Facing::Up => "up",
+            Facing::Down => "down",
+        }
+    }
+}
+
+/// The axis a block such as a log is aligned to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Axis {
+    X,
+    Y,
+    Z,
+}
+
+impl Axis {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "x" => Some(Axis::X),
+            "y" => Some(Axis::Y),
+            "z" => Some(Axis::Z),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Axis::X => "x",
+            Axis::Y => "y",
+            Axis::Z => "z",
+        }
+    }
+}
+
+/// Which half of a block a state is. Stairs and trapdoors use top/bottom, doors and tall plants
+/// use upper/lower.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Half {
+    Top,
+    Bottom,
+    Upper,
+    Lower,
+}
+
+impl Half {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "top" => Some(Half::Top),
+            "bottom" => Some(Half::Bottom),
+            "upper" => Some(Half::Upper),
+            "lower" => Some(Half::Lower),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Half::Top => "top",
+            Half::Bottom => "bottom",
+            Half::Upper => "upper",
+            Half::Lower => "lower",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAPPINGS: &str = r#"{
+        "0": {"default": true, "name": "minecraft:air"},
+        "1": {"name": "minecraft:oak_log", "properties": {"axis": "x"}},
+        "2": {"default": true, "name": "minecraft:oak_log", "properties": {"axis": "y"}},
+        "3": {"name": "minecraft:oak_log", "properties": {"axis": "z"}},
+        "4": {"name": "minecraft:oak_trapdoor", "properties": {"facing": "north", "half": "top", "waterlogged": "true"}},
+        "5": {"default": true, "name": "minecraft:oak_trapdoor", "properties": {"facing": "north", "half": "top", "waterlogged": "false"}}
+    }"#;
+
+    fn properties(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_lookup() {
+        let registry = BlockRegistry::from_json(MAPPINGS).unwrap();
+        assert_eq!(registry.len(), 6);
+        assert_eq!(
+            registry.lookup("air", &BTreeMap::new()).unwrap().id,
+            BlockId(0)
+        );
+        let log = registry
+            .lookup("minecraft:oak_log", &properties(&[("axis", "z")]))
+            .unwrap();
+        assert_eq!(log.id, BlockId(3));
+        assert_eq!(log.axis(), Some(Axis::Z));
+        assert!(registry.lookup("oak_log", &BTreeMap::new()).is_none());
+        assert_eq!(
+            registry.lookup_palette(&log.to_palette()).unwrap().id,
+            log.id
+        );
+    }
+
+    #[test]
+    fn test_defaults_and_states() {
+        let registry = BlockRegistry::from_json(MAPPINGS).unwrap();
+        assert_eq!(registry.default_state("oak_log").unwrap().id, BlockId(2));
+        assert_eq!(registry.states_of("oak_log").count(), 3);
+        assert_eq!(registry.states_of("stone").count(), 0);
+
+        let trapdoor = registry.default_state("oak_trapdoor").unwrap();
+        assert_eq!(trapdoor.facing(), Some(Facing::North));
+        assert_eq!(trapdoor.half(), Some(Half::Top));
+        assert_eq!(trapdoor.waterlogged(), Some(false));
+        let waterlogged = registry
+            .with_property(trapdoor, "waterlogged", "true")
+            .unwrap();
+        assert_eq!(waterlogged.id, BlockId(4));
+        assert!(registry.with_property(trapdoor, "axis", "x").is_none());
+    }
+
+    #[test]
+    fn test_is_solid() {
+        let registry = BlockRegistry::from_json(MAPPINGS).unwrap();
+        assert!(!registry.default_state("air").unwrap().is_solid());
+        assert!(registry.default_state("oak_log").unwrap().is_solid());
+        assert!(!registry.default_state("oak_trapdoor").unwrap().is_solid());
+    }
+
+    #[test]
+    fn test_breaks_instantly() {
+        let registry = BlockRegistry::global();
+        for name in [
+            "torch",
+            "redstone_wall_torch",
+            "short_grass",
+            "poppy",
+            "red_tulip",
+        ] {
+            assert!(registry.default_state(name).unwrap().breaks_instantly());
+        }
+        for name in [
+            "stone",
+            "mushroom_stem",
+            "mangrove_roots",
+            "tube_coral_block",
+        ] {
+            assert!(!registry.default_state(name).unwrap().breaks_instantly());
+        }
+    }
+
+    #[test]
+    fn test_is_replaceable() {
+        let registry = BlockRegistry::global();
+        for name in ["air", "cave_air", "water", "lava", "short_grass", "snow"] {
+            assert!(registry.default_state(name).unwrap().is_replaceable());
+        }
+        for name in ["stone", "grass_block", "poppy", "snow_block"] {
+            assert!(!registry.default_state(name).unwrap().is_replaceable());
+        }
+        let snow = registry.default_state("snow").unwrap();
+        let deep_snow = registry.with_property(snow, "layers", "2").unwrap();
+        assert!(!deep_snow.is_replaceable());
+    }
+
+    #[test]
+    fn test_invalid_mappings() {
+        assert!(BlockRegistry::from_json("[]").is_err());
+        assert!(BlockRegistry::from_json(r#"{"1": {"name": "minecraft:stone"}}"#).is_err());
+        // Every block needs exactly one default state
+        assert!(BlockRegistry::from_json(r#"{"0": {"name": "minecraft:stone"}}"#).is_err());
+    }
+
+    #[test]
+    fn test_bundled_defaults() {
+        let registry = BlockRegistry::global();
+        let grass = registry.default_state("grass_block").unwrap();
+        assert_eq!(grass.id, BlockId(9));
+        assert_eq!(grass.property("snowy"), Some("false"));
+
+        let slab = registry.default_state("oak_slab").unwrap();
+        assert_eq!(slab.property("type"), Some("bottom"));
+        assert_eq!(slab.waterlogged(), Some(false));
+        let stairs = registry.default_state("oak_stairs").unwrap();
+        assert_eq!(stairs.half(), Some(Half::Bottom));
+        assert_eq!(stairs.waterlogged(), Some(false));
+        assert_eq!(
+            registry.default_state("oak_log").unwrap().axis(),
+            Some(Axis::Y)
+        );
+        assert_eq!(
+            registry.default_state("glass_pane").unwrap().waterlogged(),
+            Some(false)
+        );
+        assert_eq!(registry.default_state("water").unwrap().id, BlockId(80));
+    }
+}
@@ -0,0 +1,96 @@
+//! The world border, which players can't go past.
+
+use crate::errors::WorldError;
+use crate::meta::BORDER_KEY;
+use crate::World;
+use bitcode_derive::{Decode, Encode};
+use ferrumc_config::statics::get_global_config;
+
+/// A square border around the world, centered on a point.
+#[derive(Encode, Decode, Clone, Copy, Debug, PartialEq)]
+pub struct WorldBorder {
+    pub center_x: f64,
+    pub center_z: f64,
+    /// How wide the border is, in blocks.
+    pub diameter: f64,
+    /// How close to the border players get a warning, in blocks.
+    pub warning_blocks: i32,
+    /// How long before a moving border reaches players they get a warning, in seconds.
+    pub warning_time: i32,
+}
+
+impl WorldBorder {
+    /// The border from the config.
+    pub fn from_config() -> Self {
+        let config = &get_global_config().world_border;
+        Self {
+            center_x: config.center_x,
+            center_z: config.center_z,
+            diameter: config.diameter,
+            warning_blocks: config.warning_blocks,
+            warning_time: config.warning_time,
+        }
+    }
+
+    /// How far outside the border a position is, or 0 if it's inside.
+    pub fn distance_outside(&self, x: f64, z: f64) -> f64 {
+        let radius = self.diameter / 2.0;
+        let dx = (x - self.center_x).abs() - radius;
+        let dz = (z - self.center_z).abs() - radius;
+        dx.max(dz).max(0.0)
+    }
+
+    pub fn contains(&self, x: f64, z: f64) -> bool {
+        self.distance_outside(x, z) == 0.0
+    }
+
+    /// Whether the whole of a block is inside the border.
+    pub fn contains_block(&self, x: i32, z: i32) -> bool {
+        self.contains(x as f64, z as f64) && self.contains(x as f64 + 1.0, z as f64 + 1.0)
+    }
+}
+
+impl World {
+    /// The world border.
+    pub fn border(&self) -> WorldBorder {
+        *self.border.lock()
+    }
+
+    /// Changes the world border, and saves it so it's used over the config from now on.
+    pub async fn set_border(&self, border: WorldBorder) -> Result<(), WorldError> {
+        *self.border.lock() = border;
+        self.save_meta(BORDER_KEY, &border).await
+    }
+
+    /// Loads the saved border, or the one from the config if there isn't one.
+    pub(crate) async fn load_border(&self) -> Result<WorldBorder, WorldError> {
+        Ok(self
+            .load_meta(BORDER_KEY)
+            .await?
+            .unwrap_or_else(WorldBorder::from_config))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_border_bounds() {
+        let border = WorldBorder {
+            center_x: 10.0,
+            center_z: 0.0,
+            diameter: 20.0,
+            warning_blocks: 5,
+            warning_time: 15,
+        };
+        assert!(border.contains(0.0, 10.0));
+        assert!(border.contains(19.5, -9.5));
+        assert_eq!(border.distance_outside(23.0, 0.0), 3.0);
+        assert_eq!(border.distance_outside(10.0, -15.0), 5.0);
+
+        assert!(border.contains_block(19, 9));
+        assert!(!border.contains_block(20, 0));
+        assert!(!border.contains_block(0, -11));
+    }
+}
@@ -1,41 +1,21 @@
+use crate::block_id::BlockId;
+use crate::block_state::BlockRegistry;
+use crate::edits::{bits_for_palette, pack, MIN_INDIRECT_BITS};
 use crate::errors::WorldError;
 use crate::vanilla_chunk_format;
-use crate::vanilla_chunk_format::VanillaChunk;
+use crate::vanilla_chunk_format::{VanillaChunk, VanillaHeightmaps};
 use bitcode_derive::{Decode, Encode};
 use deepsize::DeepSizeOf;
+use ferrumc_core::dimensions::dimension::Dimension;
 use ferrumc_macros::{NBTDeserialize, NBTSerialize};
+use ferrumc_nbt::{NBTSerializable, NBTSerializeOptions};
 use ferrumc_net_codec::net_types::var_int::VarInt;
-use lazy_static::lazy_static;
 use std::collections::HashMap;
-use std::io::Read;
 use tracing::error;
 use vanilla_chunk_format::Palette;
 
-#[cfg(test)]
-const BLOCKSFILE: &[u8] = &[0];
-
-// If this file doesn't exist, you'll have to create it yourself. Download the 1.21.1 server from the
-// minecraft launcher, extract the blocks data (info here https://minecraft.wiki/w/Minecraft_Wiki:Projects/wiki.vg_merge/Data_Generators#Blocks_report)
-// , put the blocks.json file in the .etc folder, and run the blocks_parser.py script in the scripts
-// folder. This will generate the blockmappings.json file that is compressed with bzip2 and included
-// in the binary.
-#[cfg(not(test))]
-const BLOCKSFILE: &[u8] = include_bytes!("../../../../.etc/blockmappings.bz2");
-
-lazy_static! {
-    static ref ID2BLOCK: HashMap<i32, Palette> = {
-        let mut bzipreader = bzip2::read::BzDecoder::new(BLOCKSFILE);
-        let mut output = String::new();
-        bzipreader.read_to_string(&mut output).unwrap();
-        let string_keys: HashMap<String, Palette> = serde_json::from_str(&output).unwrap();
-        string_keys
-            .iter()
-            .map(|(k, v)| (k.parse::<i32>().unwrap(), v.clone()))
-            .collect()
-    };
-    static ref BLOCK2ID: HashMap<Palette, i32> =
-        ID2BLOCK.iter().map(|(k, v)| (v.clone(), *k)).collect();
-}
+/// The data version of chunks saved by 1.21.1, which is what exported chunks claim to be.
+const DATA_VERSION: i32 = 3955;
 
 #[derive(Encode, Decode, Clone, DeepSizeOf)]
 // This is a placeholder for the actual chunk format
@@ -75,8 +55,8 @@ pub struct BlockStates {
 fn convert_to_net_palette(vanilla_palettes: Vec<Palette>) -> Result<Vec<VarInt>, WorldError> {
     let mut new_palette = Vec::new();
     for palette in vanilla_palettes {
-        if let Some(id) = BLOCK2ID.get(&palette) {
-            new_palette.push(VarInt::from(*id));
+        if let Some(state) = BlockRegistry::global().lookup_palette(&palette) {
+            new_palette.push(VarInt::from(state.id));
         } else {
             new_palette.push(VarInt::from(0));
             error!("Could not find block id for palette entry: {:?}", palette);
@@ -101,7 +81,9 @@ impl Default for Heightmaps {
 }
 
 impl VanillaChunk {
-    pub fn to_custom_format(&self) -> Result<Chunk, WorldError> {
+    /// Converts the chunk, which vanilla doesn't say the dimension of, for the dimension whose
+    /// region folder it was found in.
+    pub fn to_custom_format(&self, dimension: Dimension) -> Result<Chunk, WorldError> {
         let mut sections = Vec::new();
         for section in self.sections.as_ref().unwrap() {
             let y = section.y;
@@ -124,13 +106,17 @@ impl VanillaChunk {
                 .biomes
                 .as_ref()
                 .map_or(vec![], |biome_data| biome_data.palette.clone());
-            let non_air_blocks = palette.iter().filter(|id| id.name != "air").count() as u16;
-            let block_states = BlockStates {
+            let mut block_states = BlockStates {
                 bits_per_block: (palette.len() as f32).log2().ceil() as u8,
-                non_air_blocks,
+                non_air_blocks: 0,
                 data: block_data,
                 palette: convert_to_net_palette(palette)?,
             };
+            block_states.non_air_blocks = block_states
+                .blocks()
+                .iter()
+                .filter(|block| !block.is_air())
+                .count() as u16;
             let block_light = section
                 .block_light
                 .clone()
@@ -171,9 +157,161 @@ impl VanillaChunk {
         Ok(Chunk {
             x: self.x_pos,
             z: self.z_pos,
-            dimension: self.clone().dimension.unwrap_or("overworld".to_string()),
+            dimension: dimension.name().to_string(),
             sections,
             heightmaps,
         })
     }
+
+    /// Converts a chunk back into the format vanilla saves it in, so it can be written to a
+    /// region file.
+    pub fn from_custom_format(chunk: &Chunk) -> Self {
+        Self::from_custom_format_with(chunk, |block| match block.state() {
+            Some(state) => state.to_palette(),
+            None => {
+                error!("Could not find block state for id: {}", block.0);
+                Palette {
+                    name: "minecraft:air".to_string(),
+                    properties: None,
+                }
+            }
+        })
+    }
+
+    /// [VanillaChunk::from_custom_format] with the palette entry of each block given by
+    /// `palette_of`.
+    fn from_custom_format_with(chunk: &Chunk, palette_of: impl Fn(BlockId) -> Palette) -> Self {
+        let sections = chunk
+            .sections
+            .iter()
+            .map(|section| {
+                let (block_light, sky_light) = if section.has_light() {
+                    let to_nbt = |light: &[u8]| light.iter().map(|x| *x as i8).collect();
+                    (
+                        Some(to_nbt(&section.block_light)),
+                        Some(to_nbt(&section.sky_light)),
+                    )
+                } else {
+                    (None, None)
+                };
+                vanilla_chunk_format::Section {
+                    block_states: Some(section.block_states.to_vanilla(&palette_of)),
+                    biomes: (!section.biome_palette.is_empty()).then(|| {
+                        vanilla_chunk_format::Biomes {
+                            data: (!section.biome_data.is_empty())
+                                .then(|| section.biome_data.clone()),
+                            palette: section.biome_palette.clone(),
+                        }
+                    }),
+                    y: section.y,
+                    block_light,
+                    sky_light,
+                }
+            })
+            .collect();
+        let heightmap = |heightmap: &Vec<i64>| (!heightmap.is_empty()).then(|| heightmap.clone());
+        VanillaChunk {
+            status: "minecraft:full".to_string(),
+            data_version: DATA_VERSION,
+            heightmaps: Some(VanillaHeightmaps {
+                motion_blocking: heightmap(&chunk.heightmaps.motion_blocking),
+                world_surface: heightmap(&chunk.heightmaps.world_surface),
+            }),
+            is_light_on: Some(chunk.has_light() as i8),
+            inhabited_time: Some(0),
+            // Sections that only hold light can be below the dimension, so they don't say where
+            // it starts
+            y_pos: chunk.min_y().div_euclid(16),
+            x_pos: chunk.x,
+            z_pos: chunk.z,
+            structures: None,
+            last_update: Some(0),
+            sections: Some(sections),
+        }
+    }
+
+    /// The chunk as uncompressed NBT, as it's stored in region files.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        self.serialize(&mut bytes, &NBTSerializeOptions::WithHeader(""));
+        bytes
+    }
+}
+
+impl BlockStates {
+    /// The blocks in the format vanilla saves them in, which always has a palette of block
+    /// names and leaves out the data when there's only one block.
+    fn to_vanilla(
+        &self,
+        palette_of: &impl Fn(BlockId) -> Palette,
+    ) -> vanilla_chunk_format::BlockStates {
+        let mut palette = Vec::new();
+        let mut palette_indexes = HashMap::new();
+        let indexes = self
+            .blocks()
+            .into_iter()
+            .map(|block| {
+                *palette_indexes.entry(block).or_insert_with(|| {
+                    palette.push(block);
+                    palette.len() as u32 - 1
+                })
+            })
+            .collect::<Vec<_>>();
+        let data = (palette.len() > 1).then(|| {
+            pack(
+                indexes.into_iter(),
+                bits_for_palette(palette.len()).max(MIN_INDIRECT_BITS),
+            )
+        });
+        vanilla_chunk_format::BlockStates {
+            data,
+            palette: Some(palette.into_iter().map(palette_of).collect()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generation::{ChunkGenerator, SuperflatGenerator};
+
+    #[test]
+    fn test_to_vanilla() {
+        let generator =
+            SuperflatGenerator::new(vec![BlockId(1), BlockId(2), BlockId(2), BlockId(3)]);
+        let chunk = generator.generate(3, -2, Dimension::Overworld);
+        let vanilla = VanillaChunk::from_custom_format_with(&chunk, |block| Palette {
+            name: format!("test:{}", block.0),
+            properties: None,
+        });
+        assert_eq!(
+            VanillaChunk::from_bytes(&vanilla.to_bytes()).unwrap(),
+            vanilla
+        );
+        assert_eq!((vanilla.x_pos, vanilla.y_pos, vanilla.z_pos), (3, -4, -2));
+        assert_eq!(vanilla.is_light_on, Some(1));
+
+        let sections = vanilla.sections.unwrap();
+        let top = sections.last().unwrap().block_states.as_ref().unwrap();
+        assert_eq!(top.data, None);
+        assert_eq!(top.palette.as_ref().unwrap()[0].name, "test:0");
+
+        // Reading the bottom section back through its palette gives the same blocks
+        let bottom = sections[0].block_states.as_ref().unwrap();
+        let palette = bottom
+            .palette
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(|entry| VarInt::from(entry.name["test:".len()..].parse::<i32>().unwrap()))
+            .collect::<Vec<_>>();
+        assert_eq!(palette.len(), 4);
+        let states = BlockStates {
+            bits_per_block: 4,
+            non_air_blocks: 0,
+            data: bottom.data.clone().unwrap(),
+            palette,
+        };
+        assert_eq!(states.blocks(), chunk.sections[0].block_states.blocks());
+    }
 }
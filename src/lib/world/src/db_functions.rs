@@ -1,9 +1,15 @@
 use crate::chunk_format::Chunk;
 use crate::errors::WorldError;
 use crate::World;
+use ferrumc_core::dimensions::dimension::Dimension;
 use ferrumc_storage::compressors::Compressor;
+use ferrumc_storage::errors::StorageError;
 use std::hash::Hasher;
-use tracing::trace;
+use tokio::task::JoinSet;
+use tracing::{error, trace};
+
+/// The table chunks are saved in, keyed by [create_key].
+pub(crate) const CHUNKS_TABLE: &str = "chunks";
 
 impl World {
     /// Save a chunk to the storage backend
@@ -20,11 +26,16 @@ impl World {
     /// Load a chunk from the storage backend. If the chunk is in the cache, it will be returned
     /// from the cache instead of the storage backend. If the chunk is not in the cache, it will be
     /// loaded from the storage backend and inserted into the cache.
+    ///
+    /// Chunks that don't exist yet are generated by the dimension's generator and saved.
     pub async fn load_chunk(&self, x: i32, z: i32, dimension: &str) -> Result<Chunk, WorldError> {
         if let Some(chunk) = self.cache.get(&(x, z, dimension.to_string())).await {
             return Ok(chunk);
         }
-        let chunk = load_chunk_internal(self, &self.compressor, x, z, dimension).await;
+        let chunk = match load_chunk_internal(self, &self.compressor, x, z, dimension).await {
+            Err(WorldError::ChunkNotFound) => return self.generate_chunk(x, z, dimension).await,
+            chunk => chunk,
+        };
         if let Ok(ref chunk) = chunk {
             self.cache
                 .insert((x, z, dimension.to_string()), chunk.clone())
@@ -66,36 +77,92 @@ impl World {
         sync_internal(self).await
     }
 
+    /// Save the cache and the time, and close the storage backend.
+    ///
+    /// This should be the last thing done with the world before the server exits, so that no
+    /// cached chunks are lost.
+    pub async fn close(&self) -> Result<(), WorldError> {
+        self.save_time().await?;
+        self.sync().await?;
+        self.storage_backend.close().await?;
+        Ok(())
+    }
+
     /// Load a batch of chunks from the storage backend.
     ///
     /// This function attempts to load as many chunks as it can find from the cache first, then fetches
-    /// the missing chunks from the storage backend. The chunks are then inserted into the cache and
-    /// returned as a vector.
+    /// the missing chunks from the storage backend in one go. Chunks that don't exist yet are
+    /// generated, all at the same time. The chunks are inserted into the cache.
+    ///
+    /// Each coordinate gets its own result, in the same order as `coords`, so a chunk that can't
+    /// be loaded doesn't stop the others from loading.
     pub async fn load_chunk_batch(
         &self,
         coords: Vec<(i32, i32, &str)>,
-    ) -> Result<Vec<Chunk>, WorldError> {
-        let mut found_chunks = Vec::new();
-        let mut missing_chunks = Vec::new();
-        for coord in coords.iter() {
-            if let Some(chunk) = self
-                .cache
-                .get(&(coord.0, coord.1, coord.2.to_string()))
-                .await
-            {
-                found_chunks.push(chunk);
-            } else {
-                missing_chunks.push(*coord);
+    ) -> Vec<((i32, i32), Result<Chunk, WorldError>)> {
+        let mut results: Vec<Option<Result<Chunk, WorldError>>> = Vec::with_capacity(coords.len());
+        let mut missing = Vec::new();
+        for (index, &(x, z, dimension)) in coords.iter().enumerate() {
+            let cached = self.cache.get(&(x, z, dimension.to_string())).await;
+            if cached.is_none() {
+                missing.push(index);
             }
+            results.push(cached.map(Ok));
         }
-        let fetched = load_chunk_batch_internal(self, missing_chunks).await?;
-        for chunk in fetched {
-            self.cache
-                .insert((chunk.x, chunk.z, chunk.dimension.clone()), chunk.clone())
-                .await;
-            found_chunks.push(chunk);
+
+        let fetched = match load_chunk_batch_internal(
+            self,
+            missing.iter().map(|&index| coords[index]).collect(),
+        )
+        .await
+        {
+            Ok(fetched) => fetched,
+            // The whole fetch failed, so every missing chunk failed with it
+            Err(e) => missing
+                .iter()
+                .map(|_| Err(StorageError::ReadError(e.to_string()).into()))
+                .collect(),
+        };
+
+        let mut generating = JoinSet::new();
+        for (&index, chunk) in missing.iter().zip(fetched) {
+            let (x, z, dimension) = coords[index];
+            match chunk {
+                Ok(chunk) => {
+                    self.cache
+                        .insert((x, z, dimension.to_string()), chunk.clone())
+                        .await;
+                    results[index] = Some(Ok(chunk));
+                }
+                Err(WorldError::ChunkNotFound) => {
+                    let world = self.clone();
+                    let dimension = dimension.to_string();
+                    generating.spawn(async move {
+                        (index, world.generate_chunk(x, z, &dimension).await)
+                    });
+                }
+                Err(e) => results[index] = Some(Err(e)),
+            }
+        }
+        while let Some(generated) = generating.join_next().await {
+            match generated {
+                Ok((index, chunk)) => results[index] = Some(chunk),
+                Err(e) => error!("A chunk generation task failed: {}", e),
+            }
         }
-        Ok(found_chunks)
+
+        coords
+            .iter()
+            .zip(results)
+            .map(|(&(x, z, _), result)| {
+                let result = result.unwrap_or_else(|| {
+                    Err(WorldError::InvalidGenerator(
+                        "The chunk's generation task failed".to_string(),
+                    ))
+                });
+                ((x, z), result)
+            })
+            .collect()
     }
 
     /// Pre-cache a chunk in the cache
@@ -124,7 +191,7 @@ pub(crate) async fn save_chunk_internal(world: &World, chunk: Chunk) -> Result<(
     let digest = create_key(chunk.dimension.as_str(), chunk.x, chunk.z);
     world
         .storage_backend
-        .upsert("chunks".to_string(), digest, as_bytes)
+        .upsert(CHUNKS_TABLE.to_string(), digest, as_bytes)
         .await?;
     Ok(())
 }
@@ -139,7 +206,7 @@ pub(crate) async fn load_chunk_internal(
     let digest = create_key(dimension, x, z);
     match world
         .storage_backend
-        .get("chunks".to_string(), digest)
+        .get(CHUNKS_TABLE.to_string(), digest)
         .await?
     {
         Some(compressed) => {
@@ -152,17 +219,19 @@ pub(crate) async fn load_chunk_internal(
     }
 }
 
+/// Fetches a batch of chunks, giving each coordinate its own result. Chunks that aren't in the
+/// database are [WorldError::ChunkNotFound]. Only a failure of the whole fetch is an error.
 pub(crate) async fn load_chunk_batch_internal(
     world: &World,
     coords: Vec<(i32, i32, &str)>,
-) -> Result<Vec<Chunk>, WorldError> {
+) -> Result<Vec<Result<Chunk, WorldError>>, StorageError> {
     let digests = coords
         .into_iter()
         .map(|(x, z, dim)| create_key(dim, x, z))
         .collect();
-    world
+    Ok(world
         .storage_backend
-        .batch_get("chunks".to_string(), digests)
+        .batch_get(CHUNKS_TABLE.to_string(), digests)
         .await?
         .iter()
         .map(|chunk| match chunk {
@@ -174,7 +243,7 @@ pub(crate) async fn load_chunk_batch_internal(
             }
             None => Err(WorldError::ChunkNotFound),
         })
-        .collect()
+        .collect())
 }
 
 pub(crate) async fn chunk_exists_internal(
@@ -186,7 +255,7 @@ pub(crate) async fn chunk_exists_internal(
     let digest = create_key(dimension, x, z);
     Ok(world
         .storage_backend
-        .exists("chunks".to_string(), digest)
+        .exists(CHUNKS_TABLE.to_string(), digest)
         .await?)
 }
 
@@ -199,7 +268,7 @@ pub(crate) async fn delete_chunk_internal(
     let digest = create_key(dimension, x, z);
     world
         .storage_backend
-        .delete("chunks".to_string(), digest)
+        .delete(CHUNKS_TABLE.to_string(), digest)
         .await?;
     Ok(())
 }
@@ -223,3 +292,56 @@ fn create_key(dimension: &str, x: i32, z: i32) -> u128 {
 
     key
 }
+
+/// The dimension and coordinates of the chunk a key was made for by [create_key]. Keys of
+/// dimensions other than the known ones give `None`.
+pub(crate) fn parse_key(key: u128) -> Option<(Dimension, i32, i32)> {
+    let dimension = Dimension::ALL
+        .into_iter()
+        .find(|dimension| create_key(dimension.name(), 0, 0) >> 96 == key >> 96)?;
+    let x = ((key >> 48) & 0xFFFF_FFFF) as u32 as i32;
+    let z = (key & 0xFFFF_FFFF) as u32 as i32;
+    Some((dimension, x, z))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block_id::BlockId;
+    use crate::generation::{ChunkGenerator, SuperflatGenerator};
+
+    #[test]
+    fn test_parse_key() {
+        for (dimension, x, z) in [
+            (Dimension::Overworld, 0, 0),
+            (Dimension::Nether, -1, 30_000),
+            (Dimension::End, i32::MAX, i32::MIN),
+        ] {
+            assert_eq!(
+                parse_key(create_key(dimension.name(), x, z)),
+                Some((dimension, x, z))
+            );
+        }
+        assert_eq!(parse_key(create_key("aether", 1, 2)), None);
+    }
+
+    #[tokio::test]
+    async fn test_memory_backend_world() {
+        let world = World::in_memory().await;
+        let chunk = SuperflatGenerator::new(vec![BlockId(1), BlockId(2)]).generate(
+            4,
+            -7,
+            Dimension::Nether,
+        );
+        world.save_chunk(chunk).await.unwrap();
+
+        // Loading it again has to go through the backend
+        world.cache.invalidate_all();
+        assert!(world.chunk_exists(4, -7, "the_nether").await.unwrap());
+        let loaded = world.load_chunk(4, -7, "the_nether").await.unwrap();
+        assert_eq!(loaded.get_block(3, 1, 9).unwrap(), BlockId(2));
+
+        world.delete_chunk(4, -7, "the_nether").await.unwrap();
+        assert!(!world.chunk_exists(4, -7, "the_nether").await.unwrap());
+    }
+}
@@ -0,0 +1,573 @@
+use crate::block_id::BlockId;
+use crate::chunk_format::{BlockStates, Chunk, Heightmaps, Section};
+use crate::errors::WorldError;
+use crate::lighting::{BlockChange, LightProperties};
+use crate::World;
+use ferrumc_core::dimensions::dimension::Dimension;
+use ferrumc_net_codec::net_types::var_int::VarInt;
+use std::collections::HashMap;
+
+/// The number of blocks in a single section.
+const SECTION_VOLUME: usize = 16 * 16 * 16;
+/// Indirect palettes always use at least this many bits per block.
+pub(crate) const MIN_INDIRECT_BITS: u8 = 4;
+/// The largest bits per block an indirect palette can use before switching to the direct palette.
+const MAX_INDIRECT_BITS: u8 = 8;
+/// Bits per block for the direct palette. There are 26684 block states in 1.21.1, which need
+/// 15 bits.
+pub const DIRECT_BITS: u8 = 15;
+/// Heightmaps store one 9-bit entry per column.
+const HEIGHTMAP_BITS: u8 = 9;
+
+/// How the blocks of a section are stored.
+#[derive(Debug, PartialEq)]
+enum PaletteKind {
+    /// The whole section is a single block and there is no data.
+    Single(BlockId),
+    /// The data holds indexes into the palette, using the given number of bits per block.
+    Indirect(u8),
+    /// The data holds global block IDs.
+    Direct,
+}
+
+/// Index of a block in a section's data. `x`, `y` and `z` must be in `0..16`.
+fn section_index(x: i32, y: i32, z: i32) -> usize {
+    ((y * 16 + z) * 16 + x) as usize
+}
+
+/// Reads an entry from a packed long array. Entries don't span across longs.
+fn get_packed(data: &[i64], bits: u8, index: usize) -> u32 {
+    let per_long = 64 / bits as usize;
+    let Some(long) = data.get(index / per_long) else {
+        return 0;
+    };
+    let shift = (index % per_long) * bits as usize;
+    ((*long as u64 >> shift) & ((1u64 << bits) - 1)) as u32
+}
+
+/// Writes an entry to a packed long array. Entries don't span across longs.
+fn set_packed(data: &mut [i64], bits: u8, index: usize, value: u32) {
+    let per_long = 64 / bits as usize;
+    let shift = (index % per_long) * bits as usize;
+    let mask = ((1u64 << bits) - 1) << shift;
+    let long = &mut data[index / per_long];
+    *long = ((*long as u64 & !mask) | ((value as u64) << shift & mask)) as i64;
+}
+
+/// The number of longs needed to pack `entries` entries of `bits` bits each.
+fn packed_len(entries: usize, bits: u8) -> usize {
+    entries.div_ceil(64 / bits as usize)
+}
+
+/// Packs a list of values into longs.
+pub(crate) fn pack(values: impl ExactSizeIterator<Item = u32>, bits: u8) -> Vec<i64> {
+    let mut data = vec![0; packed_len(values.len(), bits)];
+    for (index, value) in values.enumerate() {
+        set_packed(&mut data, bits, index, value);
+    }
+    data
+}
+
+/// The smallest number of bits that can index a palette of this length, ignoring the indirect
+/// minimum.
+pub(crate) fn bits_for_palette(len: usize) -> u8 {
+    (usize::BITS - len.saturating_sub(1).leading_zeros()) as u8
+}
+
+impl BlockStates {
+    /// A section filled with a single block.
+    pub fn single(block: BlockId) -> Self {
+        BlockStates {
+            bits_per_block: 0,
+            non_air_blocks: if block.is_air() { 0 } else { 4096 },
+            data: vec![],
+            palette: vec![block.into()],
+        }
+    }
+
+    /// Builds block states from all 4096 blocks of a section, picking the smallest palette that
+    /// fits them.
+    pub fn from_blocks(blocks: &[BlockId]) -> Self {
+        let mut palette: Vec<BlockId> = Vec::new();
+        let mut lookup: HashMap<BlockId, u32> = HashMap::new();
+        let mut non_air_blocks = 0;
+        for &block in blocks {
+            if !block.is_air() {
+                non_air_blocks += 1;
+            }
+            lookup.entry(block).or_insert_with(|| {
+                palette.push(block);
+                (palette.len() - 1) as u32
+            });
+        }
+
+        if palette.len() <= 1 {
+            return BlockStates::single(palette.first().copied().unwrap_or(BlockId::AIR));
+        }
+
+        let bits = bits_for_palette(palette.len()).max(MIN_INDIRECT_BITS);
+        if bits > MAX_INDIRECT_BITS {
+            BlockStates {
+                bits_per_block: DIRECT_BITS,
+                non_air_blocks,
+                data: pack(blocks.iter().map(|block| block.0 as u32), DIRECT_BITS),
+                palette: vec![],
+            }
+        } else {
+            BlockStates {
+                bits_per_block: bits,
+                non_air_blocks,
+                data: pack(blocks.iter().map(|block| lookup[block]), bits),
+                palette: palette.into_iter().map(VarInt::from).collect(),
+            }
+        }
+    }
+
+    fn kind(&self) -> PaletteKind {
+        match (self.palette.len(), self.bits_per_block) {
+            // Sections without block states are all air
+            (0, 0) => PaletteKind::Single(BlockId::AIR),
+            (0, _) => PaletteKind::Direct,
+            (1, _) => PaletteKind::Single((&self.palette[0]).into()),
+            // Imported palettes store the minimum bits needed, but are packed with at least 4
+            (_, bits) => PaletteKind::Indirect(bits.max(MIN_INDIRECT_BITS)),
+        }
+    }
+
+    /// Gets the block at the given index in the section.
+    fn get(&self, index: usize) -> BlockId {
+        match self.kind() {
+            PaletteKind::Single(block) => block,
+            PaletteKind::Indirect(bits) => {
+                let palette_index = get_packed(&self.data, bits, index) as usize;
+                self.palette
+                    .get(palette_index)
+                    .map(BlockId::from)
+                    .unwrap_or_default()
+            }
+            PaletteKind::Direct => BlockId(get_packed(&self.data, DIRECT_BITS, index) as i32),
+        }
+    }
+
+    /// Every block in the section, in index order.
+    pub fn blocks(&self) -> Vec<BlockId> {
+        (0..SECTION_VOLUME).map(|index| self.get(index)).collect()
+    }
+
+    /// Sets the block at the given index in the section, returning the block that was there.
+    ///
+    /// The palette grows when it needs to, and is repacked as small as possible when the old
+    /// block no longer appears in the section.
+    fn set(&mut self, index: usize, block: BlockId) -> BlockId {
+        let old = self.get(index);
+        if old == block {
+            return old;
+        }
+
+        match self.kind() {
+            PaletteKind::Single(_) => {
+                let mut blocks = vec![old; SECTION_VOLUME];
+                blocks[index] = block;
+                *self = BlockStates::from_blocks(&blocks);
+                return old;
+            }
+            PaletteKind::Indirect(bits) => {
+                let position = self
+                    .palette
+                    .iter()
+                    .position(|id| BlockId::from(id) == block);
+                let palette_index = match position {
+                    Some(palette_index) => palette_index,
+                    None if self.palette.len() < 1 << bits => {
+                        self.palette.push(block.into());
+                        self.palette.len() - 1
+                    }
+                    None => {
+                        // The palette is full, so it has to grow
+                        let mut blocks = self.blocks();
+                        blocks[index] = block;
+                        *self = BlockStates::from_blocks(&blocks);
+                        return old;
+                    }
+                };
+                if self.data.len() < packed_len(SECTION_VOLUME, bits) {
+                    self.data.resize(packed_len(SECTION_VOLUME, bits), 0);
+                }
+                set_packed(&mut self.data, bits, index, palette_index as u32);
+            }
+            PaletteKind::Direct => {
+                if self.data.len() < packed_len(SECTION_VOLUME, DIRECT_BITS) {
+                    self.data.resize(packed_len(SECTION_VOLUME, DIRECT_BITS), 0);
+                }
+                set_packed(&mut self.data, DIRECT_BITS, index, block.0 as u32);
+            }
+        }
+
+        match (old.is_air(), block.is_air()) {
+            (true, false) => self.non_air_blocks += 1,
+            (false, true) => self.non_air_blocks = self.non_air_blocks.saturating_sub(1),
+            _ => {}
+        }
+
+        if !self.contains(old) {
+            self.optimise();
+        }
+        old
+    }
+
+    /// Checks if the block appears anywhere in the section.
+    fn contains(&self, block: BlockId) -> bool {
+        match self.kind() {
+            PaletteKind::Single(only) => only == block,
+            PaletteKind::Indirect(bits) => {
+                let Some(palette_index) = self
+                    .palette
+                    .iter()
+                    .position(|id| BlockId::from(id) == block)
+                else {
+                    return false;
+                };
+                (0..SECTION_VOLUME)
+                    .any(|index| get_packed(&self.data, bits, index) as usize == palette_index)
+            }
+            PaletteKind::Direct => (0..SECTION_VOLUME)
+                .any(|index| get_packed(&self.data, DIRECT_BITS, index) == block.0 as u32),
+        }
+    }
+
+    /// Repacks the section with the smallest palette that fits its blocks, and recounts the
+    /// non-air blocks.
+    pub fn optimise(&mut self) {
+        *self = BlockStates::from_blocks(&self.blocks());
+    }
+}
+
+impl Section {
+    /// An empty section of air at the given section y.
+    pub fn empty(y: i8) -> Self {
+        Section {
+            y,
+            block_states: BlockStates::single(BlockId::AIR),
+            biome_data: vec![],
+            biome_palette: vec!["minecraft:plains".to_string()],
+            block_light: vec![0; 2048],
+            sky_light: vec![255; 2048],
+        }
+    }
+}
+
+impl Heightmaps {
+    fn get_height(map: &[i64], x: i32, z: i32) -> i32 {
+        get_packed(map, HEIGHTMAP_BITS, (z * 16 + x) as usize) as i32
+    }
+
+    fn set_height(map: &mut [i64], x: i32, z: i32, height: i32) {
+        set_packed(map, HEIGHTMAP_BITS, (z * 16 + x) as usize, height as u32);
+    }
+
+    fn is_valid(&self) -> bool {
+        let len = packed_len(256, HEIGHTMAP_BITS);
+        self.motion_blocking.len() == len && self.world_surface.len() == len
+    }
+}
+
+impl Chunk {
+    /// An empty chunk of air, with every section of the dimension present.
+    pub fn new(x: i32, z: i32, dimension: String) -> Self {
+        let mut chunk = Chunk {
+            x,
+            z,
+            dimension,
+            sections: vec![],
+            heightmaps: Heightmaps::new(),
+        };
+        let min_section = chunk.min_y().div_euclid(16);
+        chunk.sections = (min_section..min_section + chunk.height() / 16)
+            .map(|y| Section::empty(y as i8))
+            .collect();
+        chunk.recalculate_heightmaps();
+        chunk
+    }
+
+    /// The chunk's dimension. Chunks of unknown dimensions are treated as overworld chunks.
+    pub fn dimension_kind(&self) -> Dimension {
+        Dimension::from_name(&self.dimension).unwrap_or_default()
+    }
+
+    /// The lowest block y in the chunk's dimension.
+    pub fn min_y(&self) -> i32 {
+        self.dimension_kind().min_y()
+    }
+
+    /// The height of the chunk's dimension, in blocks.
+    pub fn height(&self) -> i32 {
+        self.dimension_kind().height()
+    }
+
+    fn check_bounds(&self, x: i32, y: i32, z: i32) -> Result<(), WorldError> {
+        let min_y = self.min_y();
+        if !(0..16).contains(&x)
+            || !(0..16).contains(&z)
+            || !(min_y..min_y + self.height()).contains(&y)
+        {
+            return Err(WorldError::OutOfBounds(x, y, z));
+        }
+        Ok(())
+    }
+
+    /// Gets the block at the given position. `x` and `z` are relative to the chunk and must be in
+    /// `0..16`, `y` is the world height.
+    ///
+    /// Missing sections are treated as air.
+    pub fn get_block(&self, x: i32, y: i32, z: i32) -> Result<BlockId, WorldError> {
+        self.check_bounds(x, y, z)?;
+        let section_y = y.div_euclid(16);
+        Ok(self
+            .sections
+            .iter()
+            .find(|section| section.y as i32 == section_y)
+            .map(|section| {
+                section
+                    .block_states
+                    .get(section_index(x, y.rem_euclid(16), z))
+            })
+            .unwrap_or(BlockId::AIR))
+    }
+
+    /// Sets the block at the given position, returning the block that was there before. `x` and
+    /// `z` are relative to the chunk and must be in `0..16`, `y` is the world height.
+    ///
+    /// Missing sections are created, and the heightmaps are kept up to date.
+    pub fn set_block(
+        &mut self,
+        x: i32,
+        y: i32,
+        z: i32,
+        block: BlockId,
+    ) -> Result<BlockId, WorldError> {
+        self.check_bounds(x, y, z)?;
+        let section_y = y.div_euclid(16);
+        let section = match self
+            .sections
+            .iter()
+            .position(|section| section.y as i32 == section_y)
+        {
+            Some(position) => &mut self.sections[position],
+            None => {
+                let position = self
+                    .sections
+                    .iter()
+                    .position(|section| section.y as i32 > section_y)
+                    .unwrap_or(self.sections.len());
+                self.sections
+                    .insert(position, Section::empty(section_y as i8));
+                &mut self.sections[position]
+            }
+        };
+        let old = section
+            .block_states
+            .set(section_index(x, y.rem_euclid(16), z), block);
+        if old != block {
+            self.update_heightmaps(x, y, z, block);
+        }
+        Ok(old)
+    }
+
+    /// Updates the heightmaps of a column after a block in it changed.
+    fn update_heightmaps(&mut self, x: i32, y: i32, z: i32, block: BlockId) {
+        if !self.heightmaps.is_valid() {
+            self.recalculate_heightmaps();
+            return;
+        }
+        let height = y - self.min_y() + 1;
+        let current = Heightmaps::get_height(&self.heightmaps.world_surface, x, z);
+        let new_height = if !block.is_air() && height > current {
+            height
+        } else if block.is_air() && height == current {
+            self.column_height(x, z, y - 1)
+        } else {
+            return;
+        };
+        Heightmaps::set_height(&mut self.heightmaps.world_surface, x, z, new_height);
+        Heightmaps::set_height(&mut self.heightmaps.motion_blocking, x, z, new_height);
+    }
+
+    /// The heightmap value of a column, searching down from `top`: one above the highest non-air
+    /// block, relative to the bottom of the world.
+    fn column_height(&self, x: i32, z: i32, top: i32) -> i32 {
+        let min_y = self.min_y();
+        (min_y..=top)
+            .rev()
+            .find(|&y| !self.get_block(x, y, z).unwrap_or_default().is_air())
+            .map_or(0, |y| y - min_y + 1)
+    }
+
+    /// The y of the highest non-air block in a column, or `None` if it's all air. `x` and `z` are
+    /// relative to the chunk.
+    pub fn highest_block_y(&self, x: i32, z: i32) -> Option<i32> {
+        let height = if self.heightmaps.is_valid() {
+            Heightmaps::get_height(&self.heightmaps.motion_blocking, x, z)
+        } else {
+            self.column_height(x, z, self.min_y() + self.height() - 1)
+        };
+        (height > 0).then(|| self.min_y() + height - 1)
+    }
+
+    /// Recalculates the `WORLD_SURFACE` and `MOTION_BLOCKING` heightmaps from the blocks in the
+    /// chunk.
+    ///
+    /// There's no block property data yet, so `MOTION_BLOCKING` treats every non-air block as
+    /// motion blocking.
+    pub fn recalculate_heightmaps(&mut self) {
+        let top = self.min_y() + self.height() - 1;
+        let mut map = vec![0; packed_len(256, HEIGHTMAP_BITS)];
+        for z in 0..16 {
+            for x in 0..16 {
+                Heightmaps::set_height(&mut map, x, z, self.column_height(x, z, top));
+            }
+        }
+        self.heightmaps = Heightmaps {
+            motion_blocking: map.clone(),
+            world_surface: map,
+        };
+    }
+}
+
+impl World {
+    /// Gets the block at the given world position.
+    pub async fn get_block_at(
+        &self,
+        x: i32,
+        y: i32,
+        z: i32,
+        dimension: &str,
+    ) -> Result<BlockId, WorldError> {
+        let chunk = self
+            .load_chunk(x.div_euclid(16), z.div_euclid(16), dimension)
+            .await?;
+        chunk.get_block(x.rem_euclid(16), y, z.rem_euclid(16))
+    }
+
+    /// Sets the block at the given world position and saves the chunk, updating the light around
+    /// the block if it changed how light passes through it.
+    ///
+    /// Edits are serialised so that concurrent edits to the same chunk don't overwrite each
+    /// other.
+    pub async fn set_block_at(
+        &self,
+        x: i32,
+        y: i32,
+        z: i32,
+        dimension: &str,
+        block: BlockId,
+    ) -> Result<BlockChange, WorldError> {
+        let _guard = self.edit_lock.lock().await;
+        let mut chunk = self
+            .load_chunk(x.div_euclid(16), z.div_euclid(16), dimension)
+            .await?;
+        let old = chunk.set_block(x.rem_euclid(16), y, z.rem_euclid(16), block)?;
+        if old == block {
+            return Ok(BlockChange {
+                old,
+                relit_chunks: Vec::new(),
+            });
+        }
+        self.save_chunk(chunk).await?;
+        let relit_chunks = if LightProperties::of(old) == LightProperties::of(block) {
+            Vec::new()
+        } else {
+            self.update_light(x, y, z, dimension).await?
+        };
+        Ok(BlockChange { old, relit_chunks })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STONE: BlockId = BlockId(1);
+
+    #[test]
+    fn test_packing_round_trip() {
+        for bits in [4, 5, 8, 9, 15] {
+            let values = (0..SECTION_VOLUME as u32).map(|i| i % (1 << bits));
+            let data = pack(values.clone(), bits);
+            assert_eq!(data.len(), packed_len(SECTION_VOLUME, bits));
+            for (index, value) in values.enumerate() {
+                assert_eq!(get_packed(&data, bits, index), value);
+            }
+        }
+    }
+
+    #[test]
+    fn test_single_to_indirect_and_back() {
+        let mut states = BlockStates::single(BlockId::AIR);
+        assert_eq!(states.set(0, STONE), BlockId::AIR);
+        assert_eq!(states.kind(), PaletteKind::Indirect(4));
+        assert_eq!(states.non_air_blocks, 1);
+        assert_eq!(states.get(0), STONE);
+        assert_eq!(states.get(1), BlockId::AIR);
+
+        assert_eq!(states.set(0, BlockId::AIR), STONE);
+        assert_eq!(states.kind(), PaletteKind::Single(BlockId::AIR));
+        assert_eq!(states.non_air_blocks, 0);
+    }
+
+    #[test]
+    fn test_palette_grows_to_direct_and_shrinks() {
+        let mut states = BlockStates::single(BlockId::AIR);
+        for i in 0..300 {
+            states.set(i, BlockId(i as i32 + 1));
+        }
+        assert_eq!(states.kind(), PaletteKind::Direct);
+        assert_eq!(states.non_air_blocks, 300);
+        for i in 0..300 {
+            assert_eq!(states.get(i), BlockId(i as i32 + 1));
+        }
+
+        for i in 16..300 {
+            states.set(i, BlockId::AIR);
+        }
+        assert_eq!(states.kind(), PaletteKind::Indirect(5));
+        assert_eq!(states.non_air_blocks, 16);
+        for i in 0..16 {
+            assert_eq!(states.get(i), BlockId(i as i32 + 1));
+        }
+        assert_eq!(states.get(16), BlockId::AIR);
+    }
+
+    #[test]
+    fn test_chunk_blocks_and_heightmaps() {
+        let mut chunk = Chunk::new(0, 0, "overworld".to_string());
+        assert_eq!(chunk.sections.len(), 24);
+        assert_eq!(chunk.get_block(3, -64, 5).unwrap(), BlockId::AIR);
+
+        chunk.set_block(3, 70, 5, STONE).unwrap();
+        chunk.set_block(3, 10, 5, STONE).unwrap();
+        assert_eq!(chunk.get_block(3, 70, 5).unwrap(), STONE);
+        let height = |chunk: &Chunk| Heightmaps::get_height(&chunk.heightmaps.world_surface, 3, 5);
+        assert_eq!(height(&chunk), 70 + 64 + 1);
+
+        chunk.set_block(3, 70, 5, BlockId::AIR).unwrap();
+        assert_eq!(height(&chunk), 10 + 64 + 1);
+        chunk.set_block(3, 10, 5, BlockId::AIR).unwrap();
+        assert_eq!(height(&chunk), 0);
+
+        assert!(chunk.set_block(16, 0, 0, STONE).is_err());
+        assert!(chunk.get_block(0, 320, 0).is_err());
+    }
+
+    #[test]
+    fn test_missing_section_is_created() {
+        let mut chunk = Chunk::new(0, 0, "overworld".to_string());
+        chunk.sections.retain(|section| section.y != 2);
+        assert_eq!(chunk.get_block(0, 40, 0).unwrap(), BlockId::AIR);
+        chunk.set_block(0, 40, 0, STONE).unwrap();
+        assert_eq!(chunk.get_block(0, 40, 0).unwrap(), STONE);
+        assert!(chunk
+            .sections
+            .windows(2)
+            .all(|pair| pair[0].y + 1 == pair[1].y));
+    }
+}
@@ -18,6 +18,8 @@ pub enum WorldError {
     InvalidCacheSize(String),
     #[error("Invalid Import Path: {0}")]
     InvalidImportPath(String),
+    #[error("Invalid Export Path: {0}")]
+    InvalidExportPath(String),
     #[error("No region files")]
     NoRegionFiles,
     #[error("Unable to obtain permission to access file/folder: {0}")]
@@ -34,10 +36,24 @@ pub enum WorldError {
     ChunkNotFound,
     #[error("Anvil Decode Error: {0}")]
     AnvilDecodeError(AnvilError),
+    #[error("Anvil Encode Error: {0}")]
+    AnvilEncodeError(AnvilError),
+    #[error("Invalid block mappings: {0}")]
+    InvalidBlockMappings(String),
+    #[error("Invalid item mappings: {0}")]
+    InvalidItemMappings(String),
     #[error("Missing block mapping: {0}")]
     MissingBlockMapping(Palette),
     #[error("Invalid memory map size: {0}")]
     InvalidMapSize(u64),
+    #[error("Block position out of bounds: {0}, {1}, {2}")]
+    OutOfBounds(i32, i32, i32),
+    #[error("Invalid player data: {0}")]
+    InvalidPlayerData(String),
+    #[error("Invalid level data: {0}")]
+    InvalidLevelData(String),
+    #[error("Invalid chunk generator: {0}")]
+    InvalidGenerator(String),
 }
 
 impl From<std::io::Error> for WorldError {
@@ -0,0 +1,122 @@
+use crate::chunk_format::Chunk;
+use crate::db_functions::{load_chunk_batch_internal, parse_key, CHUNKS_TABLE};
+use crate::errors::WorldError;
+use crate::vanilla_chunk_format::VanillaChunk;
+use crate::World;
+use ferrumc_anvil::errors::AnvilError;
+use ferrumc_anvil::writer::RegionWriter;
+use ferrumc_core::dimensions::dimension::Dimension;
+use ferrumc_general_purpose::paths::BetterPathExt;
+use indicatif::ProgressBar;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::{error, info, warn};
+
+/// Writes a region's chunks to a region file, skipping any that are too large to fit.
+fn write_region(
+    chunks: Vec<Chunk>,
+    file_path: PathBuf,
+    timestamp: u32,
+    progress_bar: &ProgressBar,
+) -> Result<(), WorldError> {
+    let mut writer = RegionWriter::new();
+    for chunk in chunks {
+        let bytes = VanillaChunk::from_custom_format(&chunk).to_bytes();
+        match writer.add_chunk(chunk.x as u32, chunk.z as u32, &bytes, timestamp) {
+            Ok(()) => progress_bar.inc(1),
+            Err(AnvilError::ChunkTooLarge(..)) => {
+                error!("Chunk {}, {} is too large to export", chunk.x, chunk.z);
+            }
+            Err(e) => return Err(WorldError::AnvilEncodeError(e)),
+        }
+    }
+    if !writer.is_empty() {
+        writer
+            .write(file_path)
+            .map_err(WorldError::AnvilEncodeError)?;
+    }
+    Ok(())
+}
+
+impl World {
+    /// Exports every chunk to vanilla region files, putting each dimension's in the folder
+    /// vanilla keeps them in. Region files that are already there are replaced.
+    ///
+    /// Only the chunks are exported, so tools that read region files can open the result but
+    /// vanilla needs a `level.dat` to go with it.
+    pub async fn export(&self, export_dir: PathBuf) -> Result<(), WorldError> {
+        if export_dir.is_file() {
+            error!("Export path is a file: {}", export_dir.better_display());
+            return Err(WorldError::InvalidExportPath(export_dir.better_display()));
+        }
+        // Chunks that are only in the cache have to be saved to be found
+        self.sync().await?;
+
+        let mut regions: HashMap<(Dimension, i32, i32), Vec<(i32, i32)>> = HashMap::new();
+        let mut chunk_count = 0;
+        for key in self.storage_backend.keys(CHUNKS_TABLE.to_string()).await? {
+            let Some((dimension, x, z)) = parse_key(key) else {
+                warn!("Skipping a chunk of an unknown dimension");
+                continue;
+            };
+            regions
+                .entry((dimension, x >> 5, z >> 5))
+                .or_default()
+                .push((x, z));
+            chunk_count += 1;
+        }
+
+        info!("Exporting {} chunks...", chunk_count);
+        let progress_bar = ProgressBar::new(chunk_count);
+        let start = std::time::Instant::now();
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |time| time.as_secs() as u32);
+        for ((dimension, region_x, region_z), coords) in regions {
+            let regions_dir = export_dir.join(dimension.region_dir());
+            std::fs::create_dir_all(&regions_dir)?;
+            let chunks = self.load_region_chunks(dimension, coords).await?;
+            let file_path = regions_dir.join(format!("r.{}.{}.mca", region_x, region_z));
+            let progress_bar = progress_bar.clone();
+            tokio::task::spawn_blocking(move || {
+                write_region(chunks, file_path, timestamp, &progress_bar)
+            })
+            .await
+            .expect("Failed to run tokio task")?;
+        }
+        progress_bar.finish();
+        info!(
+            "Exported {} chunks to {} in {:?}",
+            progress_bar.position(),
+            export_dir.better_display(),
+            start.elapsed()
+        );
+        Ok(())
+    }
+
+    /// Loads the saved chunks of a region, logging the ones that can't be read.
+    async fn load_region_chunks(
+        &self,
+        dimension: Dimension,
+        coords: Vec<(i32, i32)>,
+    ) -> Result<Vec<Chunk>, WorldError> {
+        let chunks = load_chunk_batch_internal(
+            self,
+            coords
+                .iter()
+                .map(|&(x, z)| (x, z, dimension.name()))
+                .collect(),
+        )
+        .await?;
+        Ok(coords
+            .into_iter()
+            .zip(chunks)
+            .filter_map(|((x, z), chunk)| {
+                chunk
+                    .inspect_err(|e| error!("Could not load chunk {}, {}: {}", x, z, e))
+                    .ok()
+            })
+            .collect())
+    }
+}
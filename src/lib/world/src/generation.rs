@@ -0,0 +1,530 @@
+//! Generating chunks that haven't been imported or generated before. Each dimension has its own
+//! [ChunkGenerator], which is run the first time one of its chunks is loaded.
+
+use crate::block_id::BlockId;
+use crate::block_state::BlockRegistry;
+use crate::chunk_format::{BlockStates, Chunk, Section};
+use crate::edits::{bits_for_palette, pack};
+use crate::errors::WorldError;
+use crate::World;
+use ferrumc_config::server_config::GeneratorConfig;
+use ferrumc_config::statics::get_global_config;
+use ferrumc_core::dimensions::dimension::Dimension;
+use std::collections::HashMap;
+use std::hash::Hasher;
+use std::sync::Arc;
+use tracing::debug;
+
+/// The y of the top of the sea in noise terrain.
+pub const SEA_LEVEL: i32 = 62;
+/// The number of blocks in a section.
+const SECTION_VOLUME: usize = 16 * 16 * 16;
+
+/// Makes the blocks of chunks that don't exist yet.
+pub trait ChunkGenerator: Send + Sync {
+    /// Generates the chunk at the given chunk coordinates. It doesn't have to be lit; the world
+    /// lights it afterwards.
+    ///
+    /// The same coordinates should always give the same chunk, since a chunk can be generated
+    /// more than once if it's loaded twice at the same time.
+    fn generate(&self, x: i32, z: i32, dimension: Dimension) -> Chunk;
+}
+
+/// Empty chunks with nothing but air.
+pub struct VoidGenerator;
+
+impl ChunkGenerator for VoidGenerator {
+    fn generate(&self, x: i32, z: i32, dimension: Dimension) -> Chunk {
+        Chunk::new(x, z, dimension.name().to_string())
+    }
+}
+
+/// The same layers of blocks everywhere, like vanilla's superflat worlds.
+pub struct SuperflatGenerator {
+    /// The block at each y from the bottom of the dimension up.
+    layers: Vec<BlockId>,
+}
+
+impl SuperflatGenerator {
+    /// A generator with the given block at each y from the bottom of the dimension up.
+    pub fn new(layers: Vec<BlockId>) -> Self {
+        Self { layers }
+    }
+
+    /// A generator from vanilla-style layers like `3*minecraft:dirt`, from the bottom up.
+    pub fn from_layers(layers: &[String]) -> Result<Self, WorldError> {
+        let mut blocks = Vec::new();
+        for layer in layers {
+            let (count, name) = parse_layer(layer)?;
+            let block = lookup_block(name)?;
+            blocks.extend(std::iter::repeat_n(block, count));
+        }
+        Ok(Self::new(blocks))
+    }
+}
+
+/// Splits a superflat layer like `3*minecraft:dirt` into how many layers there are and the block.
+fn parse_layer(layer: &str) -> Result<(usize, &str), WorldError> {
+    let Some((count, name)) = layer.split_once('*') else {
+        return Ok((1, layer.trim()));
+    };
+    let count = count
+        .trim()
+        .parse()
+        .map_err(|_| WorldError::InvalidGenerator(format!("Invalid layer count: {}", layer)))?;
+    Ok((count, name.trim()))
+}
+
+/// The ID of a block's default state.
+fn lookup_block(name: &str) -> Result<BlockId, WorldError> {
+    let name = if name.contains(':') {
+        name.to_string()
+    } else {
+        format!("minecraft:{}", name)
+    };
+    BlockRegistry::global()
+        .default_state(&name)
+        .map(|state| state.id)
+        .ok_or_else(|| WorldError::InvalidGenerator(format!("Unknown block: {}", name)))
+}
+
+impl ChunkGenerator for SuperflatGenerator {
+    fn generate(&self, x: i32, z: i32, dimension: Dimension) -> Chunk {
+        build_chunk(x, z, dimension, |_, y, _| {
+            let layer = y - dimension.min_y();
+            (
+                self.layers
+                    .get(layer as usize)
+                    .copied()
+                    .unwrap_or(BlockId::AIR),
+                "minecraft:plains",
+            )
+        })
+    }
+}
+
+/// The blocks noise terrain is made of.
+#[derive(Clone, Copy, Debug)]
+pub struct TerrainBlocks {
+    pub bedrock: BlockId,
+    pub stone: BlockId,
+    pub dirt: BlockId,
+    pub grass: BlockId,
+    pub sand: BlockId,
+    pub snow: BlockId,
+    pub water: BlockId,
+}
+
+impl TerrainBlocks {
+    /// The vanilla blocks, from the block registry.
+    pub fn vanilla() -> Result<Self, WorldError> {
+        Ok(Self {
+            bedrock: lookup_block("minecraft:bedrock")?,
+            stone: lookup_block("minecraft:stone")?,
+            dirt: lookup_block("minecraft:dirt")?,
+            grass: lookup_block("minecraft:grass_block")?,
+            sand: lookup_block("minecraft:sand")?,
+            snow: lookup_block("minecraft:snow_block")?,
+            water: lookup_block("minecraft:water")?,
+        })
+    }
+}
+
+/// The biomes of noise terrain.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Biome {
+    Ocean,
+    Beach,
+    Plains,
+    Desert,
+    SnowyPlains,
+}
+
+impl Biome {
+    fn name(self) -> &'static str {
+        match self {
+            Biome::Ocean => "minecraft:ocean",
+            Biome::Beach => "minecraft:beach",
+            Biome::Plains => "minecraft:plains",
+            Biome::Desert => "minecraft:desert",
+            Biome::SnowyPlains => "minecraft:snowy_plains",
+        }
+    }
+
+    /// The biome of a column with its highest block at `height` and the given temperature,
+    /// from -1 to 1.
+    fn of(height: i32, temperature: f64) -> Self {
+        if height < SEA_LEVEL - 1 {
+            Biome::Ocean
+        } else if height <= SEA_LEVEL + 1 {
+            Biome::Beach
+        } else if temperature > 0.3 {
+            Biome::Desert
+        } else if temperature < -0.3 {
+            Biome::SnowyPlains
+        } else {
+            Biome::Plains
+        }
+    }
+}
+
+/// Rolling hills and seas from a heightmap made of noise, with a few biomes depending on the
+/// height and a second, slower changing temperature noise.
+pub struct NoiseGenerator {
+    seed: i64,
+    blocks: TerrainBlocks,
+}
+
+impl NoiseGenerator {
+    pub fn new(seed: i64, blocks: TerrainBlocks) -> Self {
+        Self { seed, blocks }
+    }
+
+    /// The y of the highest block of a column.
+    fn height(&self, x: i32, z: i32) -> i32 {
+        let noise = fractal_noise(self.seed, x as f64 / 128.0, z as f64 / 128.0, 4);
+        SEA_LEVEL + 2 + (noise * 24.0).round() as i32
+    }
+
+    /// How hot a column is, from -1 to 1.
+    fn temperature(&self, x: i32, z: i32) -> f64 {
+        fractal_noise(
+            self.seed.wrapping_add(1),
+            x as f64 / 512.0,
+            z as f64 / 512.0,
+            2,
+        )
+    }
+
+    fn column(&self, x: i32, z: i32) -> (i32, Biome) {
+        let height = self.height(x, z);
+        (height, Biome::of(height, self.temperature(x, z)))
+    }
+
+    fn block(&self, y: i32, min_y: i32, height: i32, biome: Biome) -> BlockId {
+        let blocks = &self.blocks;
+        let sandy = matches!(biome, Biome::Ocean | Biome::Beach | Biome::Desert);
+        if y == min_y {
+            blocks.bedrock
+        } else if y <= height - 4 {
+            blocks.stone
+        } else if y < height {
+            if sandy {
+                blocks.sand
+            } else {
+                blocks.dirt
+            }
+        } else if y == height {
+            match biome {
+                _ if sandy => blocks.sand,
+                Biome::SnowyPlains => blocks.snow,
+                _ => blocks.grass,
+            }
+        } else if y <= SEA_LEVEL {
+            blocks.water
+        } else {
+            BlockId::AIR
+        }
+    }
+}
+
+impl ChunkGenerator for NoiseGenerator {
+    fn generate(&self, x: i32, z: i32, dimension: Dimension) -> Chunk {
+        let min_y = dimension.min_y();
+        let max_height = min_y + dimension.height() - 1;
+        let columns: Vec<(i32, Biome)> = (0..256)
+            .map(|index| {
+                let (height, biome) = self.column(x * 16 + index % 16, z * 16 + index / 16);
+                (height.clamp(min_y + 1, max_height), biome)
+            })
+            .collect();
+        build_chunk(x, z, dimension, |column_x, y, column_z| {
+            let (height, biome) = columns[(column_z * 16 + column_x) as usize];
+            (self.block(y, min_y, height, biome), biome.name())
+        })
+    }
+}
+
+/// Builds a chunk from the block and biome at each position. Positions are relative to the
+/// chunk, apart from `y`. Biomes are taken from the middle of each 4x4x4 cell, like vanilla's.
+fn build_chunk<'a>(
+    x: i32,
+    z: i32,
+    dimension: Dimension,
+    block_at: impl Fn(i32, i32, i32) -> (BlockId, &'a str),
+) -> Chunk {
+    let mut chunk = Chunk::new(x, z, dimension.name().to_string());
+    for section in &mut chunk.sections {
+        let min_y = i32::from(section.y) * 16;
+        let mut blocks = Vec::with_capacity(SECTION_VOLUME);
+        for y in 0..16 {
+            for z in 0..16 {
+                for x in 0..16 {
+                    blocks.push(block_at(x, min_y + y, z).0);
+                }
+            }
+        }
+
+        let mut biome_palette: Vec<&str> = Vec::new();
+        let mut biomes = Vec::with_capacity(64);
+        for y in 0..4 {
+            for z in 0..4 {
+                for x in 0..4 {
+                    let biome = block_at(x * 4 + 2, min_y + y * 4 + 2, z * 4 + 2).1;
+                    let index = biome_palette
+                        .iter()
+                        .position(|&entry| entry == biome)
+                        .unwrap_or_else(|| {
+                            biome_palette.push(biome);
+                            biome_palette.len() - 1
+                        });
+                    biomes.push(index as u32);
+                }
+            }
+        }
+
+        *section = Section {
+            block_states: BlockStates::from_blocks(&blocks),
+            biome_data: match bits_for_palette(biome_palette.len()) {
+                0 => Vec::new(),
+                bits => pack(biomes.into_iter(), bits),
+            },
+            biome_palette: biome_palette.into_iter().map(str::to_string).collect(),
+            ..Section::empty(section.y)
+        };
+    }
+    chunk.recalculate_heightmaps();
+    chunk
+}
+
+/// A random value from -1 to 1 for a point of the noise lattice.
+fn lattice_value(seed: i64, x: i64, z: i64) -> f64 {
+    let mut hasher = wyhash::WyHash::with_seed(seed as u64);
+    hasher.write_i64(x);
+    hasher.write_i64(z);
+    hasher.finish() as f64 / u64::MAX as f64 * 2.0 - 1.0
+}
+
+/// Smoothly interpolated value noise from -1 to 1, which changes over about one unit.
+fn value_noise(seed: i64, x: f64, z: f64) -> f64 {
+    let (x0, z0) = (x.floor(), z.floor());
+    let smooth = |t: f64| t * t * (3.0 - 2.0 * t);
+    let (tx, tz) = (smooth(x - x0), smooth(z - z0));
+    let (x0, z0) = (x0 as i64, z0 as i64);
+    let lerp = |a: f64, b: f64, t: f64| a + (b - a) * t;
+    lerp(
+        lerp(
+            lattice_value(seed, x0, z0),
+            lattice_value(seed, x0 + 1, z0),
+            tx,
+        ),
+        lerp(
+            lattice_value(seed, x0, z0 + 1),
+            lattice_value(seed, x0 + 1, z0 + 1),
+            tx,
+        ),
+        tz,
+    )
+}
+
+/// Value noise with finer, fainter octaves added on top, from -1 to 1.
+fn fractal_noise(seed: i64, x: f64, z: f64, octaves: u32) -> f64 {
+    let (mut total, mut max) = (0.0, 0.0);
+    let (mut amplitude, mut frequency) = (1.0, 1.0);
+    for octave in 0..octaves {
+        total += value_noise(
+            seed.wrapping_add(i64::from(octave)),
+            x * frequency,
+            z * frequency,
+        ) * amplitude;
+        max += amplitude;
+        amplitude /= 2.0;
+        frequency *= 2.0;
+    }
+    total / max
+}
+
+/// Builds the generator described by a dimension's config.
+pub fn generator_from_config(
+    config: &GeneratorConfig,
+    seed: i64,
+) -> Result<Arc<dyn ChunkGenerator>, WorldError> {
+    Ok(match config.generator.to_lowercase().as_str() {
+        "void" => Arc::new(VoidGenerator),
+        "superflat" => Arc::new(SuperflatGenerator::from_layers(&config.superflat_layers)?),
+        "noise" => Arc::new(NoiseGenerator::new(seed, TerrainBlocks::vanilla()?)),
+        generator => {
+            return Err(WorldError::InvalidGenerator(format!(
+                "Unknown generator: {}",
+                generator
+            )))
+        }
+    })
+}
+
+impl World {
+    /// Sets up each dimension's generator from the config, using the world's seed.
+    pub(crate) fn load_generators(&self) -> Result<(), WorldError> {
+        let config = &get_global_config().generation;
+        let seed = self.level_info().seed;
+        let mut generators = HashMap::new();
+        for dimension in Dimension::ALL {
+            let generator_config = match dimension {
+                Dimension::Overworld => &config.overworld,
+                Dimension::Nether => &config.the_nether,
+                Dimension::End => &config.the_end,
+            };
+            generators.insert(dimension, generator_from_config(generator_config, seed)?);
+        }
+        *self.generators.write() = generators;
+        Ok(())
+    }
+
+    /// Replaces the generator of a dimension. Chunks that already exist aren't changed.
+    pub fn set_generator(&self, dimension: Dimension, generator: Arc<dyn ChunkGenerator>) {
+        self.generators.write().insert(dimension, generator);
+    }
+
+    /// Generates and lights a chunk, and saves it. Dimensions without a generator don't have
+    /// any chunks that don't exist already.
+    pub(crate) async fn generate_chunk(
+        &self,
+        x: i32,
+        z: i32,
+        dimension: &str,
+    ) -> Result<Chunk, WorldError> {
+        let dimension = Dimension::from_name(dimension).ok_or(WorldError::ChunkNotFound)?;
+        let generator = self
+            .generators
+            .read()
+            .get(&dimension)
+            .cloned()
+            .ok_or(WorldError::ChunkNotFound)?;
+        debug!("Generating chunk {}, {} in the {}", x, z, dimension);
+        let chunk = tokio::task::spawn_blocking(move || {
+            let mut chunk = generator.generate(x, z, dimension);
+            chunk.calculate_light();
+            chunk
+        })
+        .await
+        .map_err(|e| WorldError::InvalidGenerator(e.to_string()))?;
+        self.save_chunk(chunk.clone()).await?;
+        Ok(chunk)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BLOCKS: TerrainBlocks = TerrainBlocks {
+        bedrock: BlockId(1),
+        stone: BlockId(2),
+        dirt: BlockId(3),
+        grass: BlockId(4),
+        sand: BlockId(5),
+        snow: BlockId(6),
+        water: BlockId(7),
+    };
+
+    #[test]
+    fn test_parse_layer() {
+        assert_eq!(
+            parse_layer("minecraft:stone").unwrap(),
+            (1, "minecraft:stone")
+        );
+        assert_eq!(
+            parse_layer("3*minecraft:dirt").unwrap(),
+            (3, "minecraft:dirt")
+        );
+        assert!(parse_layer("lots*minecraft:dirt").is_err());
+    }
+
+    #[test]
+    fn test_superflat() {
+        let generator = SuperflatGenerator::new(vec![BLOCKS.bedrock, BLOCKS.dirt, BLOCKS.grass]);
+        let chunk = generator.generate(3, -2, Dimension::Overworld);
+        assert_eq!(chunk.get_block(5, -64, 5).unwrap(), BLOCKS.bedrock);
+        assert_eq!(chunk.get_block(5, -62, 5).unwrap(), BLOCKS.grass);
+        assert_eq!(chunk.get_block(5, -61, 5).unwrap(), BlockId::AIR);
+        assert_eq!(chunk.highest_block_y(5, 5), Some(-62));
+        assert_eq!(chunk.sections[0].biome_palette, vec!["minecraft:plains"]);
+    }
+
+    #[test]
+    fn test_noise() {
+        let generator = NoiseGenerator::new(1234, BLOCKS);
+        let chunk = generator.generate(0, 0, Dimension::Overworld);
+        let blocks = |chunk: &Chunk| {
+            chunk
+                .sections
+                .iter()
+                .map(|section| section.block_states.blocks())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            blocks(&generator.generate(0, 0, Dimension::Overworld)),
+            blocks(&chunk)
+        );
+        for (x, z) in [(0, 0), (7, 12), (15, 15)] {
+            let (height, _) = generator.column(x, z);
+            assert_eq!(chunk.highest_block_y(x, z), Some(height.max(SEA_LEVEL)));
+            assert_eq!(chunk.get_block(x, -64, z).unwrap(), BLOCKS.bedrock);
+            assert_eq!(chunk.get_block(x, height - 10, z).unwrap(), BLOCKS.stone);
+        }
+        assert!(chunk
+            .sections
+            .iter()
+            .all(|section| !section.biome_palette.is_empty()));
+    }
+
+    #[test]
+    fn test_vanilla_blocks() {
+        // Generated grass isn't snowy
+        let vanilla = TerrainBlocks::vanilla().unwrap();
+        assert_eq!(vanilla.grass, BlockId(9));
+        assert_eq!(lookup_block("grass_block").unwrap(), BlockId(9));
+        assert_eq!(vanilla.water, BlockId(80));
+
+        let chunk = NoiseGenerator::new(1234, vanilla).generate(0, 0, Dimension::Overworld);
+        let blocks = chunk
+            .sections
+            .iter()
+            .flat_map(|section| section.block_states.blocks())
+            .collect::<Vec<_>>();
+        assert!(!blocks.contains(&BlockId(8)));
+    }
+
+    #[test]
+    fn test_fractal_noise_range() {
+        for i in 0..1000 {
+            let value = fractal_noise(42, i as f64 * 0.37, i as f64 * -0.91, 4);
+            assert!((-1.0..=1.0).contains(&value));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_generate_fresh_world() {
+        let world = World::in_memory().await;
+        world.set_generator(
+            Dimension::Overworld,
+            Arc::new(SuperflatGenerator::new(vec![BLOCKS.bedrock, BLOCKS.stone])),
+        );
+        assert!(!world.chunk_exists(2, -3, "overworld").await.unwrap());
+        let chunk = world.load_chunk(2, -3, "overworld").await.unwrap();
+        assert_eq!(chunk.get_block(0, -63, 0).unwrap(), BLOCKS.stone);
+        assert!(world.chunk_exists(2, -3, "overworld").await.unwrap());
+
+        let loaded = world
+            .load_chunk_batch(vec![
+                (2, -3, "overworld"),
+                (5, 5, "overworld"),
+                (0, 0, "the_end"),
+            ])
+            .await;
+        assert!(loaded[0].1.is_ok());
+        assert!(loaded[1].1.is_ok());
+        // The end has no generator, so its chunks don't exist
+        assert!(matches!(loaded[2].1, Err(WorldError::ChunkNotFound)));
+    }
+}
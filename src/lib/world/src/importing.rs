@@ -3,15 +3,30 @@ use crate::errors::WorldError;
 use crate::vanilla_chunk_format::VanillaChunk;
 use crate::World;
 use ferrumc_anvil::load_anvil_file;
+use ferrumc_core::dimensions::dimension::Dimension;
 use ferrumc_general_purpose::paths::BetterPathExt;
+use flate2::read::GzDecoder;
 use indicatif::ProgressBar;
 use rayon::prelude::*;
-use std::path::PathBuf;
+use std::borrow::Cow;
+use std::io::Read;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::AtomicU64;
 use std::sync::Arc;
 use tokio::task::JoinSet;
 use tracing::{error, info};
 
+/// Reads a vanilla NBT file such as `level.dat`, unzipping it if it's gzipped like they usually
+/// are.
+pub(crate) fn read_nbt_file(bytes: &[u8]) -> Result<Cow<'_, [u8]>, WorldError> {
+    if !bytes.starts_with(&[0x1f, 0x8b]) {
+        return Ok(Cow::Borrowed(bytes));
+    }
+    let mut data = Vec::new();
+    GzDecoder::new(bytes).read_to_end(&mut data)?;
+    Ok(Cow::Owned(data))
+}
+
 /// This function is used to check if the import path is valid. It checks if the path exists, if it
 /// is a file, if the region folder exists, if the region folder is a file, and if the region folder
 /// is empty.
@@ -47,9 +62,8 @@ fn check_paths_validity(import_dir: PathBuf) -> Result<(), WorldError> {
 }
 
 impl World {
-    fn get_chunk_count(&self, import_dir: PathBuf) -> Result<u64, WorldError> {
-        info!("Counting chunks in import directory...");
-        let regions_dir = import_dir.join("region").read_dir()?;
+    fn get_chunk_count(&self, regions_dir: &Path) -> Result<u64, WorldError> {
+        let regions_dir = regions_dir.read_dir()?;
         let chunk_count = AtomicU64::new(0);
         regions_dir
             .into_iter()
@@ -84,19 +98,70 @@ impl World {
         Ok(chunk_count.load(std::sync::atomic::Ordering::Relaxed))
     }
 
+    /// Imports a vanilla world: the chunks of every dimension it has a region folder for, then
+    /// its level data and players.
     pub async fn import(&mut self, import_dir: PathBuf, _: PathBuf) -> Result<(), WorldError> {
         // Check if the import path is valid. We can assume the database path is valid since we
         // checked it in the config validity check.
         check_paths_validity(import_dir.clone())?;
-        let regions_dir = import_dir.join("region").read_dir()?;
-        let progress_bar = Arc::new(ProgressBar::new(self.get_chunk_count(import_dir)?));
+        let dimensions = Dimension::ALL
+            .into_iter()
+            .map(|dimension| (dimension, import_dir.join(dimension.region_dir())))
+            .filter(|(dimension, regions_dir)| {
+                let exists = regions_dir.is_dir();
+                if !exists {
+                    info!("No region folder for {}", dimension);
+                }
+                exists
+            })
+            .collect::<Vec<_>>();
+        if dimensions.is_empty() {
+            error!(
+                "Import path has no region folders: {}",
+                import_dir.better_display()
+            );
+            return Err(WorldError::NoRegionFiles);
+        }
+
+        info!("Counting chunks in import directory...");
+        let mut chunk_count = 0;
+        for (_, regions_dir) in &dimensions {
+            chunk_count += self.get_chunk_count(regions_dir)?;
+        }
+        let progress_bar = Arc::new(ProgressBar::new(chunk_count));
         info!("Importing chunks from import directory...");
         let start = std::time::Instant::now();
-        let mut task_set = JoinSet::new();
         self.storage_backend
             .create_table("chunks".to_string())
             .await?;
-        for region_file in regions_dir {
+        for (dimension, regions_dir) in dimensions {
+            self.import_dimension(&regions_dir, dimension, &progress_bar)
+                .await?;
+        }
+        self.sync().await?;
+        progress_bar.clone().finish();
+        info!(
+            "Imported {} chunks in {:?}",
+            progress_bar.clone().position(),
+            start.elapsed()
+        );
+
+        self.import_level(&import_dir).await?;
+        self.import_player_data(&import_dir).await?;
+
+        self.storage_backend.flush().await?;
+        Ok(())
+    }
+
+    /// Imports the chunks in a dimension's region folder.
+    async fn import_dimension(
+        &self,
+        regions_dir: &Path,
+        dimension: Dimension,
+        progress_bar: &Arc<ProgressBar>,
+    ) -> Result<(), WorldError> {
+        let mut task_set = JoinSet::new();
+        for region_file in regions_dir.read_dir()? {
             match region_file {
                 Ok(dir_entry) => {
                     if dir_entry.path().is_dir() {
@@ -123,7 +188,11 @@ impl World {
                                             let cloned_progress_bar = progress_bar.clone();
                                             let self_clone = self.clone();
                                             task_set.spawn(async move {
-                                                if let Ok(chunk) = vanilla_chunk.to_custom_format() {
+                                                if let Ok(mut chunk) = vanilla_chunk.to_custom_format(dimension) {
+                                                    // Chunks saved before vanilla lit them have no light
+                                                    if vanilla_chunk.is_light_on != Some(1) {
+                                                        chunk.calculate_light();
+                                                    }
                                                     if let Err(e) = save_chunk_internal(&self_clone, chunk).await {
                                                         error!("Could not save chunk: {}", e);
                                                     } else {
@@ -158,15 +227,6 @@ impl World {
             }
         }
         while task_set.join_next().await.is_some() {}
-        self.sync().await?;
-        progress_bar.clone().finish();
-        info!(
-            "Imported {} chunks in {:?}",
-            progress_bar.clone().position(),
-            start.elapsed()
-        );
-
-        self.storage_backend.flush().await?;
         Ok(())
     }
 }
@@ -0,0 +1,209 @@
+use crate::block_id::BlockId;
+use crate::block_state::BlockRegistry;
+use crate::errors::WorldError;
+use lazy_static::lazy_static;
+use serde_derive::Deserialize;
+use std::collections::HashMap;
+
+/// The registries report made by the vanilla data generator, which is read from the server's
+/// folder. To make it, run `java -DbundlerMainClass=net.minecraft.data.Main -jar server.jar
+/// --reports` with the 1.21.1 server and copy `generated/reports/registries.json`.
+///
+/// The item names are meant to be bundled like the block mappings instead. Put the report in the
+/// .etc folder and run the item_parser.py script in the scripts folder to make
+/// `.etc/itemmappings.bz2`, which [ItemRegistry::from_json] reads. Until that file is checked in,
+/// the report is read at startup.
+pub const REGISTRIES_REPORT: &str = "registries.json";
+
+lazy_static! {
+    static ref REGISTRY: ItemRegistry = load_global();
+}
+
+#[cfg(not(test))]
+fn load_global() -> ItemRegistry {
+    use ferrumc_general_purpose::paths::get_root_path;
+    use tracing::{info, warn};
+
+    let path = get_root_path().join(REGISTRIES_REPORT);
+    if !path.exists() {
+        warn!(
+            "There's no {} in the server folder, so items can't be placed as blocks",
+            REGISTRIES_REPORT
+        );
+        return ItemRegistry::default();
+    }
+    match std::fs::read_to_string(&path)
+        .map_err(WorldError::from)
+        .and_then(|json| ItemRegistry::from_registries_report(&json))
+    {
+        Ok(registry) => {
+            info!("Loaded {} items from {}", registry.len(), REGISTRIES_REPORT);
+            registry
+        }
+        Err(e) => {
+            warn!("Could not load the items from {}: {}", REGISTRIES_REPORT, e);
+            ItemRegistry::default()
+        }
+    }
+}
+
+/// Tests don't read files from the server folder, so they get no items.
+#[cfg(test)]
+fn load_global() -> ItemRegistry {
+    ItemRegistry::default()
+}
+
+#[derive(Deserialize)]
+struct ReportRegistry {
+    entries: HashMap<String, ReportEntry>,
+}
+
+#[derive(Deserialize)]
+struct ReportEntry {
+    protocol_id: i32,
+}
+
+/// The names of the items in the `minecraft:item` registry, by ID.
+#[derive(Debug, Default)]
+pub struct ItemRegistry {
+    names: Vec<String>,
+}
+
+impl ItemRegistry {
+    /// The registry for the server's Minecraft version, loaded from [REGISTRIES_REPORT]. It's
+    /// empty if the report isn't there.
+    pub fn global() -> &'static ItemRegistry {
+        &REGISTRY
+    }
+
+    /// Reads the `minecraft:item` registry out of a registries report.
+    pub fn from_registries_report(json: &str) -> Result<Self, WorldError> {
+        let mut registries: HashMap<String, ReportRegistry> = serde_json::from_str(json)
+            .map_err(|e| WorldError::InvalidItemMappings(e.to_string()))?;
+        let items = registries
+            .remove("minecraft:item")
+            .ok_or_else(|| WorldError::InvalidItemMappings("No item registry".to_string()))?;
+        let mut entries = items.entries.into_iter().collect::<Vec<_>>();
+        entries.sort_by_key(|(_, entry)| entry.protocol_id);
+
+        let mut names = Vec::with_capacity(entries.len());
+        for (index, (name, entry)) in entries.into_iter().enumerate() {
+            if entry.protocol_id as usize != index {
+                return Err(WorldError::InvalidItemMappings(format!(
+                    "Missing item ID {index}"
+                )));
+            }
+            names.push(name);
+        }
+        Ok(ItemRegistry { names })
+    }
+
+    /// Reads item mappings made by `scripts/item_parser.py`: an object of item names by ID.
+    pub fn from_json(json: &str) -> Result<Self, WorldError> {
+        let mappings: HashMap<String, String> = serde_json::from_str(json)
+            .map_err(|e| WorldError::InvalidItemMappings(e.to_string()))?;
+        let mut names = vec![None; mappings.len()];
+        for (id, name) in mappings {
+            let slot = id
+                .parse::<usize>()
+                .ok()
+                .and_then(|index| names.get_mut(index))
+                .ok_or_else(|| WorldError::InvalidItemMappings(format!("Bad item ID {id}")))?;
+            *slot = Some(name);
+        }
+        let names = names
+            .into_iter()
+            .enumerate()
+            .map(|(index, name)| {
+                name.ok_or_else(|| {
+                    WorldError::InvalidItemMappings(format!("Missing item ID {index}"))
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(ItemRegistry { names })
+    }
+
+    /// The namespaced name of an item.
+    pub fn name(&self, item: i32) -> Option<&str> {
+        usize::try_from(item)
+            .ok()
+            .and_then(|index| self.names.get(index))
+            .map(String::as_str)
+    }
+
+    /// The block an item places: the default state of the block with the same name, which
+    /// block items have. Items without a block of their own, like `minecraft:redstone`, place
+    /// nothing. See [BlockRegistry::placement_state] for turning it the way it's placed.
+    pub fn block(&self, item: i32, blocks: &BlockRegistry) -> Option<BlockId> {
+        blocks
+            .default_state(self.name(item)?)
+            .map(|state| state.id)
+            .filter(|block| !block.is_air())
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REPORT: &str = r#"{
+        "minecraft:block": {"entries": {"minecraft:air": {"protocol_id": 0}}},
+        "minecraft:item": {
+            "default": "minecraft:air",
+            "entries": {
+                "minecraft:granite": {"protocol_id": 2},
+                "minecraft:air": {"protocol_id": 0},
+                "minecraft:stone": {"protocol_id": 1},
+                "minecraft:redstone": {"protocol_id": 3}
+            },
+            "protocol_id": 6
+        }
+    }"#;
+
+    #[test]
+    fn test_from_registries_report() {
+        let items = ItemRegistry::from_registries_report(REPORT).unwrap();
+        assert_eq!(items.len(), 4);
+        assert_eq!(items.name(2), Some("minecraft:granite"));
+        assert_eq!(items.name(4), None);
+        assert_eq!(items.name(-1), None);
+
+        assert!(ItemRegistry::from_registries_report("{}").is_err());
+        assert!(ItemRegistry::from_registries_report(
+            r#"{"minecraft:item": {"entries": {"minecraft:stone": {"protocol_id": 1}}}}"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_from_json() {
+        let items = ItemRegistry::from_json(
+            r#"{"1": "minecraft:stone", "0": "minecraft:air", "2": "minecraft:granite"}"#,
+        )
+        .unwrap();
+        assert_eq!(items.len(), 3);
+        assert_eq!(items.name(1), Some("minecraft:stone"));
+
+        assert!(ItemRegistry::from_json(r#"{"1": "minecraft:stone"}"#).is_err());
+        assert!(ItemRegistry::from_json(r#"{"stone": "minecraft:stone"}"#).is_err());
+    }
+
+    #[test]
+    fn test_block() {
+        let items = ItemRegistry::from_registries_report(REPORT).unwrap();
+        let blocks = BlockRegistry::global();
+        assert_eq!(items.block(1, blocks), Some(BlockId(1)));
+        assert_eq!(items.block(2, blocks), Some(BlockId(2)));
+        assert_eq!(items.block(0, blocks), None);
+        assert_eq!(items.block(3, blocks), None);
+        assert_eq!(items.block(100, blocks), None);
+    }
+}
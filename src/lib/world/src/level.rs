@@ -0,0 +1,321 @@
+//! What the world is: its name, seed, difficulty and game rules. Imported worlds get these from
+//! their `level.dat`.
+
+use crate::border::WorldBorder;
+use crate::errors::WorldError;
+use crate::importing::read_nbt_file;
+use crate::meta::LEVEL_KEY;
+use crate::spawn::WorldSpawn;
+use crate::time::WorldTime;
+use crate::World;
+use bitcode_derive::{Decode, Encode};
+use ferrumc_config::statics::get_global_config;
+use ferrumc_macros::{NBTDeserialize, NBTSerialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use tracing::{error, info};
+
+/// The game rule that stops the time of day moving when it's `false`.
+pub const DO_DAYLIGHT_CYCLE: &str = "doDaylightCycle";
+
+/// Vanilla's default difficulty, easy.
+const DEFAULT_DIFFICULTY: u8 = 1;
+/// Vanilla's default world border warnings, for `level.dat` files that don't have them.
+const DEFAULT_BORDER_WARNING_BLOCKS: i32 = 5;
+const DEFAULT_BORDER_WARNING_TIME: i32 = 15;
+
+/// The world's identity and settings.
+#[derive(Encode, Decode, Clone, Debug, PartialEq)]
+pub struct LevelInfo {
+    pub name: String,
+    pub seed: i64,
+    /// 0 to 3, for peaceful to hard.
+    pub difficulty: u8,
+    pub difficulty_locked: bool,
+    pub hardcore: bool,
+    /// Game rules by name, with their values as vanilla saves them, e.g. `"true"` or `"3"`.
+    pub game_rules: BTreeMap<String, String>,
+}
+
+impl LevelInfo {
+    /// The level info of a world that wasn't imported.
+    pub fn from_config() -> Self {
+        Self {
+            name: get_global_config().world.clone(),
+            seed: 0,
+            difficulty: DEFAULT_DIFFICULTY,
+            difficulty_locked: false,
+            hardcore: false,
+            game_rules: BTreeMap::new(),
+        }
+    }
+
+    pub fn game_rule(&self, name: &str) -> Option<&str> {
+        self.game_rules.get(name).map(String::as_str)
+    }
+
+    /// A game rule that's `true` or `false`, or `None` if it isn't set or isn't a boolean.
+    pub fn bool_game_rule(&self, name: &str) -> Option<bool> {
+        self.game_rule(name)?.parse().ok()
+    }
+}
+
+/// A vanilla `level.dat` file. Everything is in the `Data` compound.
+#[derive(NBTDeserialize, NBTSerialize, Debug)]
+#[nbt(is_root)]
+#[nbt(rename = "")]
+struct VanillaLevelDat {
+    #[nbt(rename = "Data")]
+    data: VanillaLevel,
+}
+
+/// The parts of a `level.dat` that are imported.
+#[derive(NBTDeserialize, NBTSerialize, Debug)]
+struct VanillaLevel {
+    #[nbt(rename = "LevelName")]
+    level_name: Option<String>,
+    #[nbt(rename = "WorldGenSettings")]
+    world_gen_settings: Option<VanillaWorldGenSettings>,
+    /// Where the seed was kept before 1.16.
+    #[nbt(rename = "RandomSeed")]
+    random_seed: Option<i64>,
+    #[nbt(rename = "Difficulty")]
+    difficulty: Option<i8>,
+    #[nbt(rename = "DifficultyLocked")]
+    difficulty_locked: Option<bool>,
+    #[nbt(rename = "hardcore")]
+    hardcore: Option<bool>,
+    #[nbt(rename = "GameRules")]
+    game_rules: Option<BTreeMap<String, String>>,
+    #[nbt(rename = "SpawnX")]
+    spawn_x: Option<i32>,
+    #[nbt(rename = "SpawnY")]
+    spawn_y: Option<i32>,
+    #[nbt(rename = "SpawnZ")]
+    spawn_z: Option<i32>,
+    #[nbt(rename = "SpawnAngle")]
+    spawn_angle: Option<f32>,
+    #[nbt(rename = "Time")]
+    time: Option<i64>,
+    #[nbt(rename = "DayTime")]
+    day_time: Option<i64>,
+    #[nbt(rename = "BorderCenterX")]
+    border_center_x: Option<f64>,
+    #[nbt(rename = "BorderCenterZ")]
+    border_center_z: Option<f64>,
+    #[nbt(rename = "BorderSize")]
+    border_size: Option<f64>,
+    #[nbt(rename = "BorderWarningBlocks")]
+    border_warning_blocks: Option<f64>,
+    #[nbt(rename = "BorderWarningTime")]
+    border_warning_time: Option<f64>,
+}
+
+#[derive(NBTDeserialize, NBTSerialize, Debug)]
+struct VanillaWorldGenSettings {
+    seed: i64,
+}
+
+/// Everything imported from a `level.dat`. Records the file doesn't have are `None`.
+#[derive(Debug, PartialEq)]
+pub struct ImportedLevel {
+    pub info: LevelInfo,
+    pub spawn: Option<WorldSpawn>,
+    pub time: Option<WorldTime>,
+    pub border: Option<WorldBorder>,
+}
+
+impl ImportedLevel {
+    /// Reads a vanilla `level.dat` file, which is usually gzipped.
+    pub fn from_vanilla(bytes: &[u8]) -> Result<Self, WorldError> {
+        let bytes = read_nbt_file(bytes)?;
+        let level = VanillaLevelDat::from_bytes(&bytes)
+            .map_err(|e| WorldError::InvalidLevelData(e.to_string()))?
+            .data;
+
+        let info = LevelInfo {
+            name: level
+                .level_name
+                .unwrap_or_else(|| get_global_config().world.clone()),
+            seed: level
+                .world_gen_settings
+                .map(|settings| settings.seed)
+                .or(level.random_seed)
+                .unwrap_or_default(),
+            difficulty: level.difficulty.map_or(DEFAULT_DIFFICULTY, |difficulty| {
+                difficulty.clamp(0, 3) as u8
+            }),
+            difficulty_locked: level.difficulty_locked.unwrap_or_default(),
+            hardcore: level.hardcore.unwrap_or_default(),
+            game_rules: level.game_rules.unwrap_or_default(),
+        };
+
+        let spawn = match (level.spawn_x, level.spawn_y, level.spawn_z) {
+            (Some(x), Some(y), Some(z)) => Some(WorldSpawn {
+                x,
+                y,
+                z,
+                angle: level.spawn_angle.unwrap_or_default(),
+            }),
+            _ => None,
+        };
+
+        let time = level.time.map(|world_age| WorldTime {
+            world_age,
+            time_of_day: level.day_time.unwrap_or(world_age),
+        });
+
+        let border = level.border_size.map(|diameter| WorldBorder {
+            center_x: level.border_center_x.unwrap_or_default(),
+            center_z: level.border_center_z.unwrap_or_default(),
+            diameter,
+            warning_blocks: level
+                .border_warning_blocks
+                .map_or(DEFAULT_BORDER_WARNING_BLOCKS, |blocks| blocks as i32),
+            warning_time: level
+                .border_warning_time
+                .map_or(DEFAULT_BORDER_WARNING_TIME, |time| time as i32),
+        });
+
+        Ok(Self {
+            info,
+            spawn,
+            time,
+            border,
+        })
+    }
+}
+
+impl World {
+    /// The world's level info.
+    pub fn level_info(&self) -> LevelInfo {
+        self.level.lock().clone()
+    }
+
+    /// Replaces the world's level info, and saves it.
+    pub async fn set_level_info(&self, info: LevelInfo) -> Result<(), WorldError> {
+        self.save_meta(LEVEL_KEY, &info).await?;
+        *self.level.lock() = info;
+        Ok(())
+    }
+
+    /// Loads the saved level info, or the defaults if the world wasn't imported.
+    pub(crate) async fn load_level_info(&self) -> Result<LevelInfo, WorldError> {
+        Ok(self
+            .load_meta(LEVEL_KEY)
+            .await?
+            .unwrap_or_else(LevelInfo::from_config))
+    }
+
+    /// Imports a vanilla world's `level.dat`, if it has one, replacing the world's level info,
+    /// spawn point, time and border. A file that can't be read is skipped.
+    pub(crate) async fn import_level(&self, import_dir: &Path) -> Result<(), WorldError> {
+        let path = import_dir.join("level.dat");
+        if !path.is_file() {
+            info!("No level.dat to import");
+            return Ok(());
+        }
+        let imported = match ImportedLevel::from_vanilla(&std::fs::read(&path)?) {
+            Ok(imported) => imported,
+            Err(e) => {
+                error!("Could not import {}: {}", path.display(), e);
+                return Ok(());
+            }
+        };
+
+        if let Some(spawn) = imported.spawn {
+            self.set_spawn(spawn).await?;
+        }
+        if let Some(time) = imported.time {
+            *self.time.lock() = time;
+            self.save_time().await?;
+        }
+        if let Some(border) = imported.border {
+            self.set_border(border).await?;
+        }
+        info!("Imported level {}", imported.info.name);
+        self.set_level_info(imported.info).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ferrumc_nbt::{NBTSerializable, NBTSerializeOptions};
+
+    fn vanilla_level() -> VanillaLevel {
+        VanillaLevel {
+            level_name: Some("Imported".to_string()),
+            world_gen_settings: Some(VanillaWorldGenSettings { seed: -42 }),
+            random_seed: None,
+            difficulty: Some(3),
+            difficulty_locked: Some(true),
+            hardcore: Some(false),
+            game_rules: Some(BTreeMap::from([
+                (DO_DAYLIGHT_CYCLE.to_string(), "false".to_string()),
+                ("randomTickSpeed".to_string(), "3".to_string()),
+            ])),
+            spawn_x: Some(100),
+            spawn_y: Some(70),
+            spawn_z: Some(-20),
+            spawn_angle: Some(90.0),
+            time: Some(50000),
+            day_time: Some(30000),
+            border_center_x: None,
+            border_center_z: None,
+            border_size: Some(1000.0),
+            border_warning_blocks: None,
+            border_warning_time: None,
+        }
+    }
+
+    fn to_bytes(level: VanillaLevel) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        VanillaLevelDat { data: level }.serialize(&mut bytes, &NBTSerializeOptions::WithHeader(""));
+        bytes
+    }
+
+    #[test]
+    fn test_from_vanilla() {
+        let imported = ImportedLevel::from_vanilla(&to_bytes(vanilla_level())).unwrap();
+        assert_eq!(imported.info.name, "Imported");
+        assert_eq!(imported.info.seed, -42);
+        assert_eq!(imported.info.difficulty, 3);
+        assert!(imported.info.difficulty_locked);
+        assert_eq!(imported.info.bool_game_rule(DO_DAYLIGHT_CYCLE), Some(false));
+        assert_eq!(imported.info.bool_game_rule("randomTickSpeed"), None);
+        assert_eq!(imported.info.game_rule("randomTickSpeed"), Some("3"));
+        assert_eq!(
+            imported.spawn,
+            Some(WorldSpawn {
+                x: 100,
+                y: 70,
+                z: -20,
+                angle: 90.0
+            })
+        );
+        assert_eq!(
+            imported.time,
+            Some(WorldTime {
+                world_age: 50000,
+                time_of_day: 30000
+            })
+        );
+        let border = imported.border.unwrap();
+        assert_eq!(border.diameter, 1000.0);
+        assert_eq!(border.warning_blocks, DEFAULT_BORDER_WARNING_BLOCKS);
+    }
+
+    #[test]
+    fn test_old_seed_and_missing_records() {
+        let mut level = vanilla_level();
+        level.world_gen_settings = None;
+        level.random_seed = Some(1234);
+        level.spawn_y = None;
+        level.border_size = None;
+        let imported = ImportedLevel::from_vanilla(&to_bytes(level)).unwrap();
+        assert_eq!(imported.info.seed, 1234);
+        assert_eq!(imported.spawn, None);
+        assert_eq!(imported.border, None);
+    }
+}
@@ -1,31 +1,67 @@
 #![feature(hasher_prefixfree_extras)]
 
+pub mod block_id;
+pub mod block_state;
+pub mod border;
 pub mod chunk_format;
 mod db_functions;
+mod edits;
 pub mod errors;
+mod exporting;
+pub mod generation;
 mod importing;
+pub mod items;
+pub mod level;
+pub mod lighting;
+mod meta;
+pub mod placement;
+pub mod player_data;
+pub mod spawn;
+pub mod time;
 mod vanilla_chunk_format;
 
+use crate::border::WorldBorder;
 use crate::chunk_format::Chunk;
+use crate::db_functions::CHUNKS_TABLE;
 use crate::errors::WorldError;
+use crate::generation::ChunkGenerator;
+use crate::level::LevelInfo;
+use crate::meta::WORLD_META_TABLE;
+use crate::player_data::PLAYER_DATA_TABLE;
+use crate::spawn::WorldSpawn;
+use crate::time::WorldTime;
 use deepsize::DeepSizeOf;
 use ferrumc_config::statics::get_global_config;
+use ferrumc_core::dimensions::dimension::Dimension;
 use ferrumc_general_purpose::paths::get_root_path;
+use ferrumc_storage::backend::{open_backend, BackendType, DatabaseBackend};
 use ferrumc_storage::compressors::Compressor;
-use ferrumc_storage::lmdb::LmdbBackend;
 use moka::future::{Cache, FutureExt};
 use moka::notification::ListenerFuture;
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::process::exit;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
 use std::time::Duration;
 use tokio::fs::create_dir_all;
+use tokio::sync::Mutex;
 use tracing::{error, info, trace, warn};
 
 #[derive(Clone)]
 pub struct World {
-    storage_backend: LmdbBackend,
+    storage_backend: Arc<dyn DatabaseBackend>,
     compressor: Compressor,
     cache: Cache<(i32, i32, String), Chunk>,
+    /// Held while a block edit loads, modifies and saves a chunk.
+    edit_lock: Arc<Mutex<()>>,
+    time: Arc<parking_lot::Mutex<WorldTime>>,
+    daylight_cycle: Arc<AtomicBool>,
+    spawn: Arc<parking_lot::Mutex<WorldSpawn>>,
+    border: Arc<parking_lot::Mutex<WorldBorder>>,
+    level: Arc<parking_lot::Mutex<LevelInfo>>,
+    /// What makes each dimension's chunks that don't exist yet.
+    generators: Arc<parking_lot::RwLock<HashMap<Dimension, Arc<dyn ChunkGenerator>>>>,
 }
 
 async fn check_config_validity() -> Result<(), WorldError> {
@@ -96,7 +132,26 @@ impl World {
         if backend_path.is_relative() {
             backend_path = get_root_path().join(backend_path);
         }
-        let storage_backend = LmdbBackend::initialize(Some(backend_path))
+        let backend_type = match get_global_config()
+            .database
+            .backend
+            .trim()
+            .to_lowercase()
+            .as_str()
+        {
+            "lmdb" => BackendType::Lmdb,
+            "redb" => BackendType::Redb,
+            "memory" => BackendType::Memory,
+            _ => {
+                error!(
+                    "Invalid database backend: {}",
+                    get_global_config().database.backend
+                );
+                exit(1);
+            }
+        };
+        info!("Using the {:?} database backend", backend_type);
+        let storage_backend = open_backend(backend_type, backend_path)
             .await
             .expect("Failed to initialize database");
 
@@ -141,6 +196,31 @@ impl World {
             exit(1);
         }
 
+        let world = match World::with_backend(storage_backend, compression_algo).await {
+            Ok(world) => world,
+            Err(e) => {
+                error!("Could not load the world: {}", e);
+                exit(1);
+            }
+        };
+        // The noise generator needs the seed, which comes from the records
+        if let Err(e) = world.load_generators() {
+            error!("Fatal error in generation config: {}", e);
+            exit(1);
+        }
+        world
+    }
+
+    /// Creates a world on a backend that's already open, making the tables it needs and loading
+    /// its records. The world has no generators until they're loaded or set.
+    pub(crate) async fn with_backend(
+        storage_backend: Arc<dyn DatabaseBackend>,
+        compressor: Compressor,
+    ) -> Result<Self, WorldError> {
+        for table in [CHUNKS_TABLE, PLAYER_DATA_TABLE, WORLD_META_TABLE] {
+            storage_backend.create_table(table.to_string()).await?;
+        }
+
         let eviction_listener = move |key, _, cause| -> ListenerFuture {
             async move {
                 trace!("Evicting key: {:?}, cause: {:?}", key, cause);
@@ -155,10 +235,34 @@ impl World {
             .max_capacity(get_global_config().database.cache_capacity * 1024)
             .build();
 
-        World {
+        let world = World {
             storage_backend,
-            compressor: compression_algo,
+            compressor,
             cache,
-        }
+            edit_lock: Arc::new(Mutex::new(())),
+            time: Arc::new(parking_lot::Mutex::new(WorldTime::default())),
+            daylight_cycle: Arc::new(AtomicBool::new(get_global_config().time.daylight_cycle)),
+            spawn: Arc::new(parking_lot::Mutex::new(WorldSpawn::from_config())),
+            border: Arc::new(parking_lot::Mutex::new(WorldBorder::from_config())),
+            level: Arc::new(parking_lot::Mutex::new(LevelInfo::from_config())),
+            generators: Arc::new(parking_lot::RwLock::new(HashMap::new())),
+        };
+        world.load_meta_records().await?;
+        Ok(world)
+    }
+}
+
+#[cfg(test)]
+impl World {
+    /// A world kept in memory, with no generators.
+    pub(crate) async fn in_memory() -> Self {
+        use ferrumc_storage::compressors::CompressorType;
+        use ferrumc_storage::memory::MemoryBackend;
+        World::with_backend(
+            Arc::new(MemoryBackend::new()),
+            Compressor::create(CompressorType::Zlib, 6),
+        )
+        .await
+        .unwrap()
     }
 }
@@ -0,0 +1,616 @@
+//! Block and sky light. Chunks that don't have any light yet are lit from scratch, and the light
+//! around a block is updated whenever it changes.
+
+use crate::block_id::BlockId;
+use crate::block_state::BlockRegistry;
+use crate::chunk_format::{Chunk, Section};
+use crate::errors::WorldError;
+use crate::World;
+use ferrumc_core::dimensions::dimension::Dimension;
+use lazy_static::lazy_static;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// The brightest light can be.
+pub const MAX_LIGHT: u8 = 15;
+/// The length of a section's light data, which has a nibble for each block.
+pub const LIGHT_DATA_LEN: usize = 2048;
+/// The number of blocks in a section.
+const SECTION_VOLUME: usize = 16 * 16 * 16;
+
+lazy_static! {
+    static ref LIGHT_PROPERTIES: Vec<LightProperties> = {
+        let registry = BlockRegistry::global();
+        let len = registry
+            .iter()
+            .map(|state| state.id.0 as usize + 1)
+            .max()
+            .unwrap_or_default();
+        let mut properties = vec![LightProperties::default(); len];
+        for state in registry.iter() {
+            properties[state.id.0 as usize] = LightProperties {
+                emission: state.light_emission(),
+                opacity: state.light_opacity(),
+            };
+        }
+        properties
+    };
+}
+
+/// The two kinds of light, which spread separately.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LightKind {
+    /// Light from the sky, which goes straight down without dimming through blocks that don't
+    /// stop it at all.
+    Sky,
+    /// Light given off by blocks like torches.
+    Block,
+}
+
+/// What a block does to light.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LightProperties {
+    /// How much light the block gives off.
+    pub emission: u8,
+    /// How much light is lost going into the block. Light always loses at least 1 per block,
+    /// apart from sky light going straight down.
+    pub opacity: u8,
+}
+
+impl LightProperties {
+    /// The light properties of a block. Blocks that aren't in the registry stop light unless
+    /// they're air.
+    pub fn of(block: BlockId) -> Self {
+        usize::try_from(block.0)
+            .ok()
+            .and_then(|index| LIGHT_PROPERTIES.get(index))
+            .copied()
+            .unwrap_or(Self {
+                emission: 0,
+                opacity: if block.is_air() { 0 } else { MAX_LIGHT },
+            })
+    }
+}
+
+/// What happened when a block was set.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockChange {
+    /// The block that was there before.
+    pub old: BlockId,
+    /// The chunks whose light changed, which have to be sent to the players that can see them.
+    pub relit_chunks: Vec<(i32, i32)>,
+}
+
+/// The index of a block in a section's blocks and light data.
+fn block_index(x: i32, y: i32, z: i32) -> usize {
+    (((y & 15) << 8) | ((z & 15) << 4) | (x & 15)) as usize
+}
+
+fn get_nibble(data: &[u8], index: usize) -> u8 {
+    data.get(index / 2)
+        .map_or(0, |byte| (byte >> ((index % 2) * 4)) & 0xF)
+}
+
+fn set_nibble(data: &mut [u8], index: usize, value: u8) {
+    let shift = (index % 2) * 4;
+    data[index / 2] = (data[index / 2] & !(0xF << shift)) | ((value & 0xF) << shift);
+}
+
+impl Section {
+    /// The section's light data of the given kind. Sections that haven't been lit have none.
+    pub fn light(&self, kind: LightKind) -> &[u8] {
+        match kind {
+            LightKind::Sky => &self.sky_light,
+            LightKind::Block => &self.block_light,
+        }
+    }
+
+    /// Whether the section has both kinds of light data.
+    pub fn has_light(&self) -> bool {
+        self.sky_light.len() == LIGHT_DATA_LEN && self.block_light.len() == LIGHT_DATA_LEN
+    }
+}
+
+impl Chunk {
+    /// Whether every section in the chunk has light data.
+    pub fn has_light(&self) -> bool {
+        self.sections.iter().all(Section::has_light)
+    }
+
+    /// Lights the chunk from scratch, e.g. after it's been generated or imported without light.
+    ///
+    /// Only the chunk itself is looked at, so light from the chunks around it doesn't spread
+    /// into it until blocks near the border change.
+    pub fn calculate_light(&mut self) {
+        self.calculate_light_with(LightProperties::of);
+    }
+
+    fn calculate_light_with(&mut self, properties: impl Fn(BlockId) -> LightProperties) {
+        let mut engine = LightEngine::new(self.dimension_kind());
+        engine.add_chunk(self, &properties);
+        engine.light_chunk(self.x, self.z);
+        engine.write_chunk(self);
+    }
+}
+
+impl World {
+    /// Updates the light around a block that changed, saving the chunks whose light changed and
+    /// returning their coordinates.
+    ///
+    /// Light spreads at most 15 blocks, so only the chunk the block is in and the ones around it
+    /// can change. Chunks that don't exist are left out.
+    pub(crate) async fn update_light(
+        &self,
+        x: i32,
+        y: i32,
+        z: i32,
+        dimension: &str,
+    ) -> Result<Vec<(i32, i32)>, WorldError> {
+        let (chunk_x, chunk_z) = (x.div_euclid(16), z.div_euclid(16));
+        let mut engine = LightEngine::new(Dimension::from_name(dimension).unwrap_or_default());
+        let mut chunks = Vec::new();
+        for dx in -1..=1 {
+            for dz in -1..=1 {
+                match self.load_chunk(chunk_x + dx, chunk_z + dz, dimension).await {
+                    Ok(chunk) => {
+                        engine.add_chunk(&chunk, &LightProperties::of);
+                        chunks.push(chunk);
+                    }
+                    Err(WorldError::ChunkNotFound) => {}
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+
+        engine.update_block(x, y, z);
+
+        let mut relit = Vec::new();
+        for mut chunk in chunks {
+            if engine.write_chunk(&mut chunk) {
+                relit.push((chunk.x, chunk.z));
+                self.save_chunk(chunk).await?;
+            }
+        }
+        Ok(relit)
+    }
+}
+
+/// The blocks and light of a section, unpacked so light can be spread through it quickly.
+struct LightSection {
+    blocks: Vec<LightProperties>,
+    sky: Vec<u8>,
+    block: Vec<u8>,
+}
+
+impl LightSection {
+    fn light(&self, kind: LightKind) -> &[u8] {
+        match kind {
+            LightKind::Sky => &self.sky,
+            LightKind::Block => &self.block,
+        }
+    }
+
+    fn light_mut(&mut self, kind: LightKind) -> &mut [u8] {
+        match kind {
+            LightKind::Sky => &mut self.sky,
+            LightKind::Block => &mut self.block,
+        }
+    }
+}
+
+/// Spreads light through a group of chunks of the same dimension. Light doesn't spread into
+/// chunks that aren't in the group.
+struct LightEngine {
+    min_y: i32,
+    height: i32,
+    has_skylight: bool,
+    /// The sections of each chunk from the bottom of the dimension up, by chunk coordinates.
+    chunks: HashMap<(i32, i32), Vec<LightSection>>,
+    /// The chunks whose light changed.
+    changed: HashSet<(i32, i32)>,
+}
+
+/// The directions light spreads in, with `DOWN` first.
+const DIRECTIONS: [(i32, i32, i32); 6] = [
+    (0, -1, 0),
+    (0, 1, 0),
+    (-1, 0, 0),
+    (1, 0, 0),
+    (0, 0, -1),
+    (0, 0, 1),
+];
+const DOWN: (i32, i32, i32) = DIRECTIONS[0];
+
+impl LightEngine {
+    fn new(dimension: Dimension) -> Self {
+        Self {
+            min_y: dimension.min_y(),
+            height: dimension.height(),
+            has_skylight: dimension.has_skylight(),
+            chunks: HashMap::new(),
+            changed: HashSet::new(),
+        }
+    }
+
+    fn kinds(&self) -> &'static [LightKind] {
+        if self.has_skylight {
+            &[LightKind::Sky, LightKind::Block]
+        } else {
+            &[LightKind::Block]
+        }
+    }
+
+    fn add_chunk(&mut self, chunk: &Chunk, properties: &impl Fn(BlockId) -> LightProperties) {
+        let min_section = self.min_y.div_euclid(16);
+        let sections = (min_section..min_section + self.height / 16)
+            .map(|section_y| {
+                let section = chunk
+                    .sections
+                    .iter()
+                    .find(|section| i32::from(section.y) == section_y);
+                let light = |kind| {
+                    section
+                        .map(|section| section.light(kind))
+                        .filter(|light| light.len() == LIGHT_DATA_LEN)
+                        .map_or_else(|| vec![0; LIGHT_DATA_LEN], <[u8]>::to_vec)
+                };
+                LightSection {
+                    blocks: section.map_or_else(
+                        || vec![LightProperties::default(); SECTION_VOLUME],
+                        |section| {
+                            section
+                                .block_states
+                                .blocks()
+                                .into_iter()
+                                .map(properties)
+                                .collect()
+                        },
+                    ),
+                    sky: light(LightKind::Sky),
+                    block: light(LightKind::Block),
+                }
+            })
+            .collect();
+        self.chunks.insert((chunk.x, chunk.z), sections);
+    }
+
+    /// Writes the chunk's light back into it if it changed, adding any sections it's missing.
+    /// Returns whether it changed.
+    fn write_chunk(&self, chunk: &mut Chunk) -> bool {
+        let key = (chunk.x, chunk.z);
+        let Some(sections) = self
+            .chunks
+            .get(&key)
+            .filter(|_| self.changed.contains(&key))
+        else {
+            return false;
+        };
+        let min_section = self.min_y.div_euclid(16);
+        for (index, light) in sections.iter().enumerate() {
+            let section_y = (min_section + index as i32) as i8;
+            let section = match chunk
+                .sections
+                .iter()
+                .position(|section| section.y == section_y)
+            {
+                Some(position) => &mut chunk.sections[position],
+                None => {
+                    chunk.sections.push(Section::empty(section_y));
+                    chunk.sections.sort_by_key(|section| section.y);
+                    chunk
+                        .sections
+                        .iter_mut()
+                        .find(|section| section.y == section_y)
+                        .expect("the section was just added")
+                }
+            };
+            section.sky_light = light.sky.clone();
+            section.block_light = light.block.clone();
+        }
+        true
+    }
+
+    fn section(&self, x: i32, y: i32, z: i32) -> Option<&LightSection> {
+        if y < self.min_y || y >= self.min_y + self.height {
+            return None;
+        }
+        self.chunks
+            .get(&(x.div_euclid(16), z.div_euclid(16)))?
+            .get(((y - self.min_y) / 16) as usize)
+    }
+
+    /// The light properties of a block, or `None` if it's outside of the chunks.
+    fn properties(&self, x: i32, y: i32, z: i32) -> Option<LightProperties> {
+        Some(self.section(x, y, z)?.blocks[block_index(x, y, z)])
+    }
+
+    /// The light at a block, or `None` if it's in a chunk that isn't loaded. Above the top of
+    /// the world the sky is as bright as it gets, and below the bottom it's dark.
+    fn light(&self, kind: LightKind, x: i32, y: i32, z: i32) -> Option<u8> {
+        if !self
+            .chunks
+            .contains_key(&(x.div_euclid(16), z.div_euclid(16)))
+        {
+            return None;
+        }
+        if y >= self.min_y + self.height {
+            return Some(match kind {
+                LightKind::Sky if self.has_skylight => MAX_LIGHT,
+                _ => 0,
+            });
+        }
+        Some(self.section(x, y, z).map_or(0, |section| {
+            get_nibble(section.light(kind), block_index(x, y, z))
+        }))
+    }
+
+    /// Sets the light at a block. Blocks outside of the chunks are left alone.
+    fn set_light(&mut self, kind: LightKind, x: i32, y: i32, z: i32, level: u8) {
+        if y < self.min_y || y >= self.min_y + self.height {
+            return;
+        }
+        let key = (x.div_euclid(16), z.div_euclid(16));
+        let Some(section) = self
+            .chunks
+            .get_mut(&key)
+            .and_then(|sections| sections.get_mut(((y - self.min_y) / 16) as usize))
+        else {
+            return;
+        };
+        set_nibble(section.light_mut(kind), block_index(x, y, z), level);
+        self.changed.insert(key);
+    }
+
+    /// Lights a chunk from scratch. Light from the other chunks spreads into it, and its own
+    /// light spreads out into them.
+    fn light_chunk(&mut self, chunk_x: i32, chunk_z: i32) {
+        let Some(sections) = self.chunks.get_mut(&(chunk_x, chunk_z)) else {
+            return;
+        };
+        for section in sections.iter_mut() {
+            section.sky.fill(0);
+            section.block.fill(0);
+        }
+        self.changed.insert((chunk_x, chunk_z));
+
+        let (min_x, min_z) = (chunk_x * 16, chunk_z * 16);
+        let top = self.min_y + self.height;
+        for &kind in self.kinds() {
+            let mut queue = VecDeque::new();
+            for y in self.min_y..top {
+                for i in 0..16 {
+                    // The light next to the chunk
+                    queue.extend([
+                        (min_x - 1, y, min_z + i),
+                        (min_x + 16, y, min_z + i),
+                        (min_x + i, y, min_z - 1),
+                        (min_x + i, y, min_z + 16),
+                    ]);
+                }
+            }
+            match kind {
+                // The sky above every column
+                LightKind::Sky => queue.extend(
+                    (0..16).flat_map(|x| (0..16).map(move |z| (min_x + x, top, min_z + z))),
+                ),
+                LightKind::Block => {
+                    for x in min_x..min_x + 16 {
+                        for y in self.min_y..top {
+                            for z in min_z..min_z + 16 {
+                                let emission =
+                                    self.properties(x, y, z).unwrap_or_default().emission;
+                                if emission > 0 {
+                                    self.set_light(kind, x, y, z, emission);
+                                    queue.push_back((x, y, z));
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            self.spread(kind, queue);
+        }
+    }
+
+    /// Updates the light around a block after it changed. The engine's chunks must already
+    /// have the new block.
+    fn update_block(&mut self, x: i32, y: i32, z: i32) {
+        for &kind in self.kinds() {
+            let old = self.light(kind, x, y, z).unwrap_or_default();
+            self.set_light(kind, x, y, z, 0);
+            let mut queue = self.remove(kind, VecDeque::from([((x, y, z), old)]));
+
+            // The block's own light, and the light coming into it from around it
+            let emission = self.properties(x, y, z).unwrap_or_default().emission;
+            if kind == LightKind::Block && emission > 0 {
+                self.set_light(kind, x, y, z, emission);
+                queue.push_back((x, y, z));
+            }
+            for (dx, dy, dz) in DIRECTIONS {
+                queue.push_back((x + dx, y + dy, z + dz));
+            }
+            self.spread(kind, queue);
+        }
+    }
+
+    /// Spreads light out from the queued blocks to the blocks around them that are darker
+    /// than it would make them.
+    fn spread(&mut self, kind: LightKind, mut queue: VecDeque<(i32, i32, i32)>) {
+        while let Some((x, y, z)) = queue.pop_front() {
+            let Some(level) = self.light(kind, x, y, z).filter(|&level| level > 1) else {
+                continue;
+            };
+            for direction @ (dx, dy, dz) in DIRECTIONS {
+                let (nx, ny, nz) = (x + dx, y + dy, z + dz);
+                let Some(properties) = self.properties(nx, ny, nz) else {
+                    continue;
+                };
+                let spread = if kind == LightKind::Sky
+                    && direction == DOWN
+                    && level == MAX_LIGHT
+                    && properties.opacity == 0
+                {
+                    MAX_LIGHT
+                } else {
+                    level.saturating_sub(properties.opacity.max(1))
+                };
+                if spread > self.light(kind, nx, ny, nz).unwrap_or(MAX_LIGHT) {
+                    self.set_light(kind, nx, ny, nz, spread);
+                    queue.push_back((nx, ny, nz));
+                }
+            }
+        }
+    }
+
+    /// Darkens the blocks that got their light from the queued blocks, given the light each of
+    /// those had. Returns the blocks around the darkened area whose light has to spread back
+    /// into it.
+    fn remove(
+        &mut self,
+        kind: LightKind,
+        mut queue: VecDeque<((i32, i32, i32), u8)>,
+    ) -> VecDeque<(i32, i32, i32)> {
+        let mut respread = VecDeque::new();
+        while let Some(((x, y, z), level)) = queue.pop_front() {
+            for direction @ (dx, dy, dz) in DIRECTIONS {
+                let (nx, ny, nz) = (x + dx, y + dy, z + dz);
+                let Some(neighbour) = self.light(kind, nx, ny, nz).filter(|&light| light > 0)
+                else {
+                    continue;
+                };
+                let from_here = neighbour < level
+                    || (kind == LightKind::Sky
+                        && direction == DOWN
+                        && level == MAX_LIGHT
+                        && neighbour == MAX_LIGHT);
+                match self.properties(nx, ny, nz) {
+                    Some(properties) if from_here => {
+                        self.set_light(kind, nx, ny, nz, 0);
+                        queue.push_back(((nx, ny, nz), neighbour));
+                        if kind == LightKind::Block && properties.emission > 0 {
+                            self.set_light(kind, nx, ny, nz, properties.emission);
+                            respread.push_back((nx, ny, nz));
+                        }
+                    }
+                    _ => respread.push_back((nx, ny, nz)),
+                }
+            }
+        }
+        respread
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STONE: BlockId = BlockId(1);
+    const TORCH: BlockId = BlockId(2);
+
+    fn properties(block: BlockId) -> LightProperties {
+        match block {
+            STONE => LightProperties {
+                emission: 0,
+                opacity: 15,
+            },
+            TORCH => LightProperties {
+                emission: 14,
+                opacity: 0,
+            },
+            _ => LightProperties::default(),
+        }
+    }
+
+    fn light_at(chunk: &Chunk, kind: LightKind, x: i32, y: i32, z: i32) -> u8 {
+        let section = chunk
+            .sections
+            .iter()
+            .find(|section| i32::from(section.y) == y.div_euclid(16))
+            .unwrap();
+        get_nibble(section.light(kind), block_index(x, y, z))
+    }
+
+    /// A chunk with a stone floor at y 63 across the whole chunk.
+    fn floored_chunk() -> Chunk {
+        let mut chunk = Chunk::new(0, 0, "overworld".to_string());
+        for x in 0..16 {
+            for z in 0..16 {
+                chunk.set_block(x, 63, z, STONE).unwrap();
+            }
+        }
+        chunk
+    }
+
+    #[test]
+    fn test_calculate_light() {
+        let mut chunk = floored_chunk();
+        chunk.set_block(8, 64, 8, TORCH).unwrap();
+        chunk.calculate_light_with(properties);
+
+        assert_eq!(light_at(&chunk, LightKind::Sky, 3, 64, 3), 15);
+        assert_eq!(light_at(&chunk, LightKind::Sky, 3, 62, 3), 0);
+        assert_eq!(light_at(&chunk, LightKind::Block, 8, 64, 8), 14);
+        assert_eq!(light_at(&chunk, LightKind::Block, 8, 65, 10), 11);
+        assert_eq!(light_at(&chunk, LightKind::Block, 8, 62, 8), 0);
+        assert!(chunk.has_light());
+    }
+
+    #[test]
+    fn test_update_block() {
+        let mut chunk = floored_chunk();
+        chunk.calculate_light_with(properties);
+        let mut engine = LightEngine::new(Dimension::Overworld);
+
+        // Digging a hole lets the sky in, straight down and then to the sides
+        chunk.set_block(8, 63, 8, BlockId::AIR).unwrap();
+        chunk.set_block(8, 62, 8, BlockId::AIR).unwrap();
+        chunk.set_block(8, 61, 8, BlockId::AIR).unwrap();
+        chunk.set_block(9, 61, 8, BlockId::AIR).unwrap();
+        engine.add_chunk(&chunk, &properties);
+        for y in [63, 62, 61] {
+            engine.update_block(8, y, 8);
+        }
+        engine.update_block(9, 61, 8);
+        assert!(engine.write_chunk(&mut chunk));
+        assert_eq!(light_at(&chunk, LightKind::Sky, 8, 61, 8), 15);
+        assert_eq!(light_at(&chunk, LightKind::Sky, 9, 61, 8), 14);
+
+        // Covering it up darkens it again
+        chunk.set_block(8, 63, 8, STONE).unwrap();
+        engine.add_chunk(&chunk, &properties);
+        engine.update_block(8, 63, 8);
+        engine.write_chunk(&mut chunk);
+        assert_eq!(light_at(&chunk, LightKind::Sky, 8, 61, 8), 0);
+        assert_eq!(light_at(&chunk, LightKind::Sky, 9, 61, 8), 0);
+
+        // A torch lights it up, and taking it away puts it out
+        chunk.set_block(8, 62, 8, TORCH).unwrap();
+        engine.add_chunk(&chunk, &properties);
+        engine.update_block(8, 62, 8);
+        engine.write_chunk(&mut chunk);
+        assert_eq!(light_at(&chunk, LightKind::Block, 9, 61, 8), 12);
+
+        chunk.set_block(8, 62, 8, BlockId::AIR).unwrap();
+        engine.add_chunk(&chunk, &properties);
+        engine.update_block(8, 62, 8);
+        engine.write_chunk(&mut chunk);
+        assert_eq!(light_at(&chunk, LightKind::Block, 8, 62, 8), 0);
+        assert_eq!(light_at(&chunk, LightKind::Block, 9, 61, 8), 0);
+    }
+
+    #[test]
+    fn test_light_crosses_chunks() {
+        let mut engine = LightEngine::new(Dimension::Overworld);
+        let mut left = floored_chunk();
+        let right = Chunk {
+            x: 1,
+            ..floored_chunk()
+        };
+        left.set_block(15, 64, 8, TORCH).unwrap();
+        engine.add_chunk(&left, &properties);
+        engine.add_chunk(&right, &properties);
+        engine.light_chunk(0, 0);
+        engine.light_chunk(1, 0);
+        assert_eq!(engine.light(LightKind::Block, 17, 64, 8), Some(12));
+        assert_eq!(engine.light(LightKind::Block, 40, 64, 8), None);
+    }
+}
@@ -0,0 +1,66 @@
+//! Records about the world as a whole, as opposed to its chunks or players.
+
+use crate::errors::WorldError;
+use crate::level::DO_DAYLIGHT_CYCLE;
+use crate::World;
+
+/// The LMDB table world records are kept in, each under its own key.
+pub(crate) const WORLD_META_TABLE: &str = "meta";
+
+/// The key of the [crate::time::WorldTime] record.
+pub(crate) const TIME_KEY: u128 = 0;
+/// The key of the [crate::spawn::WorldSpawn] record.
+pub(crate) const SPAWN_KEY: u128 = 1;
+/// The key of the [crate::border::WorldBorder] record.
+pub(crate) const BORDER_KEY: u128 = 2;
+/// The key of the [crate::level::LevelInfo] record.
+pub(crate) const LEVEL_KEY: u128 = 3;
+
+impl World {
+    /// Loads every saved record into the world, keeping the defaults for any that aren't saved.
+    pub(crate) async fn load_meta_records(&self) -> Result<(), WorldError> {
+        let time = self.load_time().await?;
+        let spawn = self.load_spawn().await?;
+        let border = self.load_border().await?;
+        let level = self.load_level_info().await?;
+        *self.time.lock() = time;
+        *self.spawn.lock() = spawn;
+        *self.border.lock() = border;
+        if let Some(daylight_cycle) = level.bool_game_rule(DO_DAYLIGHT_CYCLE) {
+            self.set_daylight_cycle(daylight_cycle);
+        }
+        *self.level.lock() = level;
+        Ok(())
+    }
+
+    /// Saves a world record, replacing the one saved before.
+    pub(crate) async fn save_meta<T: bitcode::Encode>(
+        &self,
+        key: u128,
+        record: &T,
+    ) -> Result<(), WorldError> {
+        let as_bytes = self.compressor.compress(&bitcode::encode(record))?;
+        self.storage_backend
+            .upsert(WORLD_META_TABLE.to_string(), key, as_bytes)
+            .await?;
+        Ok(())
+    }
+
+    /// Loads a world record, or `None` if it's never been saved.
+    pub(crate) async fn load_meta<T: bitcode::DecodeOwned>(
+        &self,
+        key: u128,
+    ) -> Result<Option<T>, WorldError> {
+        let Some(compressed) = self
+            .storage_backend
+            .get(WORLD_META_TABLE.to_string(), key)
+            .await?
+        else {
+            return Ok(None);
+        };
+        let data = self.compressor.decompress(&compressed)?;
+        let record =
+            bitcode::decode(&data).map_err(|e| WorldError::BitcodeDecodeError(e.to_string()))?;
+        Ok(Some(record))
+    }
+}
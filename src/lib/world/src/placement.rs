@@ -0,0 +1,230 @@
+//! Works out which state a block is placed in from where the player clicked and where they're
+//! looking, like vanilla does for the common block shapes.
+
+use crate::block_state::{Axis, BlockRegistry, BlockState, Facing};
+
+/// Blocks that face away from the block they're placed against.
+const FACES_CLICKED_SIDE: &[&str] = &["end_rod", "lightning_rod", "amethyst_cluster", "ladder"];
+/// Blocks that face the way the player is looking, instead of facing the player.
+const FACES_AWAY_SUFFIXES: &[&str] = &["_stairs", "_door", "_fence_gate", "_bed"];
+
+/// How a block is being placed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Placement {
+    /// The face of the clicked block that the new block is placed against.
+    pub face: Facing,
+    /// How far up the clicked face the player clicked, from 0 to 1.
+    pub cursor_y: f32,
+    /// Where the player is looking, in degrees.
+    pub yaw: f32,
+    pub pitch: f32,
+}
+
+impl Placement {
+    /// The horizontal direction the player is looking in.
+    pub fn horizontal_direction(&self) -> Facing {
+        match (self.yaw / 90.0 + 0.5).floor().rem_euclid(4.0) as u8 {
+            0 => Facing::South,
+            1 => Facing::West,
+            2 => Facing::North,
+            _ => Facing::East,
+        }
+    }
+
+    /// The direction the player is looking in, counting up and down when they're looking
+    /// steeply enough.
+    pub fn looking_direction(&self) -> Facing {
+        if self.pitch > 45.0 {
+            Facing::Down
+        } else if self.pitch < -45.0 {
+            Facing::Up
+        } else {
+            self.horizontal_direction()
+        }
+    }
+
+    /// Whether a slab, stair or trapdoor goes in the top half of the block: when it's placed
+    /// against the underside of a block, or against the top half of a side.
+    fn top_half(&self) -> bool {
+        match self.face {
+            Facing::Down => true,
+            Facing::Up => false,
+            _ => self.cursor_y > 0.5,
+        }
+    }
+
+    /// Which way a block with a `facing` property faces. `vertical` is whether it can face up
+    /// and down.
+    fn facing(&self, name: &str, vertical: bool) -> Facing {
+        if name == "hopper" {
+            // Hoppers point into the block they're placed against, or down
+            return self.face.opposite();
+        }
+        if FACES_CLICKED_SIDE.contains(&name)
+            || name.ends_with("_amethyst_bud")
+            || name.ends_with("shulker_box")
+        {
+            return self.face;
+        }
+        if name.ends_with("_trapdoor") {
+            return if self.face.is_horizontal() {
+                self.face
+            } else {
+                self.horizontal_direction().opposite()
+            };
+        }
+        if name == "observer" {
+            return self.looking_direction();
+        }
+        if FACES_AWAY_SUFFIXES
+            .iter()
+            .any(|suffix| name.ends_with(suffix))
+        {
+            return self.horizontal_direction();
+        }
+        // Everything else faces the player, e.g. furnaces, chests and pistons
+        if vertical {
+            self.looking_direction().opposite()
+        } else {
+            self.horizontal_direction().opposite()
+        }
+    }
+}
+
+impl Facing {
+    pub fn opposite(self) -> Self {
+        match self {
+            Facing::North => Facing::South,
+            Facing::South => Facing::North,
+            Facing::East => Facing::West,
+            Facing::West => Facing::East,
+            Facing::Up => Facing::Down,
+            Facing::Down => Facing::Up,
+        }
+    }
+
+    pub fn is_horizontal(self) -> bool {
+        !matches!(self, Facing::Up | Facing::Down)
+    }
+
+    pub fn axis(self) -> Axis {
+        match self {
+            Facing::East | Facing::West => Axis::X,
+            Facing::Up | Facing::Down => Axis::Y,
+            Facing::North | Facing::South => Axis::Z,
+        }
+    }
+}
+
+impl BlockRegistry {
+    /// The state `block` is placed in. Its `axis`, `facing`, `half` and slab `type` are set from
+    /// how it's placed; anything else stays as it is in `block`. Values a block can't have, e.g.
+    /// a hopper facing up, are left as they were.
+    pub fn placement_state<'a>(
+        &'a self,
+        block: &'a BlockState,
+        placement: &Placement,
+    ) -> &'a BlockState {
+        let name = block.name.strip_prefix("minecraft:").unwrap_or(&block.name);
+        let top = placement.top_half();
+        let vertical = self.with_property(block, "facing", "up").is_some();
+        let mut changes = vec![
+            ("axis", placement.face.axis().as_str()),
+            ("facing", placement.facing(name, vertical).as_str()),
+        ];
+        if matches!(block.property("half"), Some("top" | "bottom")) {
+            changes.push(("half", if top { "top" } else { "bottom" }));
+        }
+        if matches!(block.property("type"), Some("top" | "bottom")) {
+            changes.push(("type", if top { "top" } else { "bottom" }));
+        }
+        changes.into_iter().fold(block, |state, (property, value)| {
+            self.with_property(state, property, value).unwrap_or(state)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block_state::Half;
+
+    fn place(name: &str, face: Facing, cursor_y: f32, yaw: f32, pitch: f32) -> &'static BlockState {
+        let registry = BlockRegistry::global();
+        let placement = Placement {
+            face,
+            cursor_y,
+            yaw,
+            pitch,
+        };
+        registry.placement_state(registry.default_state(name).unwrap(), &placement)
+    }
+
+    #[test]
+    fn test_looking_direction() {
+        let placement = |yaw, pitch| Placement {
+            face: Facing::Up,
+            cursor_y: 1.0,
+            yaw,
+            pitch,
+        };
+        assert_eq!(placement(0.0, 0.0).horizontal_direction(), Facing::South);
+        assert_eq!(placement(100.0, 0.0).horizontal_direction(), Facing::West);
+        assert_eq!(placement(-170.0, 0.0).horizontal_direction(), Facing::North);
+        assert_eq!(placement(-90.0, 0.0).horizontal_direction(), Facing::East);
+        assert_eq!(placement(0.0, 80.0).looking_direction(), Facing::Down);
+        assert_eq!(placement(0.0, -80.0).looking_direction(), Facing::Up);
+    }
+
+    #[test]
+    fn test_axis_and_halves() {
+        assert_eq!(
+            place("oak_log", Facing::East, 0.5, 0.0, 0.0).axis(),
+            Some(Axis::X)
+        );
+        assert_eq!(
+            place("oak_log", Facing::Up, 1.0, 0.0, 0.0).axis(),
+            Some(Axis::Y)
+        );
+
+        let slab = place("oak_slab", Facing::North, 0.7, 0.0, 0.0);
+        assert_eq!(slab.property("type"), Some("top"));
+        assert_eq!(slab.waterlogged(), Some(false));
+        let slab = place("oak_slab", Facing::Up, 1.0, 0.0, 0.0);
+        assert_eq!(slab.property("type"), Some("bottom"));
+
+        let stairs = place("oak_stairs", Facing::Down, 0.0, 0.0, 0.0);
+        assert_eq!(stairs.half(), Some(Half::Top));
+        assert_eq!(stairs.facing(), Some(Facing::South));
+        assert_eq!(stairs.waterlogged(), Some(false));
+    }
+
+    #[test]
+    fn test_facing() {
+        // Looking south, so it faces north towards the player
+        let furnace = place("furnace", Facing::Up, 1.0, 0.0, 0.0);
+        assert_eq!(furnace.facing(), Some(Facing::North));
+        // Furnaces can't face down, so looking up at one only turns it sideways
+        let furnace = place("furnace", Facing::Up, 1.0, 90.0, -80.0);
+        assert_eq!(furnace.facing(), Some(Facing::East));
+        assert_eq!(
+            place("piston", Facing::Up, 1.0, 0.0, 80.0).facing(),
+            Some(Facing::Up)
+        );
+        assert_eq!(
+            place("end_rod", Facing::West, 0.5, 0.0, 0.0).facing(),
+            Some(Facing::West)
+        );
+        assert_eq!(
+            place("hopper", Facing::East, 0.5, 0.0, 0.0).facing(),
+            Some(Facing::West)
+        );
+        assert_eq!(
+            place("hopper", Facing::Up, 1.0, 0.0, 0.0).facing(),
+            Some(Facing::Down)
+        );
+        let trapdoor = place("oak_trapdoor", Facing::Up, 1.0, 90.0, 0.0);
+        assert_eq!(trapdoor.facing(), Some(Facing::East));
+        assert_eq!(trapdoor.half(), Some(Half::Bottom));
+    }
+}
@@ -0,0 +1,250 @@
+use crate::errors::WorldError;
+use crate::importing::read_nbt_file;
+use crate::World;
+use bitcode_derive::{Decode, Encode};
+use ferrumc_core::dimensions::dimension::Dimension;
+use ferrumc_macros::{NBTDeserialize, NBTSerialize};
+use ferrumc_net_codec::net_types::slot::ItemStack;
+use std::path::Path;
+use tracing::{error, info, warn};
+
+/// The LMDB table player data is kept in, keyed by the player's UUID.
+pub(crate) const PLAYER_DATA_TABLE: &str = "playerdata";
+
+/// What's saved about a player between sessions.
+#[derive(Encode, Decode, Clone, Debug, PartialEq)]
+pub struct PlayerData {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+    pub yaw: f32,
+    pub pitch: f32,
+    pub on_ground: bool,
+    /// The stacks in the player's inventory, by slot.
+    pub inventory: Vec<(u8, ItemStack)>,
+    /// The selected hotbar slot.
+    pub selected_slot: u8,
+    /// The ID of the player's game mode.
+    pub game_mode: u8,
+    /// The name of the dimension the player is in.
+    pub dimension: String,
+}
+
+/// The parts of a vanilla `playerdata/<uuid>.dat` file that are imported.
+#[derive(NBTDeserialize, NBTSerialize, Debug)]
+#[nbt(is_root)]
+#[nbt(rename = "")]
+struct VanillaPlayerData {
+    #[nbt(rename = "Pos")]
+    pos: Vec<f64>,
+    #[nbt(rename = "Rotation")]
+    rotation: Vec<f32>,
+    #[nbt(rename = "OnGround")]
+    on_ground: Option<bool>,
+    #[nbt(rename = "SelectedItemSlot")]
+    selected_item_slot: Option<i32>,
+    #[nbt(rename = "playerGameType")]
+    player_game_type: Option<i32>,
+    #[nbt(rename = "Dimension")]
+    dimension: Option<String>,
+}
+
+impl PlayerData {
+    /// Reads a vanilla `playerdata/<uuid>.dat` file, which is usually gzipped.
+    ///
+    /// The inventory isn't imported, since vanilla saves items by name and there's no item
+    /// registry to look their IDs up in.
+    pub fn from_vanilla(bytes: &[u8]) -> Result<Self, WorldError> {
+        let bytes = read_nbt_file(bytes)?;
+        let vanilla = VanillaPlayerData::from_bytes(&bytes)
+            .map_err(|e| WorldError::InvalidPlayerData(e.to_string()))?;
+
+        let [x, y, z] = vanilla.pos[..] else {
+            return Err(WorldError::InvalidPlayerData(
+                "Pos doesn't have 3 coordinates".to_string(),
+            ));
+        };
+        let [yaw, pitch] = vanilla.rotation[..] else {
+            return Err(WorldError::InvalidPlayerData(
+                "Rotation doesn't have 2 angles".to_string(),
+            ));
+        };
+        Ok(Self {
+            x,
+            y,
+            z,
+            yaw,
+            pitch,
+            on_ground: vanilla.on_ground.unwrap_or_default(),
+            inventory: Vec::new(),
+            selected_slot: vanilla.selected_item_slot.unwrap_or_default().clamp(0, 8) as u8,
+            game_mode: vanilla.player_game_type.unwrap_or_default().clamp(0, 3) as u8,
+            dimension: vanilla
+                .dimension
+                .and_then(|name| Dimension::from_name(&name))
+                .unwrap_or_default()
+                .name()
+                .to_string(),
+        })
+    }
+}
+
+/// Parses a UUID in its usual hyphenated form, as used for player data file names.
+fn parse_uuid(value: &str) -> Option<u128> {
+    let hex = value.replace('-', "");
+    if hex.len() != 32 {
+        return None;
+    }
+    u128::from_str_radix(&hex, 16).ok()
+}
+
+impl World {
+    /// Saves a player's data, replacing anything saved for them before.
+    pub async fn save_player_data(&self, uuid: u128, data: &PlayerData) -> Result<(), WorldError> {
+        let as_bytes = self.compressor.compress(&bitcode::encode(data))?;
+        self.storage_backend
+            .upsert(PLAYER_DATA_TABLE.to_string(), uuid, as_bytes)
+            .await?;
+        Ok(())
+    }
+
+    /// Loads a player's data, or `None` if they've never played here before.
+    ///
+    /// Data that can't be read, e.g. because it was saved by a version of the server that saved
+    /// different things, is logged and treated as if there was none, so the player spawns fresh
+    /// instead of being unable to join. It's replaced the next time they're saved.
+    pub async fn load_player_data(&self, uuid: u128) -> Result<Option<PlayerData>, WorldError> {
+        let Some(compressed) = self
+            .storage_backend
+            .get(PLAYER_DATA_TABLE.to_string(), uuid)
+            .await?
+        else {
+            return Ok(None);
+        };
+        match self.decode_player_data(&compressed) {
+            Ok(player_data) => Ok(Some(player_data)),
+            Err(e) => {
+                warn!(
+                    "Could not read the saved data of player {:032x}: {}",
+                    uuid, e
+                );
+                Ok(None)
+            }
+        }
+    }
+
+    fn decode_player_data(&self, compressed: &[u8]) -> Result<PlayerData, WorldError> {
+        let data = self.compressor.decompress(compressed)?;
+        bitcode::decode(&data).map_err(|e| WorldError::BitcodeDecodeError(e.to_string()))
+    }
+
+    /// Imports every player's data from a vanilla world's `playerdata` folder, if it has one.
+    /// Files that can't be read are skipped.
+    pub(crate) async fn import_player_data(&self, import_dir: &Path) -> Result<(), WorldError> {
+        let player_data_dir = import_dir.join("playerdata");
+        if !player_data_dir.is_dir() {
+            info!("No player data to import");
+            return Ok(());
+        }
+
+        let mut imported = 0;
+        for entry in player_data_dir.read_dir()? {
+            let path = entry?.path();
+            if path.extension().is_none_or(|extension| extension != "dat") {
+                continue;
+            }
+            let Some(uuid) = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(parse_uuid)
+            else {
+                error!("Player data file isn't named by UUID: {}", path.display());
+                continue;
+            };
+            match PlayerData::from_vanilla(&std::fs::read(&path)?) {
+                Ok(player_data) => {
+                    self.save_player_data(uuid, &player_data).await?;
+                    imported += 1;
+                }
+                Err(e) => error!("Could not import player data {}: {}", path.display(), e),
+            }
+        }
+        info!("Imported data for {} players", imported);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ferrumc_nbt::{NBTSerializable, NBTSerializeOptions};
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+
+    fn vanilla_bytes(pos: Vec<f64>) -> Vec<u8> {
+        let vanilla = VanillaPlayerData {
+            pos,
+            rotation: vec![90.0, -15.0],
+            on_ground: Some(true),
+            selected_item_slot: Some(4),
+            player_game_type: Some(1),
+            dimension: Some("minecraft:the_nether".to_string()),
+        };
+        let mut bytes = Vec::new();
+        vanilla.serialize(&mut bytes, &NBTSerializeOptions::WithHeader(""));
+        bytes
+    }
+
+    #[test]
+    fn test_from_vanilla() {
+        let bytes = vanilla_bytes(vec![10.5, 64.0, -3.25]);
+        let expected = PlayerData {
+            x: 10.5,
+            y: 64.0,
+            z: -3.25,
+            yaw: 90.0,
+            pitch: -15.0,
+            on_ground: true,
+            inventory: Vec::new(),
+            selected_slot: 4,
+            game_mode: 1,
+            dimension: "the_nether".to_string(),
+        };
+        assert_eq!(PlayerData::from_vanilla(&bytes).unwrap(), expected);
+
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&bytes).unwrap();
+        let gzipped = encoder.finish().unwrap();
+        assert_eq!(PlayerData::from_vanilla(&gzipped).unwrap(), expected);
+
+        assert!(PlayerData::from_vanilla(&vanilla_bytes(vec![1.0, 2.0])).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_load_player_data() {
+        let world = World::in_memory().await;
+        assert_eq!(world.load_player_data(1).await.unwrap(), None);
+
+        let data = PlayerData::from_vanilla(&vanilla_bytes(vec![1.0, 2.0, 3.0])).unwrap();
+        world.save_player_data(1, &data).await.unwrap();
+        assert_eq!(world.load_player_data(1).await.unwrap(), Some(data));
+
+        // Data that can't be decoded is treated as missing
+        world
+            .storage_backend
+            .upsert(PLAYER_DATA_TABLE.to_string(), 1, vec![1, 2, 3])
+            .await
+            .unwrap();
+        assert_eq!(world.load_player_data(1).await.unwrap(), None);
+    }
+
+    #[test]
+    fn test_parse_uuid() {
+        assert_eq!(
+            parse_uuid("069a79f4-44e9-4726-a5be-fca90e38aaf5"),
+            Some(0x069a79f444e94726a5befca90e38aaf5)
+        );
+        assert_eq!(parse_uuid("not-a-uuid"), None);
+    }
+}
@@ -0,0 +1,168 @@
+//! The world's spawn point, and finding somewhere safe to put players near it.
+
+use crate::block_id::BlockId;
+use crate::chunk_format::Chunk;
+use crate::errors::WorldError;
+use crate::meta::SPAWN_KEY;
+use crate::World;
+use bitcode_derive::{Decode, Encode};
+use ferrumc_config::statics::get_global_config;
+
+/// How far from the spawn point a safe spot is looked for, in blocks.
+const SAFE_SPOT_RADIUS: i32 = 8;
+
+/// Where players spawn.
+#[derive(Encode, Decode, Clone, Copy, Debug, PartialEq)]
+pub struct WorldSpawn {
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+    /// The yaw players face when they spawn.
+    pub angle: f32,
+}
+
+impl WorldSpawn {
+    /// The spawn point from the config.
+    pub fn from_config() -> Self {
+        let config = &get_global_config().spawn;
+        Self {
+            x: config.x,
+            y: config.y,
+            z: config.z,
+            angle: config.angle,
+        }
+    }
+}
+
+/// Whether a player could stand on the block. Blocks that aren't in the registry count as
+/// solid unless they're air.
+fn can_stand_on(block: BlockId) -> bool {
+    match block.state() {
+        Some(state) => state.is_solid(),
+        None => !block.is_air(),
+    }
+}
+
+/// Whether a player could be inside the block without suffocating or drowning.
+fn can_stand_in(block: BlockId) -> bool {
+    match block.state() {
+        Some(state) => !state.is_solid() && !state.is_liquid(),
+        None => block.is_air(),
+    }
+}
+
+impl Chunk {
+    /// The highest y a player could stand at in a column, with a block to stand on and room for
+    /// their head. `x` and `z` are relative to the chunk. Spots above the dimension's logical
+    /// height, like the nether's roof, don't count.
+    pub fn safe_spawn_y(&self, x: i32, z: i32) -> Option<i32> {
+        // The player's head has to fit below the logical height
+        let ceiling = self.min_y() + self.dimension_kind().logical_height() - 3;
+        let top = self.highest_block_y(x, z)?.min(ceiling);
+        let block = |y| self.get_block(x, y, z).unwrap_or_default();
+        highest_safe_y(
+            self.min_y(),
+            top,
+            |y| can_stand_on(block(y)),
+            |y| can_stand_in(block(y)),
+        )
+    }
+}
+
+/// The highest y from `top + 1` down to `bottom + 1` with a block to stand on below it and room
+/// for a player's head.
+fn highest_safe_y(
+    bottom: i32,
+    top: i32,
+    can_stand_on: impl Fn(i32) -> bool,
+    can_stand_in: impl Fn(i32) -> bool,
+) -> Option<i32> {
+    ((bottom + 1)..=(top + 1))
+        .rev()
+        .find(|&y| can_stand_on(y - 1) && can_stand_in(y) && can_stand_in(y + 1))
+}
+
+impl World {
+    /// The world's spawn point.
+    pub fn spawn(&self) -> WorldSpawn {
+        *self.spawn.lock()
+    }
+
+    /// Moves the world's spawn point, and saves it so it's used over the config from now on.
+    pub async fn set_spawn(&self, spawn: WorldSpawn) -> Result<(), WorldError> {
+        *self.spawn.lock() = spawn;
+        self.save_meta(SPAWN_KEY, &spawn).await
+    }
+
+    /// Where a player spawning in the dimension should be put: on the safe spot closest to the
+    /// spawn point, in the middle of the block. If there isn't one, e.g. because the chunks
+    /// haven't been generated, the spawn point itself is used.
+    pub async fn safe_spawn_position(&self, dimension: &str) -> (f64, f64, f64) {
+        let spawn = self.spawn();
+        for radius in 0..=SAFE_SPOT_RADIUS {
+            for (x, z) in ring(spawn.x, spawn.z, radius) {
+                let Ok(chunk) = self
+                    .load_chunk(x.div_euclid(16), z.div_euclid(16), dimension)
+                    .await
+                else {
+                    continue;
+                };
+                if let Some(y) = chunk.safe_spawn_y(x.rem_euclid(16), z.rem_euclid(16)) {
+                    return (x as f64 + 0.5, y as f64, z as f64 + 0.5);
+                }
+            }
+        }
+        (spawn.x as f64 + 0.5, spawn.y as f64, spawn.z as f64 + 0.5)
+    }
+
+    /// Loads the saved spawn point, or the one from the config if there isn't one.
+    pub(crate) async fn load_spawn(&self) -> Result<WorldSpawn, WorldError> {
+        Ok(self
+            .load_meta(SPAWN_KEY)
+            .await?
+            .unwrap_or_else(WorldSpawn::from_config))
+    }
+}
+
+/// The columns `radius` blocks away from a center column, going around a square.
+fn ring(center_x: i32, center_z: i32, radius: i32) -> Vec<(i32, i32)> {
+    if radius == 0 {
+        return vec![(center_x, center_z)];
+    }
+    let mut columns = Vec::new();
+    for offset in -radius..radius {
+        columns.push((center_x + offset, center_z - radius));
+        columns.push((center_x + radius, center_z + offset));
+        columns.push((center_x - offset, center_z + radius));
+        columns.push((center_x - radius, center_z - offset));
+    }
+    columns
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_highest_safe_y() {
+        let solid = |y| y == 60 || y == 62;
+        // No room for a head under the overhang, so the top of it is used
+        assert_eq!(highest_safe_y(-64, 62, solid, |y| !solid(y)), Some(63));
+        // Unless there's water on top of it
+        assert_eq!(
+            highest_safe_y(-64, 62, solid, |y| !solid(y) && y != 64),
+            None
+        );
+        assert_eq!(highest_safe_y(-64, 60, |y| y == 60, |y| y != 60), Some(61));
+    }
+
+    #[test]
+    fn test_ring() {
+        assert_eq!(ring(5, 5, 0), vec![(5, 5)]);
+        let columns = ring(0, 0, 2);
+        assert_eq!(columns.len(), 16);
+        assert_eq!(columns.iter().collect::<HashSet<_>>().len(), 16);
+        assert!(columns.iter().all(|&(x, z)| x.abs().max(z.abs()) == 2));
+    }
+}
@@ -0,0 +1,118 @@
+//! The world's clock: how long it's existed, and the time of day.
+
+use crate::errors::WorldError;
+use crate::meta::TIME_KEY;
+use crate::World;
+use bitcode_derive::{Decode, Encode};
+use std::sync::atomic::Ordering;
+
+/// How many ticks a day lasts.
+pub const DAY_LENGTH: i64 = 24000;
+
+/// The world's age and time of day, in ticks.
+#[derive(Encode, Decode, Clone, Copy, Debug, Default, PartialEq)]
+pub struct WorldTime {
+    /// How many ticks the world has run for. This only ever goes up by one each tick.
+    pub world_age: i64,
+    /// The time of day, counting up across days like vanilla's `DayTime`. 0 is sunrise, 6000
+    /// noon, 12000 sunset and 18000 midnight.
+    pub time_of_day: i64,
+}
+
+impl WorldTime {
+    /// Moves the clock on by a tick. The time of day only moves with the daylight cycle.
+    pub fn tick(&mut self, daylight_cycle: bool) {
+        self.world_age += 1;
+        if daylight_cycle {
+            self.time_of_day += 1;
+        }
+    }
+
+    /// The time of day within the current day, from 0 to 23999.
+    pub fn day_time(&self) -> i64 {
+        self.time_of_day.rem_euclid(DAY_LENGTH)
+    }
+
+    /// How many days have passed.
+    pub fn day(&self) -> i64 {
+        self.time_of_day.div_euclid(DAY_LENGTH)
+    }
+}
+
+impl World {
+    /// The current time.
+    pub fn time(&self) -> WorldTime {
+        *self.time.lock()
+    }
+
+    /// Moves the clock on by a tick, returning the new time.
+    pub fn tick_time(&self) -> WorldTime {
+        let mut time = self.time.lock();
+        time.tick(self.daylight_cycle());
+        *time
+    }
+
+    /// Sets the time of day. The world's age isn't affected.
+    pub fn set_time_of_day(&self, time_of_day: i64) -> WorldTime {
+        let mut time = self.time.lock();
+        time.time_of_day = time_of_day;
+        *time
+    }
+
+    /// Moves the time of day on, or back if `ticks` is negative.
+    pub fn add_time(&self, ticks: i64) -> WorldTime {
+        let mut time = self.time.lock();
+        time.time_of_day += ticks;
+        *time
+    }
+
+    /// Whether the time of day moves on its own, like vanilla's `doDaylightCycle`.
+    pub fn daylight_cycle(&self) -> bool {
+        self.daylight_cycle.load(Ordering::Relaxed)
+    }
+
+    pub fn set_daylight_cycle(&self, enabled: bool) {
+        self.daylight_cycle.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Saves the time, so it carries on from there after a restart.
+    pub async fn save_time(&self) -> Result<(), WorldError> {
+        self.save_meta(TIME_KEY, &self.time()).await
+    }
+
+    /// Loads the saved time, or starts a new clock if there isn't one.
+    pub(crate) async fn load_time(&self) -> Result<WorldTime, WorldError> {
+        Ok(self.load_meta(TIME_KEY).await?.unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tick() {
+        let mut time = WorldTime {
+            world_age: 100,
+            time_of_day: DAY_LENGTH * 2 + 5999,
+        };
+        time.tick(true);
+        assert_eq!(time.world_age, 101);
+        assert_eq!(time.day_time(), 6000);
+        assert_eq!(time.day(), 2);
+
+        time.tick(false);
+        assert_eq!(time.world_age, 102);
+        assert_eq!(time.day_time(), 6000);
+    }
+
+    #[test]
+    fn test_negative_time_of_day() {
+        let time = WorldTime {
+            world_age: 0,
+            time_of_day: -1000,
+        };
+        assert_eq!(time.day_time(), 23000);
+        assert_eq!(time.day(), -1);
+    }
+}
@@ -24,7 +24,6 @@ attribute_alias! {
 #[nbt(is_root)]
 #[nbt(rename = "")]
 pub(crate) struct VanillaChunk {
-    pub dimension: Option<String>,
     #[nbt(rename = "Status")]
     pub status: String,
     #[nbt(rename = "DataVersion")]